@@ -0,0 +1,49 @@
+//! Benchmarks of instruction throughput of the interpreter, with and without the decoded
+//! instruction cache, and of the block engine
+
+use std::hint::black_box;
+
+use chip_8::{blocks::Engine, machine::Machine, quirks::Quirks, rom::Rom};
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+
+/// The number of instructions executed per iteration
+const CYCLES: u32 = 10_000;
+
+/// Arithmetic in a tight loop: add to V0 and V1, or and add them, jump back
+const ALU_LOOP: &[u8] = &[0x70, 0x01, 0x71, 0x02, 0x80, 0x11, 0x81, 0x04, 0x12, 0x00];
+
+/// Drawing a font sprite across the display: draw, move right, jump back
+const DRAW_LOOP: &[u8] = &[0xA0, 0x00, 0xD0, 0x15, 0x70, 0x01, 0x12, 0x02];
+
+/// Creates a machine running the program with the engine, with or without the instruction cache
+fn machine(program: &[u8], engine: Engine, cached: bool) -> Machine {
+    let mut machine = Machine::new(Quirks::default());
+    machine.set_engine(engine);
+    machine.memory_mut().set_cache_enabled(cached);
+    machine
+        .load_rom(&Rom::from_bytes(program).unwrap())
+        .unwrap();
+    machine
+}
+
+/// Benchmarks every program with every engine
+fn execution(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("execution");
+    group.throughput(Throughput::Elements(CYCLES.into()));
+    for (name, program) in [("alu", ALU_LOOP), ("draw", DRAW_LOOP)] {
+        for (variant, engine, cached) in [
+            ("uncached", Engine::Interpreter, false),
+            ("cached", Engine::Interpreter, true),
+            ("blocks", Engine::Blocks, false),
+        ] {
+            let mut machine = machine(program, engine, cached);
+            group.bench_function(format!("{name}/{variant}"), |bencher| {
+                bencher.iter(|| black_box(machine.run_frame(CYCLES)).unwrap());
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, execution);
+criterion_main!(benches);
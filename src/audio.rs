@@ -0,0 +1,193 @@
+//! This module contains the synthesis of the beeper as PCM samples, their playback on the audio
+//! device and their export as WAV.
+//!
+//! Chip-8 has a single tone that sounds while the sound timer is running. It is synthesized as a
+//! square wave one emulated frame at a time, keeping the phase between frames so the tone doesn't
+//! click when it continues.
+
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use cpal::{
+    FromSample, SampleFormat, SizedSample, Stream, StreamConfig,
+    traits::{DeviceTrait, HostTrait, StreamTrait},
+};
+
+/// The sample rate used by default, in samples per second
+pub const DEFAULT_SAMPLE_RATE: u32 = 44_100;
+
+/// The frequency of the tone, in Hz
+pub const FREQUENCY: u32 = 440;
+
+/// The amplitude of the square wave
+const AMPLITUDE: i16 = 8_000;
+
+/// The number of emulated frames per second
+const FRAMES_PER_SECOND: u64 = 60;
+
+/// The number of frames of samples queued for the audio device at most. Older samples are dropped
+/// if frames are played faster than the device consumes them, so the sound doesn't lag behind.
+const MAX_QUEUED_FRAMES: usize = 4;
+
+/// Synthesizes the tone of the beeper
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Beeper {
+    /// The number of samples per second
+    sample_rate: u32,
+
+    /// The number of frames rendered so far
+    frames: u64,
+
+    /// The number of samples rendered so far, modulo the sample rate
+    phase: u32,
+}
+
+impl Default for Beeper {
+    fn default() -> Self {
+        Self::new(DEFAULT_SAMPLE_RATE).expect("The default sample rate isn't 0")
+    }
+}
+
+impl Beeper {
+    /// Creates a beeper rendering samples at the sample rate. Returns None if the sample rate is
+    /// 0, which can't represent any sound.
+    pub const fn new(sample_rate: u32) -> Option<Self> {
+        if sample_rate == 0 {
+            return None;
+        }
+        Some(Self {
+            sample_rate,
+            frames: 0,
+            phase: 0,
+        })
+    }
+
+    /// Retrieves the number of samples per second
+    pub const fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Appends the samples of the next frame, the tone if the sound timer is running and silence
+    /// otherwise. Frames alternate in length if the sample rate isn't a multiple of 60.
+    pub fn render_frame(&mut self, sound_timer: u8, samples: &mut Vec<i16>) {
+        let rate = u64::from(self.sample_rate);
+        let count =
+            (self.frames + 1) * rate / FRAMES_PER_SECOND - self.frames * rate / FRAMES_PER_SECOND;
+        self.frames += 1;
+        for _ in 0..count {
+            let half_period = u64::from(self.phase) * 2 * u64::from(FREQUENCY) / rate;
+            samples.push(match (sound_timer, half_period % 2) {
+                (0, _) => 0,
+                (_, 0) => AMPLITUDE,
+                _ => -AMPLITUDE,
+            });
+            self.phase = (self.phase + 1) % self.sample_rate;
+        }
+    }
+}
+
+/// Plays the beeper on the default audio output device
+pub struct Speaker {
+    /// Synthesizes the samples at the sample rate of the device
+    beeper: Beeper,
+
+    /// The samples of the current frame
+    samples: Vec<i16>,
+
+    /// The samples waiting to be played, shared with the audio thread of the device
+    queue: Arc<Mutex<VecDeque<i16>>>,
+
+    /// The stream playing the queued samples, playback stops when it's dropped
+    _stream: Stream,
+}
+
+impl Speaker {
+    /// Starts playing silence on the default audio output device
+    pub fn new() -> io::Result<Self> {
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No audio output device"))?;
+        let supported = device.default_output_config().map_err(io::Error::other)?;
+        let config = supported.config();
+        let beeper = Beeper::new(config.sample_rate)
+            .ok_or_else(|| io::Error::other("The audio device has a sample rate of 0"))?;
+        let queue = Arc::new(Mutex::new(VecDeque::new()));
+        let stream = match supported.sample_format() {
+            SampleFormat::I16 => output_stream::<i16>(&device, &config, Arc::clone(&queue)),
+            SampleFormat::U16 => output_stream::<u16>(&device, &config, Arc::clone(&queue)),
+            SampleFormat::F32 => output_stream::<f32>(&device, &config, Arc::clone(&queue)),
+            format => Err(io::Error::other(format!(
+                "Unsupported audio sample format {format}"
+            ))),
+        }?;
+        stream.play().map_err(io::Error::other)?;
+        Ok(Self {
+            beeper,
+            samples: Vec::new(),
+            queue,
+            _stream: stream,
+        })
+    }
+
+    /// Queues the samples of the next frame, the tone if the sound timer is running and silence
+    /// otherwise
+    pub fn play_frame(&mut self, sound_timer: u8) {
+        self.samples.clear();
+        self.beeper.render_frame(sound_timer, &mut self.samples);
+        let mut queue = self.queue.lock().unwrap_or_else(PoisonError::into_inner);
+        queue.extend(&self.samples);
+        let excess = queue
+            .len()
+            .saturating_sub(self.samples.len() * MAX_QUEUED_FRAMES);
+        queue.drain(..excess);
+    }
+}
+
+/// Builds a stream playing the queued samples on every channel of the device, and silence while
+/// the queue is empty
+fn output_stream<T: SizedSample + FromSample<i16>>(
+    device: &cpal::Device,
+    config: &StreamConfig,
+    queue: Arc<Mutex<VecDeque<i16>>>,
+) -> io::Result<Stream> {
+    let channels = usize::from(config.channels);
+    device
+        .build_output_stream(
+            config,
+            move |data: &mut [T], _| {
+                let mut queue = queue.lock().unwrap_or_else(PoisonError::into_inner);
+                for frame in data.chunks_mut(channels) {
+                    frame.fill(T::from_sample(queue.pop_front().unwrap_or(0)));
+                }
+            },
+            |error| eprintln!("Audio playback failed: {error}"),
+            None,
+        )
+        .map_err(io::Error::other)
+}
+
+/// Writes the samples as a mono 16-bit PCM WAV file
+pub fn write_wav(samples: &[i16], sample_rate: u32, mut writer: impl Write) -> io::Result<()> {
+    let size = u32::try_from(samples.len() * 2).map_err(io::Error::other)?;
+    writer.write_all(b"RIFF")?;
+    writer.write_all(&(36 + size).to_le_bytes())?;
+    writer.write_all(b"WAVEfmt ")?;
+    writer.write_all(&16u32.to_le_bytes())?;
+    // PCM, one channel
+    writer.write_all(&1u16.to_le_bytes())?;
+    writer.write_all(&1u16.to_le_bytes())?;
+    writer.write_all(&sample_rate.to_le_bytes())?;
+    writer.write_all(&(sample_rate * 2).to_le_bytes())?;
+    // Two bytes per frame, 16 bits per sample
+    writer.write_all(&2u16.to_le_bytes())?;
+    writer.write_all(&16u16.to_le_bytes())?;
+    writer.write_all(b"data")?;
+    writer.write_all(&size.to_le_bytes())?;
+    for sample in samples {
+        writer.write_all(&sample.to_le_bytes())?;
+    }
+    writer.flush()
+}
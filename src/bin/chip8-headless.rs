@@ -0,0 +1,444 @@
+//! Runs an application without a window and dumps the final state of the machine.
+//!
+//! Usage: `chip8-headless <rom|cartridge.gif> [--frames N | --cycles N] [--keys FILE] [--movie FILE]
+//! [--seed N] [--vip-rng FILE] [--quirks vip|schip|xo-chip] [--layout vip|eti-660|xo-chip] [--font NAME|FILE] [--database FILE|none] [--stack-depth N|unlimited] [--engine interpreter|blocks] [--benchmark] [--dump-memory] [--coverage FILE] [--export-cart FILE] [--screenshot FILE] [--video FILE] [--audio FILE]`
+//!
+//! Benchmarks run the application as fast as possible, for 100 million cycles or the length of the
+//! movie unless a limit is given, then report the instructions executed and frames per second.
+//!
+//! The random instruction uses a fast generator by default. `--vip-rng` emulates the COSMAC VIP
+//! routine instead, reading its bytes from a dump of the 512 byte interpreter or of its page at
+//! 0x100 - 0x1FF, and uses the lowest 16 bits of the seed. It can't be combined with a movie, as
+//! movies replace the generator by the one they were recorded with. Movies also run the cycles per
+//! frame they were recorded with, so they can only be limited by `--frames`.
+//!
+//! Screenshots are saved unscaled as PNG, PBM or PPM and videos as GIF or Y4M, depending on the
+//! extension. Audio is saved as WAV.
+//!
+//! The key script contains one change per line: the frame it applies from and the hexadecimal
+//! keys held from then on, or `-` to release all keys. Lines starting with `#` are ignored.
+
+use std::{env, fs, io, iter::Peekable, path::Path, process, sync::Arc, time::Instant, vec};
+
+use chip_8::{
+    audio::{self, Beeper},
+    blocks::Engine,
+    cartridge::{Cartridge, CartridgeOptions},
+    coverage,
+    database::Database,
+    display::{HEIGHT, WIDTH},
+    font::Font,
+    frontend::{Frontend, Input, Runner},
+    keypad::Keypad,
+    machine::{DEFAULT_CYCLES_PER_FRAME, Machine},
+    memory::MemoryLayout,
+    movie::Movie,
+    quirks::Quirks,
+    recording::Recorder,
+    rng::CosmacVip,
+    rom::Rom,
+    screenshot::{self, Palette},
+};
+
+/// The number of frames run if no limit was given, 10 seconds
+const DEFAULT_FRAMES: u64 = 600;
+
+/// The number of cycles benchmarks run if no limit was given
+const BENCHMARK_CYCLES: u64 = 100_000_000;
+
+/// When to stop running the application
+enum Limit {
+    /// Stop after the number of frames
+    Frames(u64),
+
+    /// Stop after the number of instructions
+    Cycles(u64),
+}
+
+/// Feeds the key script to the machine and records the video and audio of every frame
+struct Headless {
+    /// The remaining changes of the key script
+    keys: Peekable<vec::IntoIter<(u64, Keypad)>>,
+
+    /// The keys currently held by the key script
+    keypad: Keypad,
+
+    /// The number of frames polled so far
+    frame: u64,
+
+    /// The video being recorded
+    video: Option<Recorder>,
+
+    /// The path of the audio recording, the beeper synthesizing it and the samples so far
+    audio: Option<(String, Beeper, Vec<i16>)>,
+}
+
+impl Frontend for Headless {
+    fn poll_input(&mut self) -> Input {
+        while let Some((_, keypad)) = self.keys.next_if(|&(start, _)| start <= self.frame) {
+            self.keypad = keypad;
+        }
+        self.frame += 1;
+        Input {
+            keypad: self.keypad,
+            ..Input::default()
+        }
+    }
+
+    fn present(&mut self, machine: &Machine) -> io::Result<()> {
+        match &mut self.video {
+            Some(video) => video.record_frame(machine.display()),
+            None => Ok(()),
+        }
+    }
+
+    fn play_audio(&mut self, sound_timer: u8) {
+        if let Some((_, beeper, samples)) = &mut self.audio {
+            beeper.render_frame(sound_timer, samples);
+        }
+    }
+}
+
+/// Parses a key script into a list of frames and the keys held from that frame on
+fn parse_keys(script: &str) -> Result<Vec<(u64, Keypad)>, String> {
+    let mut changes = script
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (frame, keys) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| format!("Invalid key script line: {line}"))?;
+            let frame = frame
+                .parse()
+                .map_err(|_| format!("Invalid frame: {frame}"))?;
+            let mut keypad = Keypad::new();
+            for key in keys.trim().chars().filter(|&key| key != '-') {
+                let key = key
+                    .to_digit(16)
+                    .ok_or_else(|| format!("Invalid key: {key}"))?;
+                keypad.set(key as u8, true);
+            }
+            Ok((frame, keypad))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    changes.sort_by_key(|&(frame, _)| frame);
+    Ok(changes)
+}
+
+/// Prints the registers, the display and optionally the memory, colored by access if coverage is
+/// tracked
+fn dump(machine: &Machine, dump_memory: bool) {
+    let registers = machine.registers();
+    println!(
+        "PC: {:03X}  I: {:03X}  DT: {:02X}  ST: {:02X}  Stack: [{}]",
+        machine.program_counter(),
+        registers.address(),
+        registers.delay(),
+        registers.sound_timer(),
+        machine
+            .stack()
+            .entries()
+            .iter()
+            .map(|address| format!("{address:03X}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    for (index, value) in registers.data().iter().enumerate() {
+        print!(
+            "V{index:X}: {value:02X}{}",
+            if index == 15 { "\n" } else { "  " }
+        );
+    }
+    println!();
+    for y in 0..HEIGHT {
+        let row = (0..WIDTH)
+            .map(|x| match machine.display().pixel(x, y) {
+                Some(true) => '#',
+                _ => '.',
+            })
+            .collect::<String>();
+        println!("{row}");
+    }
+    if let Some(coverage) = machine.coverage() {
+        println!(
+            "Coverage: {} executed, {} read, {} written, {} self modified",
+            coverage.count(coverage::EXECUTED),
+            coverage.count(coverage::READ),
+            coverage.count(coverage::WRITTEN),
+            coverage.count(coverage::SELF_MODIFIED)
+        );
+    }
+    if dump_memory {
+        println!();
+        if let Some(coverage) = machine.coverage() {
+            let _ = coverage.write_overlay(machine.memory(), io::stdout().lock());
+            return;
+        }
+        for (row, bytes) in machine.memory().data().chunks(16).enumerate() {
+            let bytes = bytes
+                .iter()
+                .map(|byte| format!("{byte:02X}"))
+                .collect::<Vec<_>>();
+            println!("{:03X}: {}", row * 16, bytes.join(" "));
+        }
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut rom = None;
+    let (mut limit, mut benchmark) = (None, false);
+    let (mut keys, mut movie, mut seed, mut vip_rng) = (Vec::new(), None, None, None);
+    let (mut quirks, mut database) = (None, Some(Database::builtin()));
+    let mut layout = MemoryLayout::default();
+    let mut font = None;
+    let mut stack_limit = Machine::default().stack().limit();
+    let mut engine = Engine::default();
+    let (mut dump_memory, mut coverage, mut export_cart) = (false, None, None);
+    let (mut screenshot, mut video, mut audio) = (None, None, None);
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("Missing value for {arg}"))
+        };
+        match arg.as_str() {
+            "--frames" => {
+                limit = Some(Limit::Frames(
+                    value()?.parse().map_err(|_| "Invalid frame count")?,
+                ));
+            }
+            "--cycles" => {
+                limit = Some(Limit::Cycles(
+                    value()?.parse().map_err(|_| "Invalid cycle count")?,
+                ));
+            }
+            "--keys" => {
+                keys =
+                    parse_keys(&fs::read_to_string(value()?).map_err(|error| error.to_string())?)?;
+            }
+            "--movie" => {
+                let bytes = fs::read(value()?).map_err(|error| error.to_string())?;
+                movie = Some(Movie::from_bytes(&bytes).map_err(|error| error.to_string())?);
+            }
+            "--seed" => seed = Some(value()?.parse::<u64>().map_err(|_| "Invalid seed")?),
+            "--vip-rng" => {
+                let path = value()?;
+                vip_rng = Some(
+                    fs::read(&path).map_err(|error| format!("Failed to load {path}: {error}"))?,
+                );
+            }
+            "--quirks" => {
+                let name = value()?;
+                quirks = Some(Quirks::from_name(&name).ok_or(format!("Unknown quirks: {name}"))?);
+            }
+            "--layout" => {
+                let name = value()?;
+                layout = MemoryLayout::from_name(&name).ok_or(format!("Unknown layout: {name}"))?;
+            }
+            "--database" => {
+                database = match value()?.as_str() {
+                    "none" => None,
+                    path => {
+                        Some(Arc::new(Database::from_path(path).map_err(|error| {
+                            format!("Failed to load {path}: {error}")
+                        })?))
+                    }
+                };
+            }
+            "--font" => {
+                let name = value()?;
+                font = Some(match Font::from_name(&name) {
+                    Some(font) => font,
+                    None => Font::from_path(&name)
+                        .map_err(|error| format!("Failed to load font {name}: {error}"))?,
+                });
+            }
+            "--engine" => {
+                let name = value()?;
+                engine = Engine::from_name(&name).ok_or(format!("Unknown engine: {name}"))?;
+            }
+            "--stack-depth" => {
+                stack_limit = match value()?.as_str() {
+                    "unlimited" => None,
+                    depth => Some(depth.parse().map_err(|_| "Invalid stack depth")?),
+                };
+            }
+            "--benchmark" => benchmark = true,
+            "--dump-memory" => dump_memory = true,
+            "--coverage" => coverage = Some(value()?),
+            "--export-cart" => export_cart = Some(value()?),
+            "--screenshot" => screenshot = Some(value()?),
+            "--video" => {
+                let path = value()?;
+                video = Some(
+                    Recorder::create(Path::new(&path), 1, Palette::default())
+                        .map_err(|error| format!("Failed to create {path}: {error}"))?,
+                );
+            }
+            "--audio" => audio = Some((value()?, Beeper::default(), Vec::new())),
+            _ => rom = Some(arg),
+        }
+    }
+    let rom = rom.ok_or("Missing application path")?;
+    // Movies run the cycles they were recorded with, so they can only be limited by frames
+    let limit = match (limit, &movie) {
+        (Some(Limit::Cycles(_)), Some(_)) => {
+            return Err("A movie can only be limited by --frames, not --cycles".to_owned());
+        }
+        (Some(limit), _) => limit,
+        (None, Some(movie)) if benchmark => Limit::Frames(movie.frames().len() as u64),
+        (None, _) if benchmark => Limit::Cycles(BENCHMARK_CYCLES),
+        (None, _) => Limit::Frames(DEFAULT_FRAMES),
+    };
+
+    let mut machine =
+        Machine::with_layout(Quirks::default(), layout).expect("The memory map presets are valid");
+    machine.set_database(database);
+    machine.stack_mut().set_limit(stack_limit);
+    machine.set_engine(engine);
+    machine.set_coverage_tracking(coverage.is_some());
+    match (vip_rng, seed) {
+        (Some(_), _) if movie.is_some() => {
+            return Err("The VIP random number generator can't be used with a movie".to_owned());
+        }
+        (Some(interpreter), seed) => {
+            let rng = CosmacVip::from_interpreter(seed.unwrap_or(0) as u16, &interpreter)
+                .ok_or("The VIP interpreter dump must be 256 or 512 bytes long")?;
+            machine.set_rng(rng);
+        }
+        (None, Some(seed)) => machine.set_seed(seed),
+        (None, None) => {}
+    }
+    // Cartridges take precedence over the database, explicit options over both
+    let (rom, options) = if Cartridge::is_cartridge_path(Path::new(&rom)) {
+        let cartridge =
+            Cartridge::from_path(&rom).map_err(|error| format!("Failed to load {rom}: {error}"))?;
+        (cartridge.rom, Some(cartridge.options))
+    } else {
+        let rom = Rom::from_path(&rom).map_err(|error| format!("Failed to load {rom}: {error}"))?;
+        (rom, None)
+    };
+    machine
+        .load_rom(&rom)
+        .map_err(|error| format!("Failed to load application: {error}"))?;
+    if let Some(options) = &options {
+        options.apply(&mut machine);
+    }
+    if let Some(quirks) = quirks {
+        machine.set_quirks(quirks);
+    }
+    if let Some(font) = font {
+        machine.set_font(font);
+    }
+    let cycles_per_frame = options.as_ref().map_or_else(
+        || {
+            machine
+                .game()
+                .and_then(|game| game.cycles_per_frame)
+                .unwrap_or(DEFAULT_CYCLES_PER_FRAME)
+        },
+        |options| options.cycles_per_frame,
+    );
+    if let Some(game) = machine.game() {
+        println!("Game: {}", game.title);
+    }
+    // Movies take precedence over the key script, until they run out of frames
+    let mut runner = Runner::new(cycles_per_frame);
+    runner.set_real_time(false);
+    if let Some(movie) = movie {
+        runner
+            .play_movie(&mut machine, movie)
+            .map_err(|error| error.to_string())?;
+    }
+    let mut frontend = Headless {
+        keys: keys.into_iter().peekable(),
+        keypad: Keypad::new(),
+        frame: 0,
+        video,
+        audio,
+    };
+
+    // Frames end early when waiting for a key or the display, so executed instructions are counted
+    let first_instruction = machine.instruction_count();
+    let mut cycles = 0;
+    let start = Instant::now();
+    loop {
+        match limit {
+            Limit::Frames(frames) if runner.frame() as u64 >= frames => break,
+            Limit::Cycles(limit) if cycles >= limit => break,
+            _ => {}
+        }
+        if machine.is_halted() {
+            break;
+        }
+        if let Limit::Cycles(limit) = limit {
+            runner.set_cycles_per_frame((limit - cycles).min(cycles_per_frame.into()) as u32);
+        }
+        let running = runner
+            .run_frame(&mut machine, &mut frontend)
+            .map_err(|error| error.to_string())?;
+        cycles = machine.instruction_count() - first_instruction;
+        if !running {
+            break;
+        }
+    }
+
+    let elapsed = start.elapsed().as_secs_f64();
+    let frame = runner.frame();
+    let Headless { video, audio, .. } = frontend;
+
+    println!("Frames: {frame}");
+    if benchmark {
+        println!(
+            "Benchmark: {cycles} instructions in {elapsed:.3} s, {:.0} instructions per second, {:.0} \
+             frames per second",
+            cycles as f64 / elapsed,
+            frame as f64 / elapsed
+        );
+    }
+    dump(&machine, dump_memory);
+    if let Some(video) = video {
+        video
+            .finish()
+            .map_err(|error| format!("Failed to finish video: {error}"))?;
+    }
+    if let Some(path) = export_cart {
+        let options = CartridgeOptions {
+            cycles_per_frame,
+            quirks: machine.quirks(),
+            palette: options
+                .map(|options| options.palette)
+                .or_else(|| machine.game().and_then(|game| game.palette))
+                .unwrap_or_default(),
+            font: machine.font().clone(),
+        };
+        Cartridge::new(rom, options)
+            .save(machine.display(), &path)
+            .map_err(|error| format!("Failed to save {path}: {error}"))?;
+    }
+    if let (Some(path), Some(coverage)) = (coverage, machine.coverage()) {
+        fs::File::create(&path)
+            .and_then(|file| coverage.write_json(io::BufWriter::new(file)))
+            .map_err(|error| format!("Failed to save {path}: {error}"))?;
+    }
+    if let Some((path, beeper, samples)) = audio {
+        fs::File::create(&path)
+            .and_then(|file| {
+                audio::write_wav(&samples, beeper.sample_rate(), io::BufWriter::new(file))
+            })
+            .map_err(|error| format!("Failed to save {path}: {error}"))?;
+    }
+    if let Some(path) = screenshot {
+        screenshot::save(machine.display(), Path::new(&path), 1, Palette::default())
+            .map_err(|error| format!("Failed to save {path}: {error}"))?;
+    }
+    Ok(())
+}
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("{error}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,156 @@
+//! Runs an application on two machine configurations in lockstep and prints a side-by-side diff of
+//! the first difference.
+//!
+//! Usage: `chip8-lockstep <rom> [--left CONFIG] [--right CONFIG] [--frames N | --movie FILE]
+//! [--seed N] [--cycles-per-frame N]`
+//!
+//! A configuration is an engine, optionally prefixed by a quirk preset: `[vip|schip|xo-chip:]ENGINE`
+//! where the engine is `uncached` (the interpreter without the instruction cache), `interpreter`
+//! or `blocks`. The defaults compare `uncached` to `blocks`, with the quirks of the database or
+//! movie. Exits with status 1 if the machines diverge.
+
+use std::{env, fs, process};
+
+use chip_8::{
+    blocks::Engine,
+    keypad::Keypad,
+    lockstep::Lockstep,
+    machine::{DEFAULT_CYCLES_PER_FRAME, Machine},
+    movie::Movie,
+    quirks::Quirks,
+    rom::Rom,
+};
+
+/// The number of frames run if neither a limit nor a movie was given, 10 seconds
+const DEFAULT_FRAMES: usize = 600;
+
+/// A machine configuration under test
+struct Config {
+    /// The quirks, None to keep those of the database or movie
+    quirks: Option<Quirks>,
+
+    /// The engine
+    engine: Engine,
+
+    /// Whether decoded instructions are cached
+    cached: bool,
+}
+
+impl Config {
+    /// Parses a configuration in the `[PRESET:]ENGINE` format
+    fn parse(config: &str) -> Result<Self, String> {
+        let (quirks, engine) = match config.split_once(':') {
+            Some((preset, engine)) => (
+                Some(Quirks::from_name(preset).ok_or(format!("Unknown quirks: {preset}"))?),
+                engine,
+            ),
+            None => (None, config),
+        };
+        let (engine, cached) = match engine {
+            "uncached" => (Engine::Interpreter, false),
+            "interpreter" => (Engine::Interpreter, true),
+            "blocks" => (Engine::Blocks, true),
+            _ => return Err(format!("Unknown engine: {engine}")),
+        };
+        Ok(Self {
+            quirks,
+            engine,
+            cached,
+        })
+    }
+
+    /// Creates a machine with the configuration running the application
+    fn machine(&self, rom: &Rom, movie: Option<&Movie>, seed: u64) -> Result<Machine, String> {
+        let mut machine = Machine::new(Quirks::default());
+        machine.memory_mut().set_cache_enabled(self.cached);
+        machine.set_engine(self.engine);
+        machine
+            .load_rom(rom)
+            .map_err(|error| format!("Failed to load application: {error}"))?;
+        machine.set_seed(seed);
+        if let Some(movie) = movie {
+            movie
+                .start_playback(&mut machine)
+                .map_err(|error| error.to_string())?;
+        }
+        if let Some(quirks) = self.quirks {
+            machine.set_quirks(quirks);
+        }
+        Ok(machine)
+    }
+}
+
+fn run() -> Result<bool, String> {
+    let mut rom = None;
+    let (mut left, mut right) = (Config::parse("uncached")?, Config::parse("blocks")?);
+    let (mut frames, mut movie, mut seed, mut cycles_per_frame) = (None, None, 0, None);
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("Missing value for {arg}"))
+        };
+        match arg.as_str() {
+            "--left" => left = Config::parse(&value()?)?,
+            "--right" => right = Config::parse(&value()?)?,
+            "--frames" => frames = Some(value()?.parse().map_err(|_| "Invalid frame count")?),
+            "--movie" => {
+                let bytes = fs::read(value()?).map_err(|error| error.to_string())?;
+                movie = Some(Movie::from_bytes(&bytes).map_err(|error| error.to_string())?);
+            }
+            "--seed" => seed = value()?.parse().map_err(|_| "Invalid seed")?,
+            "--cycles-per-frame" => {
+                cycles_per_frame = Some(value()?.parse().map_err(|_| "Invalid cycle count")?);
+            }
+            _ => rom = Some(arg),
+        }
+    }
+    let path = rom.ok_or("Missing application path")?;
+    let rom = Rom::from_path(&path).map_err(|error| format!("Failed to load {path}: {error}"))?;
+
+    let left_machine = left.machine(&rom, movie.as_ref(), seed)?;
+    let right_machine = right.machine(&rom, movie.as_ref(), seed)?;
+    let cycles_per_frame = cycles_per_frame.unwrap_or_else(|| {
+        movie.as_ref().map_or_else(
+            || {
+                left_machine
+                    .game()
+                    .and_then(|game| game.cycles_per_frame)
+                    .unwrap_or(DEFAULT_CYCLES_PER_FRAME)
+            },
+            Movie::cycles_per_frame,
+        )
+    });
+    let keypads = match &movie {
+        Some(movie) => movie
+            .frames()
+            .iter()
+            .copied()
+            .take(frames.unwrap_or(usize::MAX))
+            .collect(),
+        None => vec![Keypad::new(); frames.unwrap_or(DEFAULT_FRAMES)],
+    };
+
+    let mut lockstep = Lockstep::new(left_machine, right_machine, cycles_per_frame);
+    match lockstep.run(keypads) {
+        Some(divergence) => {
+            print!("{divergence}");
+            Ok(false)
+        }
+        None => {
+            println!("No divergence in {} frames", lockstep.frame());
+            Ok(true)
+        }
+    }
+}
+
+fn main() {
+    match run() {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(error) => {
+            eprintln!("{error}");
+            process::exit(2);
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Recompiles an application into a Rust module linking against the chip_8 crate.
+//!
+//! Usage: `chip8-recompile <rom> [--quirks vip|schip|xo-chip] [--layout vip|eti-660|xo-chip]
+//! [--database FILE|none] [--output FILE]`
+//!
+//! The quirks default to those of the database, the module is written to standard output if no
+//! output file is given.
+
+use std::{env, fs, io, path::Path, process, sync::Arc};
+
+use chip_8::{
+    database::Database, machine::Machine, memory::MemoryLayout, quirks::Quirks,
+    recompiler::Program, rom::Rom,
+};
+
+fn run() -> Result<(), String> {
+    let (mut rom, mut output) = (None, None);
+    let (mut quirks, mut database) = (None, Some(Database::builtin()));
+    let mut layout = MemoryLayout::default();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("Missing value for {arg}"))
+        };
+        match arg.as_str() {
+            "--quirks" => {
+                let name = value()?;
+                quirks = Some(Quirks::from_name(&name).ok_or(format!("Unknown quirks: {name}"))?);
+            }
+            "--layout" => {
+                let name = value()?;
+                layout = MemoryLayout::from_name(&name).ok_or(format!("Unknown layout: {name}"))?;
+            }
+            "--database" => {
+                database = match value()?.as_str() {
+                    "none" => None,
+                    path => {
+                        Some(Arc::new(Database::from_path(path).map_err(|error| {
+                            format!("Failed to load {path}: {error}")
+                        })?))
+                    }
+                };
+            }
+            "--output" => output = Some(value()?),
+            _ => rom = Some(arg),
+        }
+    }
+    let path = rom.ok_or("Missing application path")?;
+    let rom = Rom::from_path(&path).map_err(|error| format!("Failed to load {path}: {error}"))?;
+
+    // Loading the application checks that it fits and applies the quirks of the database
+    let mut machine = Machine::with_layout(Quirks::default(), layout.clone())
+        .expect("The memory map presets are valid");
+    machine.set_database(database);
+    machine
+        .load_rom(&rom)
+        .map_err(|error| format!("Failed to load application: {error}"))?;
+    let quirks = quirks.unwrap_or(machine.quirks());
+
+    let program = Program::analyze(&rom, &layout);
+    let name = Path::new(&path)
+        .file_name()
+        .map_or(path.clone(), |name| name.to_string_lossy().into_owned());
+    match &output {
+        Some(output) => fs::File::create(output)
+            .and_then(|file| program.write_rust(&name, quirks, io::BufWriter::new(file)))
+            .map_err(|error| format!("Failed to save {output}: {error}"))?,
+        None => program
+            .write_rust(&name, quirks, io::stdout().lock())
+            .map_err(|error| error.to_string())?,
+    }
+    eprintln!(
+        "Recompiled {} routines, {} blocks and {} instructions",
+        program.routines().len(),
+        program.block_count(),
+        program.instruction_count()
+    );
+    Ok(())
+}
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("{error}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,589 @@
+//! Runs an application in a terminal, for machines without a window system such as SSH sessions.
+//!
+//! Usage: `chip8-tui <rom|cartridge.gif> [--seed N] [--quirks vip|schip|xo-chip]
+//! [--cells half|braille] [--keymap KEYS] [--hold N] [--coverage FILE] [--database FILE]`
+//!
+//! The display is drawn with half blocks (two pixels per cell) or braille patterns (eight pixels
+//! per cell) in 24-bit color, with the registers beside it. F7 switches between the display and
+//! the memory around the program counter, which is colored by access if `--coverage` tracks
+//! them: red for self modified code, yellow for written, green for executed and cyan for read
+//! bytes. The coverage map is written to the file as JSON when quitting. The keymap lists the keyboard keys of
+//! the chip-8 keys 0 through F, `x123qweasdzc4rfv` by default. The arrows, space and enter press
+//! the buttons of applications in the database, which is built in or loaded from a `programs.json`
+//! of the community chip-8 database.
+//!
+//! The hotkeys match the windowed frontend: Tab fast-forwards and Backspace rewinds while held, F1
+//! to F4 save and Shift+F1 to F4 load the save slots, F5 pauses, F6 advances a single frame while
+//! paused, F10 starts and stops a video, F12 takes a screenshot and Escape or Ctrl+C quits.
+//!
+//! Most terminals only report key presses, so keys count as held for a number of frames after
+//! their last press or repeat. Terminals supporting the kitty keyboard protocol report releases,
+//! which makes keys behave exactly like on a keyboard.
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    process,
+    sync::Arc,
+    time::Duration,
+};
+
+#[cfg(feature = "audio")]
+use chip_8::audio::Speaker;
+use chip_8::{
+    cartridge::Cartridge,
+    coverage::Access,
+    database::Database,
+    display::{Display, HEIGHT, WIDTH},
+    frontend::{Command, Frontend, Input, Runner},
+    machine::{DEFAULT_CYCLES_PER_FRAME, Machine},
+    quirks::Quirks,
+    rewind::{self, Rewind},
+    rom::Rom,
+    screenshot::Palette,
+};
+use crossterm::{
+    cursor, event,
+    event::{
+        Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
+    queue,
+    style::{
+        Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor,
+    },
+    terminal::{self, ClearType},
+};
+
+/// The keyboard keys mapped to the chip-8 keys 0 through F
+const DEFAULT_KEYMAP: &str = "x123qweasdzc4rfv";
+
+/// The keyboard keys of the buttons of the database, pressing the chip-8 key the application
+/// uses for that button
+const BUTTONS: [(&str, KeyCode); 6] = [
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("a", KeyCode::Char(' ')),
+    ("b", KeyCode::Enter),
+];
+
+/// The number of frames a key counts as held after its last press by default, if the terminal
+/// doesn't report releases. Long enough to bridge the delay before keys repeat.
+const DEFAULT_HOLD_FRAMES: u64 = 15;
+
+/// The key running the emulation faster while held
+const TURBO_KEY: KeyCode = KeyCode::Tab;
+
+/// The key stepping backwards in time while held
+const REWIND_KEY: KeyCode = KeyCode::Backspace;
+
+/// How the display is drawn with characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cells {
+    /// Upper half blocks, the foreground color is the upper and the background the lower pixel
+    HalfBlocks,
+
+    /// Braille patterns covering two by four pixels
+    Braille,
+}
+
+impl Cells {
+    /// Retrieves the number of pixels covered by a character, horizontally and vertically
+    const fn size(self) -> (usize, usize) {
+        match self {
+            Self::HalfBlocks => (1, 2),
+            Self::Braille => (2, 4),
+        }
+    }
+}
+
+/// The raw mode terminal, restored when dropped
+struct Terminal {
+    /// Whether key releases are reported, which required pushing keyboard enhancement flags
+    releases: bool,
+}
+
+impl Terminal {
+    /// Switches to raw mode and the alternate screen
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let releases = terminal::supports_keyboard_enhancement().unwrap_or(false);
+        let mut stdout = io::stdout();
+        queue!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+        if releases {
+            queue!(
+                stdout,
+                PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+            )?;
+        }
+        stdout.flush()?;
+        Ok(Self { releases })
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let mut stdout = io::stdout();
+        if self.releases {
+            let _ = queue!(stdout, PopKeyboardEnhancementFlags);
+        }
+        let _ = queue!(
+            stdout,
+            ResetColor,
+            cursor::Show,
+            terminal::LeaveAlternateScreen
+        );
+        let _ = stdout.flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Converts a color to a terminal color
+const fn color([r, g, b]: [u8; 3]) -> Color {
+    Color::Rgb { r, g, b }
+}
+
+/// Retrieves the terminal color of bytes with the access
+const fn access_color(access: Access) -> Color {
+    match access {
+        Access::SelfModified => Color::Red,
+        Access::Written => Color::Yellow,
+        Access::Executed => Color::Green,
+        Access::Read => Color::Cyan,
+        Access::Unused => Color::DarkGrey,
+    }
+}
+
+/// Queues the memory around the program counter in place of the display, colored by access if
+/// they are tracked. The instruction at the program counter is shown reversed.
+fn draw_memory(stdout: &mut impl Write, machine: &Machine, cells: Cells) -> io::Result<()> {
+    let (cell_width, cell_height) = cells.size();
+    let (columns, rows) = (WIDTH / cell_width, HEIGHT / cell_height);
+    // Every row shows its address and as many bytes as fit, 16 if possible
+    let per_row = if columns >= 5 + 16 * 3 { 16 } else { 8 };
+    let data = machine.memory().data();
+    let program_counter = usize::from(machine.program_counter());
+    let first = (program_counter / per_row).saturating_sub(rows / 2) * per_row;
+    for row in 0..rows {
+        queue!(stdout, cursor::MoveTo(0, row as u16))?;
+        let start = first + row * per_row;
+        let bytes = data.get(start..data.len().min(start + per_row));
+        let mut width = 0;
+        if let Some(bytes) = bytes.filter(|bytes| !bytes.is_empty()) {
+            queue!(stdout, Print(format!("{start:04X}:")))?;
+            width += 5;
+            for (offset, byte) in bytes.iter().enumerate() {
+                let address = start + offset;
+                let access = machine.coverage().map_or(Access::Unused, |coverage| {
+                    Access::from_flags(coverage.accesses().get(address).copied().unwrap_or(0))
+                });
+                queue!(stdout, Print(' '), SetForegroundColor(access_color(access)))?;
+                let current = (program_counter..program_counter + 2).contains(&address);
+                if current {
+                    queue!(stdout, SetAttribute(Attribute::Reverse))?;
+                }
+                queue!(stdout, Print(format!("{byte:02X}")), ResetColor)?;
+                if current {
+                    queue!(stdout, SetAttribute(Attribute::NoReverse))?;
+                }
+                width += 3;
+            }
+        }
+        queue!(stdout, Print(" ".repeat(columns.saturating_sub(width))))?;
+    }
+    Ok(())
+}
+
+/// Queues the display, only changing colors when needed
+fn draw_display(
+    stdout: &mut impl Write,
+    display: &Display,
+    cells: Cells,
+    palette: Palette,
+) -> io::Result<()> {
+    let (cell_width, cell_height) = cells.size();
+    let lit = |x, y| display.pixel(x, y) == Some(true);
+    let mut colors = None;
+    for row in 0..HEIGHT / cell_height {
+        queue!(stdout, cursor::MoveTo(0, row as u16))?;
+        for column in 0..WIDTH / cell_width {
+            let (x, y) = (column * cell_width, row * cell_height);
+            let (character, foreground, background) = match cells {
+                Cells::HalfBlocks => ('▀', palette.color(lit(x, y)), palette.color(lit(x, y + 1))),
+                Cells::Braille => {
+                    // The dots of the left column, then the right column, then the bottom row
+                    let dots = [
+                        (0, 0),
+                        (0, 1),
+                        (0, 2),
+                        (1, 0),
+                        (1, 1),
+                        (1, 2),
+                        (0, 3),
+                        (1, 3),
+                    ];
+                    let pattern = dots
+                        .into_iter()
+                        .enumerate()
+                        .filter(|&(_, (dx, dy))| lit(x + dx, y + dy))
+                        .fold(0, |pattern, (bit, _)| pattern | 1 << bit);
+                    let character = char::from_u32(0x2800 + pattern).unwrap_or(' ');
+                    (character, palette.foreground, palette.background)
+                }
+            };
+            if colors != Some((foreground, background)) {
+                queue!(
+                    stdout,
+                    SetForegroundColor(color(foreground)),
+                    SetBackgroundColor(color(background))
+                )?;
+                colors = Some((foreground, background));
+            }
+            queue!(stdout, Print(character))?;
+        }
+    }
+    queue!(stdout, ResetColor)
+}
+
+/// Queues the registers and the last message to the right of the display
+fn draw_registers(
+    stdout: &mut impl Write,
+    machine: &Machine,
+    cells: Cells,
+    message: Option<&str>,
+) -> io::Result<()> {
+    let registers = machine.registers();
+    let mut lines = vec![
+        format!(
+            "PC {:03X}  I {:03X}",
+            machine.program_counter(),
+            registers.address()
+        ),
+        format!(
+            "DT {:02X}   ST {:02X}",
+            registers.delay(),
+            registers.sound_timer()
+        ),
+        String::new(),
+    ];
+    lines.extend(registers.data().chunks(4).enumerate().map(|(row, values)| {
+        values
+            .iter()
+            .enumerate()
+            .map(|(column, value)| format!("V{:X} {value:02X}", row * 4 + column))
+            .collect::<Vec<_>>()
+            .join("  ")
+    }));
+    lines.push(String::new());
+    lines.push(format!("Stack {}", machine.stack().depth()));
+    lines.push(String::new());
+    lines.push(message.unwrap_or_default().to_owned());
+    let column = (WIDTH / cells.size().0 + 2) as u16;
+    for (row, line) in lines.iter().enumerate() {
+        queue!(
+            stdout,
+            cursor::MoveTo(column, row as u16),
+            Print(line),
+            terminal::Clear(ClearType::UntilNewLine)
+        )?;
+    }
+    Ok(())
+}
+
+/// The terminal showing the display and registers
+struct TerminalFrontend {
+    /// The raw mode terminal
+    terminal: Terminal,
+
+    /// The frame up to which every key counts as held, forever if releases are reported
+    held: HashMap<KeyCode, u64>,
+
+    /// The number of frames polled so far
+    frame: u64,
+
+    /// The number of frames a key counts as held after its last press
+    hold: u64,
+
+    /// The keyboard keys of the chip-8 keys 0 through F
+    keymap: Vec<KeyCode>,
+
+    /// The chip-8 keys of the buttons of the application, and the keyboard keys pressing them
+    buttons: Vec<(u8, KeyCode)>,
+
+    /// How the display is drawn
+    cells: Cells,
+
+    /// The colors of the pixels
+    palette: Palette,
+
+    /// The display as last drawn, to skip drawing it if it didn't change
+    drawn: Option<Display>,
+
+    /// Whether the memory is shown instead of the display
+    memory_view: bool,
+
+    /// The message shown below the registers
+    message: Option<String>,
+
+    /// Whether the user asked to quit, or the terminal failed
+    quit: bool,
+
+    /// Plays the beeper, None if the audio device couldn't be opened
+    #[cfg(feature = "audio")]
+    speaker: Option<Speaker>,
+}
+
+impl TerminalFrontend {
+    /// Handles a key press, repeat or release, returning the command of hotkeys
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Command> {
+        let code = match key.code {
+            KeyCode::Char(character) => KeyCode::Char(character.to_ascii_lowercase()),
+            code => code,
+        };
+        match key.kind {
+            KeyEventKind::Release => {
+                self.held.remove(&code);
+                return None;
+            }
+            _ if self.terminal.releases => self.held.insert(code, u64::MAX),
+            _ => self.held.insert(code, self.frame + self.hold),
+        };
+        if key.kind != KeyEventKind::Press {
+            return None;
+        }
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        match code {
+            KeyCode::Esc => Some(Command::Quit),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                Some(Command::Quit)
+            }
+            KeyCode::F(slot @ 1..=4) if shift => Some(Command::Load(slot.into())),
+            KeyCode::F(slot @ 1..=4) => Some(Command::Save(slot.into())),
+            KeyCode::F(5) => Some(Command::TogglePause),
+            KeyCode::F(6) => Some(Command::Advance),
+            KeyCode::F(7) => {
+                self.memory_view = !self.memory_view;
+                self.drawn = None;
+                None
+            }
+            KeyCode::F(10) => Some(Command::ToggleVideo),
+            KeyCode::F(12) => Some(Command::Screenshot),
+            _ => None,
+        }
+    }
+}
+
+impl Frontend for TerminalFrontend {
+    fn poll_input(&mut self) -> Input {
+        let mut input = Input::default();
+        loop {
+            match event::poll(Duration::ZERO).and_then(|ready| ready.then(event::read).transpose())
+            {
+                Ok(Some(Event::Key(key))) => input.commands.extend(self.handle_key(key)),
+                Ok(Some(_)) => {}
+                Ok(None) => break,
+                Err(_) => {
+                    self.quit = true;
+                    break;
+                }
+            }
+        }
+        let frame = self.frame;
+        self.held.retain(|_, until| *until > frame);
+        self.frame += 1;
+
+        for (key, host_key) in self.keymap.iter().enumerate() {
+            input
+                .keypad
+                .set(key as u8, self.held.contains_key(host_key));
+        }
+        for (key, host_key) in &self.buttons {
+            if self.held.contains_key(host_key) {
+                input.keypad.set(*key, true);
+            }
+        }
+        input.fast_forward = self.held.contains_key(&TURBO_KEY);
+        input.rewind = self.held.contains_key(&REWIND_KEY);
+        input
+    }
+
+    fn present(&mut self, machine: &Machine) -> io::Result<()> {
+        let mut stdout = io::stdout().lock();
+        if self.memory_view {
+            draw_memory(&mut stdout, machine, self.cells)?;
+        } else if self.drawn.as_ref() != Some(machine.display()) {
+            draw_display(&mut stdout, machine.display(), self.cells, self.palette)?;
+            self.drawn = Some(machine.display().clone());
+        }
+        draw_registers(&mut stdout, machine, self.cells, self.message.as_deref())?;
+        stdout.flush()
+    }
+
+    #[cfg(feature = "audio")]
+    fn play_audio(&mut self, sound_timer: u8) {
+        if let Some(speaker) = &mut self.speaker {
+            speaker.play_frame(sound_timer);
+        }
+    }
+
+    fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    fn notify(&mut self, message: &str) {
+        self.message = Some(message.to_owned());
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut rom = None;
+    let (mut seed, mut quirks) = (None, None);
+    let (mut cells, mut keymap, mut hold) = (Cells::HalfBlocks, None, DEFAULT_HOLD_FRAMES);
+    let (mut coverage, mut database) = (None, None);
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("Missing value for {arg}"))
+        };
+        match arg.as_str() {
+            "--seed" => seed = Some(value()?.parse::<u64>().map_err(|_| "Invalid seed")?),
+            "--quirks" => {
+                let name = value()?;
+                quirks = Some(Quirks::from_name(&name).ok_or(format!("Unknown quirks: {name}"))?);
+            }
+            "--cells" => {
+                cells = match value()?.as_str() {
+                    "half" => Cells::HalfBlocks,
+                    "braille" => Cells::Braille,
+                    name => return Err(format!("Unknown cells: {name}")),
+                };
+            }
+            "--keymap" => keymap = Some(value()?),
+            "--hold" => hold = value()?.parse().map_err(|_| "Invalid hold duration")?,
+            "--coverage" => coverage = Some(value()?),
+            "--database" => {
+                let path = value()?;
+                database = Some(
+                    Database::from_path(&path)
+                        .map_err(|error| format!("Failed to load {path}: {error}"))?,
+                );
+            }
+            _ => rom = Some(arg),
+        }
+    }
+    let keymap = keymap
+        .as_deref()
+        .unwrap_or(DEFAULT_KEYMAP)
+        .chars()
+        .map(|key| KeyCode::Char(key.to_ascii_lowercase()))
+        .collect::<Vec<_>>();
+    if keymap.len() != 16 {
+        return Err("The keymap needs exactly 16 keys".to_owned());
+    }
+    let rom = rom.ok_or("Missing application path")?;
+
+    let mut machine = Machine::new(Quirks::default());
+    if let Some(seed) = seed {
+        machine.set_seed(seed);
+    }
+    if let Some(database) = database {
+        machine.set_database(Some(Arc::new(database)));
+    }
+    // Cartridges bring their own settings, which take precedence over the database
+    let options = if Cartridge::is_cartridge_path(Path::new(&rom)) {
+        let cartridge =
+            Cartridge::from_path(&rom).map_err(|error| format!("Failed to load {rom}: {error}"))?;
+        machine
+            .load_rom(&cartridge.rom)
+            .map_err(|error| format!("Failed to load application: {error}"))?;
+        cartridge.options.apply(&mut machine);
+        Some(cartridge.options)
+    } else {
+        let rom = Rom::from_path(&rom).map_err(|error| format!("Failed to load {rom}: {error}"))?;
+        machine
+            .load_rom(&rom)
+            .map_err(|error| format!("Failed to load application: {error}"))?;
+        None
+    };
+    if let Some(quirks) = quirks {
+        machine.set_quirks(quirks);
+    }
+    machine.set_coverage_tracking(coverage.is_some());
+    let game = machine.game().cloned();
+    let cycles_per_frame = options.as_ref().map_or_else(
+        || {
+            game.as_ref()
+                .and_then(|game| game.cycles_per_frame)
+                .unwrap_or(DEFAULT_CYCLES_PER_FRAME)
+        },
+        |options| options.cycles_per_frame,
+    );
+    let palette = options
+        .as_ref()
+        .map(|options| options.palette)
+        .or_else(|| game.as_ref().and_then(|game| game.palette))
+        .unwrap_or_default();
+    let buttons = game.as_ref().map_or_else(Vec::new, |game| {
+        BUTTONS
+            .into_iter()
+            .filter_map(|(name, host_key)| {
+                let (_, key) = game.keys.iter().find(|(button, _)| button == name)?;
+                Some((*key, host_key))
+            })
+            .collect()
+    });
+
+    let mut runner = Runner::new(cycles_per_frame);
+    runner.set_rewind(Some(Rewind::new(rewind::DEFAULT_BUDGET)));
+    runner.set_path(Some(rom));
+    runner.set_palette(palette);
+    // The terminal is drawn over by the frontend, so failing to open the device is shown below the
+    // registers
+    #[cfg(feature = "audio")]
+    let (speaker, message) = match Speaker::new() {
+        Ok(speaker) => (Some(speaker), None),
+        Err(error) => (None, Some(format!("Sound is disabled: {error}"))),
+    };
+    #[cfg(not(feature = "audio"))]
+    let message = None;
+    let mut frontend = TerminalFrontend {
+        terminal: Terminal::enter().map_err(|error| error.to_string())?,
+        held: HashMap::new(),
+        frame: 0,
+        hold,
+        keymap,
+        buttons,
+        cells,
+        palette,
+        drawn: None,
+        memory_view: false,
+        message,
+        quit: false,
+        #[cfg(feature = "audio")]
+        speaker,
+    };
+    queue!(io::stdout(), terminal::Clear(ClearType::All)).map_err(|error| error.to_string())?;
+    runner
+        .run(&mut machine, &mut frontend)
+        .map_err(|error| error.to_string())?;
+    runner.finish().map_err(|error| error.to_string())?;
+
+    if let (Some(path), Some(coverage)) = (coverage, machine.coverage()) {
+        File::create(&path)
+            .and_then(|file| coverage.write_json(BufWriter::new(file)))
+            .map_err(|error| format!("Failed to save {path}: {error}"))?;
+    }
+    Ok(())
+}
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("{error}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,707 @@
+//! This module contains the basic block compiler, an execution engine that compiles straight-line
+//! code once into micro-operations and then runs them without fetching, decoding or dispatching on
+//! every instruction.
+//!
+//! A block starts at the address it is entered at and ends with the first instruction that may
+//! not continue with the next one. Every instruction is compiled into a micro-operation, a handler
+//! specialized for the quirks the block was compiled with and its operands with the register
+//! indices already resolved. Blocks are discarded as soon as one of their bytes is written or the
+//! quirks change, so self-modifying code behaves exactly as with the interpreter.
+
+use std::ops::Range;
+
+use crate::{
+    instruction::Instruction,
+    machine::{Machine, MachineError, Step},
+    memory::Memory,
+    quirks::Quirks,
+};
+
+/// The maximum number of instructions in a block
+const MAX_BLOCK_LENGTH: usize = 64;
+
+/// The ways instructions can be executed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Engine {
+    /// Fetches and decodes every instruction before executing it
+    #[default]
+    Interpreter,
+
+    /// Compiles code into basic blocks of micro-operations specialized for the quirks and
+    /// executes whole blocks
+    Blocks,
+}
+
+impl Engine {
+    /// Retrieves the engine with the given name (interpreter or blocks), case insensitive
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "interpreter" => Some(Self::Interpreter),
+            "blocks" => Some(Self::Blocks),
+            _ => None,
+        }
+    }
+}
+
+/// Retrieves whether the instruction may continue with anything but the next instruction
+const fn ends_block(instruction: Instruction) -> bool {
+    matches!(
+        instruction,
+        Instruction::Return
+            | Instruction::JumpAddress(_)
+            | Instruction::CallAddress(_)
+            | Instruction::SkipEqualRegByte(..)
+            | Instruction::SkipNotEqualRegByte(..)
+            | Instruction::SkipEqualRegisters(_)
+            | Instruction::SkipNotEqualReg(_)
+            | Instruction::JumpAddressOffset(_)
+            | Instruction::SkipPressed(_)
+            | Instruction::SkipNotPressed(_)
+            | Instruction::LoadKeyPress(_)
+            | Instruction::Exit
+    )
+}
+
+/// The function executing a micro-operation on a machine, the program counter already points to
+/// the next instruction
+type Handler<const SIZE: usize> =
+    fn(&mut Machine<SIZE>, &MicroOp<SIZE>) -> Result<Step, MachineError>;
+
+/// A compiled instruction
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MicroOp<const SIZE: usize> {
+    /// The function executing the instruction
+    handler: Handler<SIZE>,
+
+    /// The address the instruction was compiled from
+    address: u16,
+
+    /// The first register operand, resolved by the quirks
+    x: u8,
+
+    /// The second register operand, resolved by the quirks
+    y: u8,
+
+    /// The byte operand
+    byte: u8,
+
+    /// The address operand, or the increment of I after loading or storing registers
+    target: u16,
+}
+
+impl<const SIZE: usize> MicroOp<SIZE> {
+    /// Compiles the instruction at the address for the quirks
+    fn compile(address: u16, instruction: Instruction, quirks: Quirks) -> Self {
+        let op = |handler: Handler<SIZE>| Self {
+            handler,
+            address,
+            x: 0,
+            y: 0,
+            byte: 0,
+            target: 0,
+        };
+        match instruction {
+            Instruction::SystemAddress(_) => op(nothing),
+            Instruction::ClearScreen => op(clear_screen),
+            Instruction::Return => op(return_from),
+            Instruction::JumpAddress(target) => Self { target, ..op(jump) },
+            Instruction::CallAddress(target) => Self { target, ..op(call) },
+            Instruction::SkipEqualRegByte(x, byte) => Self {
+                x: x & 0xF,
+                byte,
+                ..op(skip_equal_byte)
+            },
+            Instruction::SkipNotEqualRegByte(x, byte) => Self {
+                x: x & 0xF,
+                byte,
+                ..op(skip_not_equal_byte)
+            },
+            Instruction::SkipEqualRegisters(regs) => registers(regs, op(skip_equal)),
+            Instruction::LoadByte(x, byte) => Self {
+                x: x & 0xF,
+                byte,
+                ..op(load_byte)
+            },
+            Instruction::AddByte(x, byte) => Self {
+                x: x & 0xF,
+                byte,
+                ..op(add_byte)
+            },
+            Instruction::LoadRegister(regs) => registers(regs, op(load_register)),
+            Instruction::Or(regs) if quirks.vf_reset => registers(regs, op(or::<SIZE, true>)),
+            Instruction::Or(regs) => registers(regs, op(or::<SIZE, false>)),
+            Instruction::And(regs) if quirks.vf_reset => registers(regs, op(and::<SIZE, true>)),
+            Instruction::And(regs) => registers(regs, op(and::<SIZE, false>)),
+            Instruction::Xor(regs) if quirks.vf_reset => registers(regs, op(xor::<SIZE, true>)),
+            Instruction::Xor(regs) => registers(regs, op(xor::<SIZE, false>)),
+            Instruction::Add(regs) => registers(regs, op(add)),
+            Instruction::Sub(regs) => registers(regs, op(sub)),
+            Instruction::ShiftRight(regs) => shift(regs, quirks, op(shift_right)),
+            Instruction::SubInverted(regs) => registers(regs, op(sub_inverted)),
+            Instruction::ShiftLeft(regs) => shift(regs, quirks, op(shift_left)),
+            Instruction::SkipNotEqualReg(regs) => registers(regs, op(skip_not_equal)),
+            Instruction::LoadI(target) => Self {
+                target,
+                ..op(load_i)
+            },
+            Instruction::JumpAddressOffset(target) => Self {
+                x: if quirks.jump_uses_vx {
+                    (target >> 8) as u8 & 0xF
+                } else {
+                    0
+                },
+                target,
+                ..op(jump_offset)
+            },
+            Instruction::RandRange(x, byte) => Self {
+                x: x & 0xF,
+                byte,
+                ..op(random)
+            },
+            Instruction::Draw(regs, byte) => {
+                let handler = match (quirks.clip_sprites, quirks.display_wait) {
+                    (false, false) => draw::<SIZE, false, false>,
+                    (false, true) => draw::<SIZE, false, true>,
+                    (true, false) => draw::<SIZE, true, false>,
+                    (true, true) => draw::<SIZE, true, true>,
+                };
+                Self {
+                    byte,
+                    ..registers(regs, op(handler))
+                }
+            }
+            Instruction::SkipPressed(x) => register(x, op(skip_pressed)),
+            Instruction::SkipNotPressed(x) => register(x, op(skip_not_pressed)),
+            Instruction::LoadRegisterDelayTimer(x) => register(x, op(load_delay)),
+            Instruction::LoadKeyPress(x) => register(x, op(wait_for_key)),
+            Instruction::LoadDelayTimerRegister(x) => register(x, op(set_delay)),
+            Instruction::LoadSoundTimerRegister(x) => register(x, op(set_sound_timer)),
+            Instruction::AddAddresssRegister(x) => register(x, op(add_address)),
+            Instruction::LoadSpriteAddress(x) => register(x, op(load_sprite_address)),
+            Instruction::LoadRegisterSprites(x) => register(x, op(store_digits)),
+            Instruction::LoadMemoryRegisters(x) => Self {
+                target: increment(x, quirks),
+                ..register(x, op(store_registers))
+            },
+            Instruction::LoadRegistersMemory(x) => Self {
+                target: increment(x, quirks),
+                ..register(x, op(load_registers))
+            },
+            Instruction::Exit => op(exit),
+        }
+    }
+}
+
+/// Sets the register operand of the micro-operation
+const fn register<const SIZE: usize>(x: u8, op: MicroOp<SIZE>) -> MicroOp<SIZE> {
+    MicroOp { x: x & 0xF, ..op }
+}
+
+/// Sets the register operands of the micro-operation from the registers X and Y packed into a byte
+const fn registers<const SIZE: usize>(regs: u8, op: MicroOp<SIZE>) -> MicroOp<SIZE> {
+    MicroOp {
+        x: regs >> 4,
+        y: regs & 0xF,
+        ..op
+    }
+}
+
+/// Sets the register operands of a shift, the second one being the register shifted
+const fn shift<const SIZE: usize>(regs: u8, quirks: Quirks, op: MicroOp<SIZE>) -> MicroOp<SIZE> {
+    MicroOp {
+        x: regs >> 4,
+        y: if quirks.shift_uses_vy {
+            regs & 0xF
+        } else {
+            regs >> 4
+        },
+        ..op
+    }
+}
+
+/// Retrieves the increment of I after loading or storing registers up to the register
+const fn increment(x: u8, quirks: Quirks) -> u16 {
+    if quirks.load_store_increments_i {
+        (x & 0xF) as u16 + !quirks.increment_i_by_x as u16
+    } else {
+        0
+    }
+}
+
+/// Executes 0NNN, which is ignored
+const fn nothing<const SIZE: usize>(
+    _: &mut Machine<SIZE>,
+    _: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    Ok(Step::Continue)
+}
+
+/// Executes 00E0
+fn clear_screen<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    _: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.display.clear();
+    Ok(Step::Continue)
+}
+
+/// Executes 00EE
+fn return_from<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.return_from(op.address)?;
+    Ok(Step::Continue)
+}
+
+/// Executes 1NNN
+const fn jump<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.program_counter = op.target;
+    Ok(Step::Continue)
+}
+
+/// Executes 2NNN
+fn call<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.call(op.address, op.target)?;
+    Ok(Step::Continue)
+}
+
+/// Executes 3XNN
+const fn skip_equal_byte<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    let condition = machine.value(op.x) == op.byte;
+    skip(machine, op, condition)
+}
+
+/// Executes 4XNN
+const fn skip_not_equal_byte<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    let condition = machine.value(op.x) != op.byte;
+    skip(machine, op, condition)
+}
+
+/// Executes 5XY0
+const fn skip_equal<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    let condition = machine.value(op.x) == machine.value(op.y);
+    skip(machine, op, condition)
+}
+
+/// Executes 6XNN
+const fn load_byte<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.set_value(op.x, op.byte);
+    Ok(Step::Continue)
+}
+
+/// Executes 7XNN
+const fn add_byte<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.set_value(op.x, machine.value(op.x).wrapping_add(op.byte));
+    Ok(Step::Continue)
+}
+
+/// Executes 8XY0
+const fn load_register<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.set_value(op.x, machine.value(op.y));
+    Ok(Step::Continue)
+}
+
+/// Executes 8XY1, resetting VF if `RESET` is set
+const fn or<const SIZE: usize, const RESET: bool>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.set_value(op.x, machine.value(op.x) | machine.value(op.y));
+    if RESET {
+        machine.set_value(0xF, 0);
+    }
+    Ok(Step::Continue)
+}
+
+/// Executes 8XY2, resetting VF if `RESET` is set
+const fn and<const SIZE: usize, const RESET: bool>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.set_value(op.x, machine.value(op.x) & machine.value(op.y));
+    if RESET {
+        machine.set_value(0xF, 0);
+    }
+    Ok(Step::Continue)
+}
+
+/// Executes 8XY3, resetting VF if `RESET` is set
+const fn xor<const SIZE: usize, const RESET: bool>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.set_value(op.x, machine.value(op.x) ^ machine.value(op.y));
+    if RESET {
+        machine.set_value(0xF, 0);
+    }
+    Ok(Step::Continue)
+}
+
+/// Executes 8XY4
+const fn add<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    let (result, carry) = machine.value(op.x).overflowing_add(machine.value(op.y));
+    machine.set_value(op.x, result);
+    machine.set_value(0xF, carry as u8);
+    Ok(Step::Continue)
+}
+
+/// Executes 8XY5
+const fn sub<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    let (result, borrow) = machine.value(op.x).overflowing_sub(machine.value(op.y));
+    machine.set_value(op.x, result);
+    machine.set_value(0xF, !borrow as u8);
+    Ok(Step::Continue)
+}
+
+/// Executes 8XY6, shifting the register Y resolved by the quirks
+const fn shift_right<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    let source = machine.value(op.y);
+    machine.set_value(op.x, source >> 1);
+    machine.set_value(0xF, source & 1);
+    Ok(Step::Continue)
+}
+
+/// Executes 8XY7
+const fn sub_inverted<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    let (result, borrow) = machine.value(op.y).overflowing_sub(machine.value(op.x));
+    machine.set_value(op.x, result);
+    machine.set_value(0xF, !borrow as u8);
+    Ok(Step::Continue)
+}
+
+/// Executes 8XYE, shifting the register Y resolved by the quirks
+const fn shift_left<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    let source = machine.value(op.y);
+    machine.set_value(op.x, source << 1);
+    machine.set_value(0xF, source >> 7);
+    Ok(Step::Continue)
+}
+
+/// Executes 9XY0
+const fn skip_not_equal<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    let condition = machine.value(op.x) != machine.value(op.y);
+    skip(machine, op, condition)
+}
+
+/// Executes ANNN
+const fn load_i<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    *machine.registers.address_mut() = op.target;
+    Ok(Step::Continue)
+}
+
+/// Executes BNNN, offsetting by the register resolved by the quirks
+const fn jump_offset<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.program_counter = op.target + machine.value(op.x) as u16;
+    Ok(Step::Continue)
+}
+
+/// Executes CXNN
+fn random<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    let random = machine.rng.next_byte();
+    machine.set_value(op.x, random & op.byte);
+    Ok(Step::Continue)
+}
+
+/// Executes DXYN, clipping sprites if `CLIP` is set and waiting for the display if `WAIT` is set
+fn draw<const SIZE: usize, const CLIP: bool, const WAIT: bool>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.draw(op.x, op.y, op.byte, CLIP)?;
+    Ok(if WAIT {
+        Step::WaitingForDisplay
+    } else {
+        Step::Continue
+    })
+}
+
+/// Executes EX9E
+fn skip_pressed<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    let condition = machine.keypad.is_pressed(machine.value(op.x));
+    skip(machine, op, condition)
+}
+
+/// Executes EXA1
+fn skip_not_pressed<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    let condition = !machine.keypad.is_pressed(machine.value(op.x));
+    skip(machine, op, condition)
+}
+
+/// Executes FX07
+fn load_delay<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.set_value(op.x, machine.registers.delay());
+    Ok(Step::Continue)
+}
+
+/// Executes FX0A
+fn wait_for_key<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    Ok(machine.wait_for_key(op.address, op.x))
+}
+
+/// Executes FX15
+fn set_delay<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.registers.set_delay(machine.value(op.x));
+    Ok(Step::Continue)
+}
+
+/// Executes FX18
+fn set_sound_timer<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.registers.set_sound_timer(machine.value(op.x));
+    Ok(Step::Continue)
+}
+
+/// Executes FX1E
+fn add_address<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.add_address(op.x);
+    Ok(Step::Continue)
+}
+
+/// Executes FX29
+fn load_sprite_address<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    *machine.registers.address_mut() =
+        machine.memory.layout().small_font + u16::from(machine.value(op.x) & 0xF) * 5;
+    Ok(Step::Continue)
+}
+
+/// Executes FX33
+fn store_digits<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.store_digits(op.address, op.x)?;
+    Ok(Step::Continue)
+}
+
+/// Executes FX55, incrementing I by the increment resolved by the quirks
+fn store_registers<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.store_registers(op.address, op.x)?;
+    let address = machine.registers.address_mut();
+    *address = address.wrapping_add(op.target);
+    Ok(Step::Continue)
+}
+
+/// Executes FX65, incrementing I by the increment resolved by the quirks
+fn load_registers<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.load_registers(op.x)?;
+    let address = machine.registers.address_mut();
+    *address = address.wrapping_add(op.target);
+    Ok(Step::Continue)
+}
+
+/// Executes 00FD
+const fn exit<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+) -> Result<Step, MachineError> {
+    machine.program_counter = op.address;
+    Ok(Step::Exit)
+}
+
+/// Skips the next instruction if the condition holds
+const fn skip<const SIZE: usize>(
+    machine: &mut Machine<SIZE>,
+    op: &MicroOp<SIZE>,
+    condition: bool,
+) -> Result<Step, MachineError> {
+    match machine.skip_if(op.address, condition) {
+        Ok(()) => Ok(Step::Continue),
+        Err(error) => Err(error),
+    }
+}
+
+/// The compiled blocks of a memory
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BlockCache<const SIZE: usize> {
+    /// The code generation of the memory the blocks were compiled from
+    generation: u64,
+
+    /// The quirks the blocks were compiled for
+    quirks: Quirks,
+
+    /// The index of the block starting at every address plus one, 0 if there is none
+    starts: Vec<u32>,
+
+    /// The range of every block in the micro-operations
+    blocks: Vec<Range<usize>>,
+
+    /// The micro-operations of all blocks, one block after the other
+    ops: Vec<MicroOp<SIZE>>,
+}
+
+impl<const SIZE: usize> BlockCache<SIZE> {
+    /// Retrieves the range of the micro-operations of the block starting at the address,
+    /// compiling the block for the quirks if needed
+    fn block(
+        &mut self,
+        memory: &mut Memory<SIZE>,
+        quirks: Quirks,
+        address: u16,
+    ) -> Result<Range<usize>, MachineError> {
+        if self.generation != memory.code_generation()
+            || self.quirks != quirks
+            || self.starts.len() != memory.data().len()
+        {
+            self.quirks = quirks;
+            self.starts = vec![0; memory.data().len()];
+            self.blocks.clear();
+            self.ops.clear();
+        }
+        let start = usize::from(address);
+        match self.starts.get(start) {
+            Some(0) => {}
+            Some(&index) => return Ok(self.blocks[index as usize - 1].clone()),
+            None => return Err(MachineError::ProgramCounterOutOfBounds(address)),
+        }
+
+        let first = self.ops.len();
+        let mut next = address;
+        while self.ops.len() - first < MAX_BLOCK_LENGTH {
+            // The program counter isn't checked while running a block, so an instruction it
+            // can't advance past has to end the block before it
+            let decoded = Machine::<SIZE>::check_instruction(next, memory.decode(next)).and_then(
+                |instruction| {
+                    next.checked_add(2)
+                        .map(|following| (instruction, following))
+                        .ok_or(MachineError::ProgramCounterOutOfBounds(next))
+                },
+            );
+            let (instruction, following) = match decoded {
+                Ok(decoded) => decoded,
+                // Invalid instructions are reported once they are reached
+                Err(_) if self.ops.len() > first => break,
+                Err(error) => return Err(error),
+            };
+            self.ops.push(MicroOp::compile(next, instruction, quirks));
+            if ends_block(instruction) {
+                break;
+            }
+            next = following;
+        }
+
+        let length = self.ops.len() - first;
+        memory.mark_code(start..start + 2 * length);
+        self.generation = memory.code_generation();
+        self.blocks.push(first..self.ops.len());
+        self.starts[start] = self.blocks.len() as u32;
+        Ok(first..self.ops.len())
+    }
+}
+
+impl<const SIZE: usize> Machine<SIZE> {
+    /// Executes up to the given number of instructions block by block, running the compiled
+    /// micro-operations of a block without fetching them or checking the program counter.
+    /// Stops early if the machine waits for a key or the application exits.
+    pub(crate) fn run_blocks(&mut self, cycles: u32) -> Result<Step, MachineError> {
+        let mut remaining = cycles as usize;
+        while remaining > 0 {
+            let block = self
+                .blocks
+                .block(&mut self.memory, self.quirks, self.program_counter)?;
+            let length = block.len().min(remaining);
+            let generation = self.memory.code_generation();
+            let mut executed = length;
+            for (offset, index) in block.take(length).enumerate() {
+                let op = self.blocks.ops[index];
+                if let Some(coverage) = &mut self.coverage {
+                    coverage.execute(op.address);
+                }
+                self.program_counter = op.address + 2;
+                self.instruction_count += 1;
+                let step = (op.handler)(self, &op)?;
+                if step != Step::Continue {
+                    return Ok(step);
+                }
+
+                // The block may have overwritten itself, so it must be compiled again
+                if self.memory.code_generation() != generation {
+                    executed = offset + 1;
+                    break;
+                }
+            }
+            remaining -= executed;
+        }
+        Ok(Step::Continue)
+    }
+}
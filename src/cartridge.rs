@@ -0,0 +1,385 @@
+//! This module contains the import and export of Octo cartridges: GIF images with the program and
+//! its options hidden in the lowest two bits of every pixel.
+//!
+//! The hidden payload starts with its length as a big-endian 32-bit number, followed by a JSON
+//! object with the Octo source of the program and the options. Every byte is spread over four
+//! consecutive pixels, most significant bits first, continuing through all frames of the image.
+//!
+//! Octo cartridges contain source code, which is compiled by [`crate::octo`] when imported.
+//! Exported cartridges store applications as byte literals, which Octo compiles back to the same
+//! bytes.
+
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use serde_json::{Map, Value, json};
+
+use crate::{
+    display::{Display, HEIGHT, WIDTH},
+    font::Font,
+    machine::Machine,
+    octo::{CompileError, compile},
+    quirks::Quirks,
+    rom::{Rom, RomError},
+    screenshot::{Palette, parse_color, scaled_rows},
+};
+
+/// The width of cartridge images
+const CART_WIDTH: usize = 160;
+
+/// The height of cartridge images
+const CART_HEIGHT: usize = 128;
+
+/// The factor the display is scaled by in the label of exported cartridges
+const LABEL_SCALE: usize = 2;
+
+/// The delay between the frames of exported cartridges, in hundredths of a second
+const FRAME_DELAY: u16 = 10;
+
+/// The number of byte literals per line of exported sources
+const BYTES_PER_LINE: usize = 16;
+
+/// The fonts and their names in the font style option of Octo
+const FONT_STYLES: [(&str, Font); 5] = [
+    ("octo", Font::STANDARD),
+    ("vip", Font::VIP),
+    ("dream6800", Font::DREAM_6800),
+    ("eti660", Font::ETI_660),
+    ("fish", Font::FISH_N_CHIPS),
+];
+
+/// The behaviour of Octo if none of its quirk options are set
+const OCTO_QUIRKS: Quirks = Quirks {
+    vf_reset: false,
+    load_store_increments_i: true,
+    shift_uses_vy: true,
+    jump_uses_vx: false,
+    clip_sprites: false,
+    increment_i_by_x: false,
+    display_wait: false,
+};
+
+/// The error returned if a cartridge can't be imported
+#[derive(Debug)]
+pub enum CartridgeError {
+    /// The cartridge couldn't be read
+    Io(io::Error),
+
+    /// The image isn't a valid GIF
+    Gif(gif::DecodingError),
+
+    /// The image is too small to contain the payload its length announces
+    Truncated,
+
+    /// The payload isn't a valid JSON object with a program
+    InvalidPayload,
+
+    /// The source couldn't be compiled
+    Compile(CompileError),
+
+    /// The compiled program can't be used as an application
+    Rom(RomError),
+}
+
+impl std::fmt::Display for CartridgeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "Failed to read cartridge: {error}"),
+            Self::Gif(error) => write!(f, "Invalid cartridge image: {error}"),
+            Self::Truncated => write!(f, "Cartridge is truncated"),
+            Self::InvalidPayload => write!(f, "Cartridge doesn't contain a program"),
+            Self::Compile(error) => write!(f, "Failed to compile cartridge source: {error}"),
+            Self::Rom(error) => write!(f, "Invalid cartridge program: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for CartridgeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Gif(error) => Some(error),
+            Self::Compile(error) => Some(error),
+            Self::Rom(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CartridgeError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<gif::DecodingError> for CartridgeError {
+    fn from(error: gif::DecodingError) -> Self {
+        Self::Gif(error)
+    }
+}
+
+impl From<CompileError> for CartridgeError {
+    fn from(error: CompileError) -> Self {
+        Self::Compile(error)
+    }
+}
+
+impl From<RomError> for CartridgeError {
+    fn from(error: RomError) -> Self {
+        Self::Rom(error)
+    }
+}
+
+/// The settings stored in a cartridge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CartridgeOptions {
+    /// The number of instructions executed per frame
+    pub cycles_per_frame: u32,
+
+    /// The behaviour of ambiguous instructions
+    pub quirks: Quirks,
+
+    /// The colors of unlit and lit pixels
+    pub palette: Palette,
+
+    /// The font of the hexadecimal digits
+    pub font: Font,
+}
+
+impl Default for CartridgeOptions {
+    fn default() -> Self {
+        Self {
+            cycles_per_frame: crate::machine::DEFAULT_CYCLES_PER_FRAME,
+            quirks: Quirks::default(),
+            palette: Palette::default(),
+            font: Font::default(),
+        }
+    }
+}
+
+impl CartridgeOptions {
+    /// Reads the options from the JSON object of Octo, missing quirk options are off like in Octo
+    /// and other missing options keep their defaults
+    fn from_json(options: &Map<String, Value>) -> Self {
+        let mut result = Self {
+            quirks: OCTO_QUIRKS,
+            ..Self::default()
+        };
+        let flag = |name: &str| options.get(name).and_then(Value::as_bool);
+        let color = |name: &str| {
+            options
+                .get(name)
+                .and_then(Value::as_str)
+                .and_then(parse_color)
+        };
+
+        if let Some(tickrate) = options.get("tickrate").and_then(Value::as_u64) {
+            result.cycles_per_frame = u32::try_from(tickrate).unwrap_or(u32::MAX);
+        }
+        if let Some(shift) = flag("shiftQuirks") {
+            result.quirks.shift_uses_vy = !shift;
+        }
+        if let Some(load_store) = flag("loadStoreQuirks") {
+            result.quirks.load_store_increments_i = !load_store;
+        }
+        if let Some(clip) = flag("clipQuirks") {
+            result.quirks.clip_sprites = clip;
+        }
+        if let Some(jump) = flag("jumpQuirks") {
+            result.quirks.jump_uses_vx = jump;
+        }
+        if let Some(logic) = flag("logicQuirks") {
+            result.quirks.vf_reset = logic;
+        }
+        if let Some(vblank) = flag("vBlankQuirks") {
+            result.quirks.display_wait = vblank;
+        }
+        if let Some(background) = color("backgroundColor") {
+            result.palette.background = background;
+        }
+        if let Some(foreground) = color("fillColor") {
+            result.palette.foreground = foreground;
+        }
+        if let Some((_, font)) = options
+            .get("fontStyle")
+            .and_then(Value::as_str)
+            .and_then(|style| FONT_STYLES.iter().find(|(name, _)| *name == style))
+        {
+            result.font = font.clone();
+        }
+        result
+    }
+
+    /// Converts the options to the JSON object of Octo
+    fn to_json(&self) -> Value {
+        let color = |[red, green, blue]: [u8; 3]| format!("#{red:02X}{green:02X}{blue:02X}");
+        let mut options = json!({
+            "tickrate": self.cycles_per_frame,
+            "backgroundColor": color(self.palette.background),
+            "fillColor": color(self.palette.foreground),
+            "fillColor2": color(self.palette.foreground),
+            "blendColor": color(self.palette.foreground),
+            "shiftQuirks": !self.quirks.shift_uses_vy,
+            "loadStoreQuirks": !self.quirks.load_store_increments_i,
+            "clipQuirks": self.quirks.clip_sprites,
+            "jumpQuirks": self.quirks.jump_uses_vx,
+            "logicQuirks": self.quirks.vf_reset,
+            "vBlankQuirks": self.quirks.display_wait,
+        });
+        if let Some((name, _)) = FONT_STYLES.iter().find(|(_, font)| *font == self.font) {
+            options["fontStyle"] = json!(name);
+        }
+        options
+    }
+
+    /// Applies the quirks and font to the machine, the tick rate and colors are up to the frontend
+    pub fn apply(&self, machine: &mut Machine) {
+        machine.set_quirks(self.quirks);
+        machine.set_font(self.font.clone());
+    }
+}
+
+/// Converts an application to an Octo source of byte literals
+fn decompile(program: &[u8]) -> String {
+    let mut source = String::from(": main\n");
+    for line in program.chunks(BYTES_PER_LINE) {
+        let bytes = line
+            .iter()
+            .map(|byte| format!("0x{byte:02X}"))
+            .collect::<Vec<_>>();
+        let _ = writeln!(source, "\t{}", bytes.join(" "));
+    }
+    source
+}
+
+/// An application and its settings, as shared by Octo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cartridge {
+    /// The application
+    pub rom: Rom,
+
+    /// The settings of the application
+    pub options: CartridgeOptions,
+}
+
+impl Cartridge {
+    /// Creates a cartridge of an application and its settings
+    pub const fn new(rom: Rom, options: CartridgeOptions) -> Self {
+        Self { rom, options }
+    }
+
+    /// Reads a cartridge from a GIF image
+    pub fn from_reader(reader: impl Read) -> Result<Self, CartridgeError> {
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = options.read_info(reader)?;
+        let mut pixels = Vec::new();
+        while let Some(frame) = decoder.read_next_frame()? {
+            pixels.extend_from_slice(&frame.buffer);
+        }
+
+        let bytes = pixels
+            .chunks_exact(4)
+            .map(|pixels| pixels.iter().fold(0, |byte, pixel| byte << 2 | pixel & 3))
+            .collect::<Vec<u8>>();
+        let (length, payload) = bytes
+            .split_first_chunk::<4>()
+            .ok_or(CartridgeError::Truncated)?;
+        let payload = payload
+            .get(..u32::from_be_bytes(*length) as usize)
+            .ok_or(CartridgeError::Truncated)?;
+
+        let payload =
+            serde_json::from_slice::<Value>(payload).map_err(|_| CartridgeError::InvalidPayload)?;
+        let source = payload
+            .get("program")
+            .and_then(Value::as_str)
+            .ok_or(CartridgeError::InvalidPayload)?;
+        let options = match payload.get("options") {
+            Some(Value::Object(options)) => CartridgeOptions::from_json(options),
+            _ => CartridgeOptions::default(),
+        };
+        Ok(Self {
+            rom: Rom::from_bytes(compile(source)?)?,
+            options,
+        })
+    }
+
+    /// Reads a cartridge from a GIF file
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, CartridgeError> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Retrieves whether the path has the extension of cartridges
+    pub fn is_cartridge_path(path: &Path) -> bool {
+        path.extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("gif"))
+    }
+
+    /// Writes the cartridge as a GIF image, showing the display as its label
+    pub fn write(&self, label: &Display, writer: impl Write) -> io::Result<()> {
+        let payload = json!({
+            "options": self.options.to_json(),
+            "program": decompile(self.rom.data()),
+        })
+        .to_string();
+        let length = u32::try_from(payload.len()).map_err(io::Error::other)?;
+        let data = [&length.to_be_bytes(), payload.as_bytes()].concat();
+
+        // Every base color is repeated for all values of the hidden bits
+        let palette = [
+            self.options.palette.background,
+            self.options.palette.foreground,
+        ]
+        .iter()
+        .flat_map(|color| [*color; 4])
+        .flatten()
+        .collect::<Vec<_>>();
+        let mut encoder =
+            gif::Encoder::new(writer, CART_WIDTH as u16, CART_HEIGHT as u16, &palette)
+                .map_err(io::Error::other)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(io::Error::other)?;
+
+        let mut image = vec![0; CART_WIDTH * CART_HEIGHT];
+        let (left, top) = (
+            (CART_WIDTH - WIDTH * LABEL_SCALE) / 2,
+            (CART_HEIGHT - HEIGHT * LABEL_SCALE) / 2,
+        );
+        for (y, row) in scaled_rows(label, LABEL_SCALE).enumerate() {
+            for (x, lit) in row.into_iter().enumerate() {
+                image[(top + y) * CART_WIDTH + left + x] = u8::from(lit) << 2;
+            }
+        }
+
+        let bits = data
+            .iter()
+            .flat_map(|byte| [6, 4, 2, 0].map(|shift| byte >> shift & 3))
+            .collect::<Vec<_>>();
+        for chunk in bits.chunks(image.len()) {
+            let mut pixels = image.clone();
+            for (pixel, bits) in pixels.iter_mut().zip(chunk) {
+                *pixel |= bits;
+            }
+            let mut frame = gif::Frame::from_indexed_pixels(
+                CART_WIDTH as u16,
+                CART_HEIGHT as u16,
+                pixels,
+                None,
+            );
+            frame.delay = FRAME_DELAY;
+            encoder.write_frame(&frame).map_err(io::Error::other)?;
+        }
+        encoder.into_inner().map_err(io::Error::other)?.flush()
+    }
+
+    /// Saves the cartridge as a GIF file, showing the display as its label
+    pub fn save(&self, label: &Display, path: impl AsRef<Path>) -> io::Result<()> {
+        self.write(label, BufWriter::new(File::create(path)?))
+    }
+}
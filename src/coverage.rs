@@ -0,0 +1,255 @@
+//! This module contains the coverage map, which tracks how every memory address was accessed.
+//!
+//! The map separates code from data, shows which parts of an application were never reached and
+//! detects self-modifying code: instructions that are overwritten after being executed.
+
+use std::{
+    io::{self, Write},
+    ops::Range,
+};
+
+#[cfg(feature = "serde")]
+use serde_json::{Value, json};
+
+use crate::memory::Memory;
+
+/// The access flag of bytes executed as part of an instruction
+pub const EXECUTED: u8 = 1 << 0;
+
+/// The access flag of bytes read as data: sprites and loaded registers
+pub const READ: u8 = 1 << 1;
+
+/// The access flag of bytes written by the application
+pub const WRITTEN: u8 = 1 << 2;
+
+/// The access flag of executed bytes that were written afterwards
+pub const SELF_MODIFIED: u8 = 1 << 3;
+
+/// The most notable access of an address, which decides its color in overlays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// Executed, then written
+    SelfModified,
+
+    /// Written, but not executed before
+    Written,
+
+    /// Executed as part of an instruction
+    Executed,
+
+    /// Read as data
+    Read,
+
+    /// Never accessed
+    Unused,
+}
+
+impl Access {
+    /// All accesses, from the most to the least notable
+    pub const ALL: [Self; 5] = [
+        Self::SelfModified,
+        Self::Written,
+        Self::Executed,
+        Self::Read,
+        Self::Unused,
+    ];
+
+    /// Retrieves the most notable access of the access flags
+    pub const fn from_flags(flags: u8) -> Self {
+        if flags & SELF_MODIFIED != 0 {
+            Self::SelfModified
+        } else if flags & WRITTEN != 0 {
+            Self::Written
+        } else if flags & EXECUTED != 0 {
+            Self::Executed
+        } else if flags & READ != 0 {
+            Self::Read
+        } else {
+            Self::Unused
+        }
+    }
+
+    /// Retrieves the name of the access, as shown in the legend of overlays
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::SelfModified => "self modified",
+            Self::Written => "written",
+            Self::Executed => "executed",
+            Self::Read => "read",
+            Self::Unused => "unused",
+        }
+    }
+
+    /// Retrieves the ANSI escape code parameters of the color of the access
+    const fn ansi_color(self) -> &'static str {
+        match self {
+            Self::SelfModified => "31",
+            Self::Written => "33",
+            Self::Executed => "32",
+            Self::Read => "36",
+            Self::Unused => "2",
+        }
+    }
+}
+
+/// A write to a byte that was previously executed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelfModification {
+    /// The address of the overwritten byte
+    pub address: u16,
+
+    /// The address of the instruction that wrote it
+    pub instruction: u16,
+}
+
+/// The accesses of every memory address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    /// The access flags of every address
+    access: Vec<u8>,
+
+    /// The first write to every executed byte, in the order they happened
+    self_modifications: Vec<SelfModification>,
+}
+
+impl Coverage {
+    /// Creates an empty coverage map of memory of the given size
+    pub fn new(size: usize) -> Self {
+        Self {
+            access: vec![0; size],
+            self_modifications: Vec::new(),
+        }
+    }
+
+    /// Retrieves the access flags of an address, 0 if it wasn't accessed or doesn't exist
+    pub fn access(&self, address: u16) -> u8 {
+        self.access.get(usize::from(address)).copied().unwrap_or(0)
+    }
+
+    /// Retrieves the access flags of all addresses
+    pub fn accesses(&self) -> &[u8] {
+        &self.access
+    }
+
+    /// Retrieves the writes to executed bytes, in the order they happened
+    pub fn self_modifications(&self) -> &[SelfModification] {
+        &self.self_modifications
+    }
+
+    /// Retrieves the number of addresses with the access flag
+    pub fn count(&self, flag: u8) -> usize {
+        self.access
+            .iter()
+            .filter(|&&access| access & flag != 0)
+            .count()
+    }
+
+    /// Retrieves the ranges of consecutive addresses with the access flag
+    pub fn ranges(&self, flag: u8) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for (address, &access) in self.access.iter().enumerate() {
+            if access & flag == 0 {
+                continue;
+            }
+            match ranges.last_mut() {
+                Some(range) if range.end == address => range.end += 1,
+                _ => ranges.push(address..address + 1),
+            }
+        }
+        ranges
+    }
+
+    /// Forgets all accesses
+    pub fn clear(&mut self) {
+        self.access.fill(0);
+        self.self_modifications.clear();
+    }
+
+    /// Marks both bytes of the instruction at the address as executed
+    pub(crate) fn execute(&mut self, address: u16) {
+        for address in [address, address.wrapping_add(1)] {
+            if let Some(access) = self.access.get_mut(usize::from(address)) {
+                *access |= EXECUTED;
+            }
+        }
+    }
+
+    /// Marks the addresses as read as data
+    pub(crate) fn read(&mut self, range: Range<usize>) {
+        let range = range.start..range.end.min(self.access.len());
+        if let Some(access) = self.access.get_mut(range) {
+            access.iter_mut().for_each(|access| *access |= READ);
+        }
+    }
+
+    /// Marks the address as written by the instruction at the given address, flagging it as self
+    /// modifying code if it was executed before
+    pub(crate) fn write(&mut self, address: u16, instruction: u16) {
+        let Some(access) = self.access.get_mut(usize::from(address)) else {
+            return;
+        };
+        *access |= WRITTEN;
+        if *access & (EXECUTED | SELF_MODIFIED) == EXECUTED {
+            *access |= SELF_MODIFIED;
+            self.self_modifications.push(SelfModification {
+                address,
+                instruction,
+            });
+        }
+    }
+
+    /// Converts the coverage map to JSON: the ranges of every kind of access as pairs of start
+    /// and exclusive end addresses, and the self modifications
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Value {
+        let ranges = |flag| {
+            self.ranges(flag)
+                .into_iter()
+                .map(|range| [range.start, range.end])
+                .collect::<Vec<_>>()
+        };
+        let modifications = self
+            .self_modifications
+            .iter()
+            .map(|modification| {
+                json!({
+                    "address": modification.address,
+                    "instruction": modification.instruction,
+                })
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "size": self.access.len(),
+            "executed": ranges(EXECUTED),
+            "read": ranges(READ),
+            "written": ranges(WRITTEN),
+            "self_modified": ranges(SELF_MODIFIED),
+            "self_modifications": modifications,
+        })
+    }
+
+    /// Writes the coverage map as JSON, see [`Coverage::to_json`]
+    #[cfg(feature = "serde")]
+    pub fn write_json(&self, mut writer: impl Write) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut writer, &self.to_json())?;
+        writeln!(writer)
+    }
+
+    /// Writes a hexadecimal dump of memory, colored by access using ANSI escape codes: red for
+    /// self modified code, yellow for written, green for executed and cyan for read bytes
+    pub fn write_overlay(&self, memory: &Memory, mut writer: impl Write) -> io::Result<()> {
+        let legend = Access::ALL
+            .map(|access| format!("\x1b[{}m{}\x1b[0m", access.ansi_color(), access.name()));
+        writeln!(writer, "{}", legend.join(" "))?;
+        for (row, bytes) in memory.data().chunks(16).enumerate() {
+            write!(writer, "{:03X}:", row * 16)?;
+            for (column, byte) in bytes.iter().enumerate() {
+                let access =
+                    Access::from_flags(self.access.get(row * 16 + column).copied().unwrap_or(0));
+                write!(writer, " \x1b[{}m{byte:02X}\x1b[0m", access.ansi_color())?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+}
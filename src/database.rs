@@ -0,0 +1,311 @@
+//! This module contains the database of known applications and the settings they need to run
+//! correctly, keyed by the SHA-1 hash of the application.
+//!
+//! The database uses the format of the `programs.json` file of the community chip-8 database, so
+//! that file can be loaded in place of the small built-in database. The built-in database only
+//! describes the applications shipped with the emulator: the platforms their recorded sessions
+//! play back identically on and the tick rate they were recorded at.
+
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Arc, OnceLock},
+};
+
+use serde::Deserialize;
+
+use crate::{
+    quirks::Quirks,
+    screenshot::{Palette, parse_color},
+};
+
+/// The built-in database, in the format of the community chip-8 database
+const BUILTIN: &str = include_str!("../data/programs.json");
+
+/// The error returned if a database can't be read
+#[derive(Debug)]
+pub enum DatabaseError {
+    /// The database couldn't be read
+    Io(std::io::Error),
+
+    /// The database isn't valid
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for DatabaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "Failed to read database: {error}"),
+            Self::Json(error) => write!(f, "Invalid database: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for DatabaseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Json(error) => Some(error),
+        }
+    }
+}
+
+impl From<std::io::Error> for DatabaseError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for DatabaseError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error)
+    }
+}
+
+/// The quirks an application needs that differ from its platform, as named by the database
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QuirkOverrides {
+    /// Whether shifts shift VX in place instead of VY
+    shift: Option<bool>,
+
+    /// Whether loading and storing registers increments I by x instead of x + 1
+    memory_increment_by_x: Option<bool>,
+
+    /// Whether loading and storing registers leaves I unchanged
+    memory_leave_i_unchanged: Option<bool>,
+
+    /// Whether sprites wrap around the edges of the display instead of being clipped
+    wrap: Option<bool>,
+
+    /// Whether the jump with offset uses VX instead of V0
+    jump: Option<bool>,
+
+    /// Whether drawing waits for the display to refresh
+    vblank: Option<bool>,
+
+    /// Whether logical instructions reset VF
+    logic: Option<bool>,
+}
+
+impl QuirkOverrides {
+    /// Applies the overrides to the quirks of the platform
+    fn apply(&self, mut quirks: Quirks) -> Quirks {
+        if let Some(shift) = self.shift {
+            quirks.shift_uses_vy = !shift;
+        }
+        if let Some(by_x) = self.memory_increment_by_x {
+            quirks.increment_i_by_x = by_x;
+            quirks.load_store_increments_i |= by_x;
+        }
+        if let Some(unchanged) = self.memory_leave_i_unchanged {
+            quirks.load_store_increments_i = !unchanged;
+        }
+        if let Some(wrap) = self.wrap {
+            quirks.clip_sprites = !wrap;
+        }
+        if let Some(jump) = self.jump {
+            quirks.jump_uses_vx = jump;
+        }
+        if let Some(vblank) = self.vblank {
+            quirks.display_wait = vblank;
+        }
+        if let Some(logic) = self.logic {
+            quirks.vf_reset = logic;
+        }
+        quirks
+    }
+}
+
+/// The colors an application was designed for
+#[derive(Debug, Clone, Default, Deserialize)]
+struct Colors {
+    /// The colors of the pixels as `#rrggbb`, starting with unlit pixels
+    #[serde(default)]
+    pixels: Vec<String>,
+}
+
+/// A single version of an application
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RomEntry {
+    /// The platforms the application runs on, the preferred one first
+    #[serde(default)]
+    platforms: Vec<String>,
+
+    /// The quirks that differ from the platforms
+    #[serde(default)]
+    quirky_platforms: HashMap<String, QuirkOverrides>,
+
+    /// The number of instructions executed per frame
+    tickrate: Option<u32>,
+
+    /// The chip-8 keys of the buttons of the application, such as up, down, left, right and a
+    #[serde(default)]
+    keys: HashMap<String, u8>,
+
+    /// The colors of the application
+    colors: Option<Colors>,
+}
+
+/// An application and all its known versions
+#[derive(Debug, Clone, Deserialize)]
+struct Program {
+    /// The title of the application
+    title: String,
+
+    /// The authors of the application
+    #[serde(default)]
+    authors: Vec<String>,
+
+    /// The versions of the application, by their SHA-1 hash as lowercase hexadecimal digits
+    #[serde(default)]
+    roms: HashMap<String, RomEntry>,
+}
+
+/// The description and recommended settings of a known application
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameInfo {
+    /// The title of the application
+    pub title: String,
+
+    /// The authors of the application
+    pub authors: Vec<String>,
+
+    /// The database name of the platform the settings are for, None if no platform is supported
+    pub platform: Option<String>,
+
+    /// The quirks the application needs, None if no platform is supported
+    pub quirks: Option<Quirks>,
+
+    /// The number of instructions to execute per frame, None for the default
+    pub cycles_per_frame: Option<u32>,
+
+    /// The chip-8 keys of the buttons of the application, sorted by the name of the button
+    pub keys: Vec<(String, u8)>,
+
+    /// The colors of the application, None for the default
+    pub palette: Option<Palette>,
+}
+
+/// Retrieves the quirks of a platform of the database, as defined by its `platforms.json`, None
+/// if it isn't supported
+fn platform_quirks(platform: &str) -> Option<Quirks> {
+    match platform {
+        "originalChip8" | "hybridVIP" => Some(Quirks::VIP),
+        "modernChip8" => Some(Quirks {
+            vf_reset: false,
+            display_wait: false,
+            ..Quirks::VIP
+        }),
+        "chip48" | "superchip1" => Some(Quirks {
+            load_store_increments_i: true,
+            increment_i_by_x: true,
+            ..Quirks::SCHIP
+        }),
+        "superchip" => Some(Quirks::SCHIP),
+        "xochip" => Some(Quirks::XO_CHIP),
+        _ => None,
+    }
+}
+
+/// A database of known applications
+#[derive(Debug, Clone)]
+pub struct Database {
+    /// The applications
+    programs: Vec<Program>,
+
+    /// The index of the application of every SHA-1 hash
+    hashes: HashMap<String, usize>,
+}
+
+impl Database {
+    /// Parses a database in the format of `programs.json` of the community chip-8 database
+    pub fn from_json(json: &str) -> Result<Self, DatabaseError> {
+        let programs = serde_json::from_str::<Vec<Program>>(json)?;
+        let hashes = programs
+            .iter()
+            .enumerate()
+            .flat_map(|(index, program)| {
+                program
+                    .roms
+                    .keys()
+                    .map(move |hash| (hash.to_ascii_lowercase(), index))
+            })
+            .collect();
+        Ok(Self { programs, hashes })
+    }
+
+    /// Reads a database from a file, see [`Database::from_json`] for the format
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, DatabaseError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Retrieves the database built into the emulator
+    pub fn builtin() -> Arc<Self> {
+        /// The parsed built-in database, shared by all machines
+        static DATABASE: OnceLock<Arc<Database>> = OnceLock::new();
+        DATABASE
+            .get_or_init(|| Arc::new(Self::from_json(BUILTIN).expect("Invalid built-in database")))
+            .clone()
+    }
+
+    /// Retrieves the number of known versions of applications
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Retrieves whether the database doesn't contain any application
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Retrieves the description and settings of the application with the SHA-1 hash, None if it
+    /// is unknown
+    pub fn lookup(&self, sha1: [u8; 20]) -> Option<GameInfo> {
+        let hash = sha1
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+        let program = &self.programs[*self.hashes.get(&hash)?];
+        let rom = program
+            .roms
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(&hash))
+            .map(|(_, rom)| rom)?;
+
+        let platform = rom
+            .platforms
+            .iter()
+            .find(|platform| platform_quirks(platform).is_some());
+        let quirks = platform.and_then(|platform| {
+            let quirks = platform_quirks(platform)?;
+            Some(match rom.quirky_platforms.get(platform) {
+                Some(overrides) => overrides.apply(quirks),
+                None => quirks,
+            })
+        });
+        let mut keys = rom
+            .keys
+            .iter()
+            .map(|(button, &key)| (button.clone(), key & 0xF))
+            .collect::<Vec<_>>();
+        keys.sort();
+        let palette = rom.colors.as_ref().and_then(|colors| {
+            Some(Palette {
+                background: parse_color(colors.pixels.first()?)?,
+                foreground: parse_color(colors.pixels.get(1)?)?,
+            })
+        });
+
+        Some(GameInfo {
+            title: program.title.clone(),
+            authors: program.authors.clone(),
+            platform: platform.cloned(),
+            quirks,
+            cycles_per_frame: rom.tickrate,
+            keys,
+            palette,
+        })
+    }
+}
@@ -0,0 +1,101 @@
+//! This module contains the implementation of the chip-8 display
+
+/// The width of the display in pixels
+pub const WIDTH: usize = 64;
+
+/// The height of the display in pixels
+pub const HEIGHT: usize = 32;
+
+/// A row of pixels, the leftmost pixel in the most significant bit
+pub type Row = u64;
+
+/// The monochrome framebuffer of the chip-8
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Display {
+    /// The rows of pixels, from top to bottom
+    rows: [Row; HEIGHT],
+}
+
+impl Default for Display {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display {
+    /// Creates a new, cleared display
+    pub const fn new() -> Self {
+        Self { rows: [0; HEIGHT] }
+    }
+
+    /// Turns off all pixels
+    pub fn clear(&mut self) {
+        self.rows.fill(0);
+    }
+
+    /// Retrieves whether the pixel at the position is lit, None if it's outside the display
+    pub const fn pixel(&self, x: usize, y: usize) -> Option<bool> {
+        if x < WIDTH && y < HEIGHT {
+            Some(self.rows[y] >> (WIDTH - 1 - x) & 1 == 1)
+        } else {
+            None
+        }
+    }
+
+    /// Iterates over whether each pixel is lit, row by row
+    pub fn pixels(&self) -> impl Iterator<Item = bool> + '_ {
+        self.rows
+            .iter()
+            .flat_map(|&row| (0..WIDTH).map(move |x| row >> (WIDTH - 1 - x) & 1 == 1))
+    }
+
+    /// Retrieves the rows of pixels, from top to bottom
+    pub const fn rows(&self) -> &[Row; HEIGHT] {
+        &self.rows
+    }
+
+    /// Draws a sprite by xoring its rows with the display, starting at the given position.
+    /// The starting position always wraps around, the rest of the sprite is either clipped or
+    /// wrapped depending on `clip`.
+    /// Returns whether any lit pixel was turned off.
+    pub fn draw_sprite(&mut self, x: usize, y: usize, sprite: &[u8], clip: bool) -> bool {
+        let (x, y) = (x % WIDTH, y % HEIGHT);
+        let shift = WIDTH as u32 - 8;
+        let mut collision = false;
+        for (offset, &byte) in sprite.iter().enumerate() {
+            if clip && y + offset >= HEIGHT {
+                break;
+            }
+            // Shifting clips the sprite at the right edge, rotating wraps it around
+            let line = Row::from(byte) << shift;
+            let line = if clip {
+                line >> x
+            } else {
+                line.rotate_right(x as u32)
+            };
+            let row = &mut self.rows[(y + offset) % HEIGHT];
+            collision |= *row & line != 0;
+            *row ^= line;
+        }
+        collision
+    }
+
+    /// Packs the pixels into bytes, 8 pixels per byte with the leftmost pixel in the most
+    /// significant bit.
+    pub fn to_bytes(&self) -> [u8; WIDTH * HEIGHT / 8] {
+        let mut bytes = [0; WIDTH * HEIGHT / 8];
+        for (chunk, row) in bytes.chunks_exact_mut(WIDTH / 8).zip(&self.rows) {
+            chunk.copy_from_slice(&row.to_be_bytes());
+        }
+        bytes
+    }
+
+    /// Recreates a display from pixels packed by [`Display::to_bytes`]
+    pub fn from_bytes(bytes: &[u8; WIDTH * HEIGHT / 8]) -> Self {
+        let mut display = Self::new();
+        for (row, chunk) in display.rows.iter_mut().zip(bytes.chunks_exact(WIDTH / 8)) {
+            *row = Row::from_be_bytes(chunk.try_into().expect("Rows are 8 bytes"));
+        }
+        display
+    }
+}
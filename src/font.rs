@@ -0,0 +1,835 @@
+//! This module contains the hexadecimal fonts applications can draw using the font sprite
+//! instruction
+
+#[cfg(feature = "std")]
+use std::{fs::File, io::Read, path::Path};
+
+/// The number of bytes of every small 4x5 glyph
+pub const SMALL_GLYPH_SIZE: usize = 5;
+
+/// The number of bytes of every large 8x10 glyph
+pub const LARGE_GLYPH_SIZE: usize = 10;
+
+/// The number of bytes of a small font
+pub const SMALL_FONT_SIZE: usize = 16 * SMALL_GLYPH_SIZE;
+
+/// The number of bytes of a large font
+pub const LARGE_FONT_SIZE: usize = 16 * LARGE_GLYPH_SIZE;
+
+/// The small glyphs used by most modern interpreters
+const STANDARD_SMALL: [[u8; SMALL_GLYPH_SIZE]; 16] = [
+    // 0
+    [
+        0b1111_0000,
+        0b1001_0000,
+        0b1001_0000,
+        0b1001_0000,
+        0b1111_0000,
+    ],
+    // 1
+    [
+        0b0010_0000,
+        0b0110_0000,
+        0b0010_0000,
+        0b0010_0000,
+        0b0111_0000,
+    ],
+    // 2
+    [
+        0b1111_0000,
+        0b0001_0000,
+        0b1111_0000,
+        0b1000_0000,
+        0b1111_0000,
+    ],
+    // 3
+    [
+        0b1111_0000,
+        0b0001_0000,
+        0b1111_0000,
+        0b0001_0000,
+        0b1111_0000,
+    ],
+    // 4
+    [
+        0b1001_0000,
+        0b1001_0000,
+        0b1111_0000,
+        0b0001_0000,
+        0b0001_0000,
+    ],
+    // 5
+    [
+        0b1111_0000,
+        0b1000_0000,
+        0b1111_0000,
+        0b0001_0000,
+        0b1111_0000,
+    ],
+    // 6
+    [
+        0b1111_0000,
+        0b1000_0000,
+        0b1111_0000,
+        0b1001_0000,
+        0b1111_0000,
+    ],
+    // 7
+    [
+        0b1111_0000,
+        0b0001_0000,
+        0b0010_0000,
+        0b0100_0000,
+        0b0100_0000,
+    ],
+    // 8
+    [
+        0b1111_0000,
+        0b1001_0000,
+        0b1111_0000,
+        0b1001_0000,
+        0b1111_0000,
+    ],
+    // 9
+    [
+        0b1111_0000,
+        0b1001_0000,
+        0b1111_0000,
+        0b0001_0000,
+        0b1111_0000,
+    ],
+    // A
+    [
+        0b1111_0000,
+        0b1001_0000,
+        0b1111_0000,
+        0b1001_0000,
+        0b1001_0000,
+    ],
+    // B
+    [
+        0b1110_0000,
+        0b1001_0000,
+        0b1110_0000,
+        0b1001_0000,
+        0b1110_0000,
+    ],
+    // C
+    [
+        0b1111_0000,
+        0b1000_0000,
+        0b1000_0000,
+        0b1000_0000,
+        0b1111_0000,
+    ],
+    // D
+    [
+        0b1110_0000,
+        0b1001_0000,
+        0b1001_0000,
+        0b1001_0000,
+        0b1110_0000,
+    ],
+    // E
+    [
+        0b1111_0000,
+        0b1000_0000,
+        0b1111_0000,
+        0b1000_0000,
+        0b1111_0000,
+    ],
+    // F
+    [
+        0b1111_0000,
+        0b1000_0000,
+        0b1111_0000,
+        0b1000_0000,
+        0b1000_0000,
+    ],
+];
+
+/// The small glyphs of the COSMAC VIP interpreter, with its narrow 1 and open 4
+const VIP_SMALL: [[u8; SMALL_GLYPH_SIZE]; 16] = [
+    // 0
+    [
+        0b1111_0000,
+        0b1001_0000,
+        0b1001_0000,
+        0b1001_0000,
+        0b1111_0000,
+    ],
+    // 1
+    [
+        0b0110_0000,
+        0b0010_0000,
+        0b0010_0000,
+        0b0010_0000,
+        0b0111_0000,
+    ],
+    // 2
+    [
+        0b1111_0000,
+        0b0001_0000,
+        0b1111_0000,
+        0b1000_0000,
+        0b1111_0000,
+    ],
+    // 3
+    [
+        0b1111_0000,
+        0b0001_0000,
+        0b1111_0000,
+        0b0001_0000,
+        0b1111_0000,
+    ],
+    // 4
+    [
+        0b1010_0000,
+        0b1010_0000,
+        0b1111_0000,
+        0b0010_0000,
+        0b0010_0000,
+    ],
+    // 5
+    [
+        0b1111_0000,
+        0b1000_0000,
+        0b1111_0000,
+        0b0001_0000,
+        0b1111_0000,
+    ],
+    // 6
+    [
+        0b1111_0000,
+        0b1000_0000,
+        0b1111_0000,
+        0b1001_0000,
+        0b1111_0000,
+    ],
+    // 7
+    [
+        0b1111_0000,
+        0b0001_0000,
+        0b0001_0000,
+        0b0001_0000,
+        0b0001_0000,
+    ],
+    // 8
+    [
+        0b1111_0000,
+        0b1001_0000,
+        0b1111_0000,
+        0b1001_0000,
+        0b1111_0000,
+    ],
+    // 9
+    [
+        0b1111_0000,
+        0b1001_0000,
+        0b1111_0000,
+        0b0001_0000,
+        0b1111_0000,
+    ],
+    // A
+    [
+        0b1111_0000,
+        0b1001_0000,
+        0b1111_0000,
+        0b1001_0000,
+        0b1001_0000,
+    ],
+    // B
+    [
+        0b1111_0000,
+        0b0101_0000,
+        0b0111_0000,
+        0b0101_0000,
+        0b1111_0000,
+    ],
+    // C
+    [
+        0b1111_0000,
+        0b1000_0000,
+        0b1000_0000,
+        0b1000_0000,
+        0b1111_0000,
+    ],
+    // D
+    [
+        0b1111_0000,
+        0b0101_0000,
+        0b0101_0000,
+        0b0101_0000,
+        0b1111_0000,
+    ],
+    // E
+    [
+        0b1111_0000,
+        0b1000_0000,
+        0b1111_0000,
+        0b1000_0000,
+        0b1111_0000,
+    ],
+    // F
+    [
+        0b1111_0000,
+        0b1000_0000,
+        0b1111_0000,
+        0b1000_0000,
+        0b1000_0000,
+    ],
+];
+
+/// The small glyphs of the DREAM 6800 interpreter, only 3 pixels wide
+const DREAM_6800_SMALL: [[u8; SMALL_GLYPH_SIZE]; 16] = [
+    // 0
+    [
+        0b1110_0000,
+        0b1010_0000,
+        0b1010_0000,
+        0b1010_0000,
+        0b1110_0000,
+    ],
+    // 1
+    [
+        0b0100_0000,
+        0b0100_0000,
+        0b0100_0000,
+        0b0100_0000,
+        0b0100_0000,
+    ],
+    // 2
+    [
+        0b1110_0000,
+        0b0010_0000,
+        0b1110_0000,
+        0b1000_0000,
+        0b1110_0000,
+    ],
+    // 3
+    [
+        0b1110_0000,
+        0b0010_0000,
+        0b1110_0000,
+        0b0010_0000,
+        0b1110_0000,
+    ],
+    // 4
+    [
+        0b1000_0000,
+        0b1010_0000,
+        0b1010_0000,
+        0b1110_0000,
+        0b0010_0000,
+    ],
+    // 5
+    [
+        0b1110_0000,
+        0b1000_0000,
+        0b1110_0000,
+        0b0010_0000,
+        0b1110_0000,
+    ],
+    // 6
+    [
+        0b1110_0000,
+        0b1000_0000,
+        0b1110_0000,
+        0b1010_0000,
+        0b1110_0000,
+    ],
+    // 7
+    [
+        0b1110_0000,
+        0b0010_0000,
+        0b0010_0000,
+        0b0010_0000,
+        0b0010_0000,
+    ],
+    // 8
+    [
+        0b1110_0000,
+        0b1010_0000,
+        0b1110_0000,
+        0b1010_0000,
+        0b1110_0000,
+    ],
+    // 9
+    [
+        0b1110_0000,
+        0b1010_0000,
+        0b1110_0000,
+        0b0010_0000,
+        0b1110_0000,
+    ],
+    // A
+    [
+        0b1110_0000,
+        0b1010_0000,
+        0b1110_0000,
+        0b1010_0000,
+        0b1010_0000,
+    ],
+    // B
+    [
+        0b1100_0000,
+        0b1010_0000,
+        0b1110_0000,
+        0b1010_0000,
+        0b1100_0000,
+    ],
+    // C
+    [
+        0b1110_0000,
+        0b1000_0000,
+        0b1000_0000,
+        0b1000_0000,
+        0b1110_0000,
+    ],
+    // D
+    [
+        0b1100_0000,
+        0b1010_0000,
+        0b1010_0000,
+        0b1010_0000,
+        0b1100_0000,
+    ],
+    // E
+    [
+        0b1110_0000,
+        0b1000_0000,
+        0b1110_0000,
+        0b1000_0000,
+        0b1110_0000,
+    ],
+    // F
+    [
+        0b1110_0000,
+        0b1000_0000,
+        0b1100_0000,
+        0b1000_0000,
+        0b1000_0000,
+    ],
+];
+
+/// The small glyphs of the ETI-660 interpreter, only 3 pixels wide with lowercase b and d
+const ETI_660_SMALL: [[u8; SMALL_GLYPH_SIZE]; 16] = [
+    // 0
+    [
+        0b1110_0000,
+        0b1010_0000,
+        0b1010_0000,
+        0b1010_0000,
+        0b1110_0000,
+    ],
+    // 1
+    [
+        0b0010_0000,
+        0b0010_0000,
+        0b0010_0000,
+        0b0010_0000,
+        0b0010_0000,
+    ],
+    // 2
+    [
+        0b1110_0000,
+        0b0010_0000,
+        0b1110_0000,
+        0b1000_0000,
+        0b1110_0000,
+    ],
+    // 3
+    [
+        0b1110_0000,
+        0b0010_0000,
+        0b1110_0000,
+        0b0010_0000,
+        0b1110_0000,
+    ],
+    // 4
+    [
+        0b1010_0000,
+        0b1010_0000,
+        0b1110_0000,
+        0b0010_0000,
+        0b0010_0000,
+    ],
+    // 5
+    [
+        0b1110_0000,
+        0b1000_0000,
+        0b1110_0000,
+        0b0010_0000,
+        0b1110_0000,
+    ],
+    // 6
+    [
+        0b1110_0000,
+        0b1000_0000,
+        0b1110_0000,
+        0b1010_0000,
+        0b1110_0000,
+    ],
+    // 7
+    [
+        0b1110_0000,
+        0b0010_0000,
+        0b0010_0000,
+        0b0010_0000,
+        0b0010_0000,
+    ],
+    // 8
+    [
+        0b1110_0000,
+        0b1010_0000,
+        0b1110_0000,
+        0b1010_0000,
+        0b1110_0000,
+    ],
+    // 9
+    [
+        0b1110_0000,
+        0b1010_0000,
+        0b1110_0000,
+        0b0010_0000,
+        0b1110_0000,
+    ],
+    // A
+    [
+        0b1110_0000,
+        0b1010_0000,
+        0b1110_0000,
+        0b1010_0000,
+        0b1010_0000,
+    ],
+    // B
+    [
+        0b1000_0000,
+        0b1000_0000,
+        0b1110_0000,
+        0b1010_0000,
+        0b1110_0000,
+    ],
+    // C
+    [
+        0b1110_0000,
+        0b1000_0000,
+        0b1000_0000,
+        0b1000_0000,
+        0b1110_0000,
+    ],
+    // D
+    [
+        0b0010_0000,
+        0b0010_0000,
+        0b1110_0000,
+        0b1010_0000,
+        0b1110_0000,
+    ],
+    // E
+    [
+        0b1110_0000,
+        0b1000_0000,
+        0b1110_0000,
+        0b1000_0000,
+        0b1110_0000,
+    ],
+    // F
+    [
+        0b1110_0000,
+        0b1000_0000,
+        0b1100_0000,
+        0b1000_0000,
+        0b1000_0000,
+    ],
+];
+
+/// The small glyphs of the FISH'N'CHIPS interpreter, with rounded digits
+const FISH_N_CHIPS_SMALL: [[u8; SMALL_GLYPH_SIZE]; 16] = [
+    // 0
+    [
+        0b0110_0000,
+        0b1010_0000,
+        0b1010_0000,
+        0b1010_0000,
+        0b1100_0000,
+    ],
+    // 1
+    [
+        0b0100_0000,
+        0b1100_0000,
+        0b0100_0000,
+        0b0100_0000,
+        0b1110_0000,
+    ],
+    // 2
+    [
+        0b1100_0000,
+        0b0010_0000,
+        0b0100_0000,
+        0b1000_0000,
+        0b1110_0000,
+    ],
+    // 3
+    [
+        0b1100_0000,
+        0b0010_0000,
+        0b0100_0000,
+        0b0010_0000,
+        0b1100_0000,
+    ],
+    // 4
+    [
+        0b0010_0000,
+        0b1010_0000,
+        0b1110_0000,
+        0b0010_0000,
+        0b0010_0000,
+    ],
+    // 5
+    [
+        0b1110_0000,
+        0b1000_0000,
+        0b1100_0000,
+        0b0010_0000,
+        0b1100_0000,
+    ],
+    // 6
+    [
+        0b0100_0000,
+        0b1000_0000,
+        0b1100_0000,
+        0b1010_0000,
+        0b0100_0000,
+    ],
+    // 7
+    [
+        0b1110_0000,
+        0b0010_0000,
+        0b0110_0000,
+        0b0100_0000,
+        0b0100_0000,
+    ],
+    // 8
+    [
+        0b0100_0000,
+        0b1010_0000,
+        0b0100_0000,
+        0b1010_0000,
+        0b0100_0000,
+    ],
+    // 9
+    [
+        0b0100_0000,
+        0b1010_0000,
+        0b0110_0000,
+        0b0010_0000,
+        0b0100_0000,
+    ],
+    // A
+    [
+        0b0100_0000,
+        0b1010_0000,
+        0b1110_0000,
+        0b1010_0000,
+        0b1010_0000,
+    ],
+    // B
+    [
+        0b1100_0000,
+        0b1010_0000,
+        0b1100_0000,
+        0b1010_0000,
+        0b1100_0000,
+    ],
+    // C
+    [
+        0b0110_0000,
+        0b1000_0000,
+        0b1000_0000,
+        0b1000_0000,
+        0b0110_0000,
+    ],
+    // D
+    [
+        0b1100_0000,
+        0b1010_0000,
+        0b1010_0000,
+        0b1010_0000,
+        0b1100_0000,
+    ],
+    // E
+    [
+        0b1110_0000,
+        0b1000_0000,
+        0b1100_0000,
+        0b1000_0000,
+        0b1110_0000,
+    ],
+    // F
+    [
+        0b1110_0000,
+        0b1000_0000,
+        0b1100_0000,
+        0b1000_0000,
+        0b1000_0000,
+    ],
+];
+
+/// The 8x10 font of the super chip-48 interpreter, completed with the hexadecimal letters of
+/// XO-CHIP, as the super chip-48 only contained digits
+const SCHIP_LARGE: [[u8; LARGE_GLYPH_SIZE]; 16] = [
+    [0x3C, 0x7E, 0xE7, 0xC3, 0xC3, 0xC3, 0xC3, 0xE7, 0x7E, 0x3C], // 0
+    [0x18, 0x38, 0x58, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x3C], // 1
+    [0x3E, 0x7F, 0xC3, 0x06, 0x0C, 0x18, 0x30, 0x60, 0xFF, 0xFF], // 2
+    [0x3C, 0x7E, 0xC3, 0x03, 0x0E, 0x0E, 0x03, 0xC3, 0x7E, 0x3C], // 3
+    [0x06, 0x0E, 0x1E, 0x36, 0x66, 0xC6, 0xFF, 0xFF, 0x06, 0x06], // 4
+    [0xFF, 0xFF, 0xC0, 0xC0, 0xFC, 0xFE, 0x03, 0xC3, 0x7E, 0x3C], // 5
+    [0x3E, 0x7C, 0xE0, 0xC0, 0xFC, 0xFE, 0xC3, 0xC3, 0x7E, 0x3C], // 6
+    [0xFF, 0xFF, 0x03, 0x06, 0x0C, 0x18, 0x30, 0x60, 0x60, 0x60], // 7
+    [0x3C, 0x7E, 0xC3, 0xC3, 0x7E, 0x7E, 0xC3, 0xC3, 0x7E, 0x3C], // 8
+    [0x3C, 0x7E, 0xC3, 0xC3, 0x7F, 0x3F, 0x03, 0x03, 0x3E, 0x7C], // 9
+    [0x7E, 0xFF, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, 0xC3, 0xC3, 0xC3], // A
+    [0xFC, 0xFC, 0xC3, 0xC3, 0xFC, 0xFC, 0xC3, 0xC3, 0xFC, 0xFC], // B
+    [0x3C, 0xFF, 0xC3, 0xC0, 0xC0, 0xC0, 0xC0, 0xC3, 0xFF, 0x3C], // C
+    [0xFC, 0xFE, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFE, 0xFC], // D
+    [0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF], // E
+    [0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xC0, 0xC0], // F
+];
+
+/// The error returned if a font can't be read
+#[derive(Debug)]
+pub enum FontError {
+    /// The font couldn't be read
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+
+    /// The font is neither a small font nor a small font followed by a large font
+    InvalidLength(usize),
+}
+
+impl core::fmt::Display for FontError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            Self::Io(error) => write!(f, "Failed to read font: {error}"),
+            Self::InvalidLength(length) => write!(
+                f,
+                "Font is {length} bytes, expected {SMALL_FONT_SIZE} or {} bytes",
+                SMALL_FONT_SIZE + LARGE_FONT_SIZE
+            ),
+        }
+    }
+}
+
+impl core::error::Error for FontError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            Self::Io(error) => Some(error),
+            Self::InvalidLength(_) => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for FontError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// The glyphs of the hexadecimal digits 0 to F
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Font {
+    /// The 4x5 glyphs, every row stored in the upper nibble of a byte
+    pub small: [[u8; SMALL_GLYPH_SIZE]; 16],
+
+    /// The 8x10 glyphs used by super chip-48 and XO-CHIP
+    pub large: [[u8; LARGE_GLYPH_SIZE]; 16],
+}
+
+impl Default for Font {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+impl Font {
+    /// The font used by most modern interpreters
+    pub const STANDARD: Self = Self {
+        small: STANDARD_SMALL,
+        large: SCHIP_LARGE,
+    };
+
+    /// The font of the COSMAC VIP interpreter
+    pub const VIP: Self = Self {
+        small: VIP_SMALL,
+        large: SCHIP_LARGE,
+    };
+
+    /// The font of the DREAM 6800 interpreter
+    pub const DREAM_6800: Self = Self {
+        small: DREAM_6800_SMALL,
+        large: SCHIP_LARGE,
+    };
+
+    /// The font of the ETI-660 interpreter
+    pub const ETI_660: Self = Self {
+        small: ETI_660_SMALL,
+        large: SCHIP_LARGE,
+    };
+
+    /// The font of the FISH'N'CHIPS interpreter
+    pub const FISH_N_CHIPS: Self = Self {
+        small: FISH_N_CHIPS_SMALL,
+        large: SCHIP_LARGE,
+    };
+
+    /// Retrieves the preset with the given name (standard, vip, dream-6800, eti-660 or
+    /// fish-n-chips), case insensitive
+    #[cfg(feature = "std")]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "standard" => Some(Self::STANDARD),
+            "vip" => Some(Self::VIP),
+            "dream-6800" | "dream6800" => Some(Self::DREAM_6800),
+            "eti-660" | "eti660" => Some(Self::ETI_660),
+            "fish-n-chips" | "fishnchips" => Some(Self::FISH_N_CHIPS),
+            _ => None,
+        }
+    }
+
+    /// Creates a font from the raw glyphs: 80 bytes of small glyphs, optionally followed by
+    /// 160 bytes of large glyphs. Without large glyphs, those of super chip-48 are used.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FontError> {
+        if bytes.len() != SMALL_FONT_SIZE && bytes.len() != SMALL_FONT_SIZE + LARGE_FONT_SIZE {
+            return Err(FontError::InvalidLength(bytes.len()));
+        }
+        let mut font = Self::STANDARD;
+        let (small, large) = bytes.split_at(SMALL_FONT_SIZE);
+        for (glyph, bytes) in font
+            .small
+            .iter_mut()
+            .zip(small.chunks_exact(SMALL_GLYPH_SIZE))
+        {
+            glyph.copy_from_slice(bytes);
+        }
+        for (glyph, bytes) in font
+            .large
+            .iter_mut()
+            .zip(large.chunks_exact(LARGE_GLYPH_SIZE))
+        {
+            glyph.copy_from_slice(bytes);
+        }
+        Ok(font)
+    }
+
+    /// Reads a font from a file, see [`Font::from_bytes`] for the format
+    #[cfg(feature = "std")]
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, FontError> {
+        let mut bytes = Vec::new();
+        File::open(path)?.read_to_end(&mut bytes)?;
+        Self::from_bytes(&bytes)
+    }
+}
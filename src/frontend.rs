@@ -0,0 +1,439 @@
+//! This module contains the interface of frontends and the run loop driving a machine through
+//! them.
+//!
+//! Frontends only present frames, report input and play sound. The run loop owns everything else:
+//! pacing, pausing, fast-forwarding, rewinding, save slots, screenshots, videos and movies, so
+//! every frontend behaves the same.
+
+use std::{
+    fs, io,
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    keypad::Keypad,
+    machine::{Machine, MachineError, Step},
+    movie::{Movie, MovieError},
+    recording::Recorder,
+    rewind::Rewind,
+    screenshot::{self, Palette},
+};
+
+/// The number of frames emulated per presented frame while fast-forwarding by default
+pub const DEFAULT_TURBO: u32 = 8;
+
+/// The scale of screenshots and videos
+pub const SCREENSHOT_SCALE: usize = 8;
+
+/// The duration of a frame, at 60 frames per second
+const FRAME: Duration = Duration::from_micros(16_667);
+
+/// A command triggered by a hotkey
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Pauses or resumes the emulation
+    TogglePause,
+
+    /// Emulates a single frame while paused
+    Advance,
+
+    /// Saves the state to the numbered save slot
+    Save(usize),
+
+    /// Loads the state from the numbered save slot
+    Load(usize),
+
+    /// Saves a screenshot next to the application
+    Screenshot,
+
+    /// Starts or stops a video recording next to the application
+    ToggleVideo,
+
+    /// Stops the run loop
+    Quit,
+}
+
+/// The input of a frontend since the previous frame
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Input {
+    /// The currently pressed chip-8 keys
+    pub keypad: Keypad,
+
+    /// Whether the emulation runs faster
+    pub fast_forward: bool,
+
+    /// Whether the emulation steps backwards in time
+    pub rewind: bool,
+
+    /// The commands triggered since the previous frame
+    pub commands: Vec<Command>,
+}
+
+/// A display and input backend
+pub trait Frontend {
+    /// Retrieves the input since the previous frame, without blocking
+    fn poll_input(&mut self) -> Input;
+
+    /// Presents the machine after the emulated frames
+    fn present(&mut self, machine: &Machine) -> io::Result<()>;
+
+    /// Plays the sound of the presented frame if it was emulated, the beeper sounds while the
+    /// sound timer is running
+    fn play_audio(&mut self, _sound_timer: u8) {}
+
+    /// Checks whether the user closed the frontend
+    fn should_quit(&self) -> bool {
+        false
+    }
+
+    /// Shows a message to the user, such as a failure to save
+    fn notify(&mut self, message: &str) {
+        eprintln!("{message}");
+    }
+}
+
+/// The error returned if the run loop can't continue
+#[derive(Debug)]
+pub enum RunError {
+    /// The machine couldn't execute an instruction
+    Machine(MachineError),
+
+    /// The frontend couldn't present a frame
+    Frontend(io::Error),
+}
+
+impl std::fmt::Display for RunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Machine(error) => write!(f, "{error}"),
+            Self::Frontend(error) => write!(f, "Frontend error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for RunError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Machine(error) => Some(error),
+            Self::Frontend(error) => Some(error),
+        }
+    }
+}
+
+impl From<MachineError> for RunError {
+    fn from(error: MachineError) -> Self {
+        Self::Machine(error)
+    }
+}
+
+impl From<io::Error> for RunError {
+    fn from(error: io::Error) -> Self {
+        Self::Frontend(error)
+    }
+}
+
+/// Runs a machine through a frontend
+pub struct Runner {
+    /// The number of instructions executed per frame
+    cycles_per_frame: u32,
+
+    /// The number of frames emulated per presented frame while fast-forwarding
+    turbo: u32,
+
+    /// Whether frames are paced to 60 per second, instead of running as fast as possible
+    real_time: bool,
+
+    /// Whether the emulation is paused
+    paused: bool,
+
+    /// The number of frames emulated so far
+    frame: usize,
+
+    /// The snapshots to rewind to, None if rewinding is disabled
+    rewind: Option<Rewind>,
+
+    /// The movie being recorded
+    recording: Option<Movie>,
+
+    /// The movie being played back
+    playback: Option<Movie>,
+
+    /// The path save slots, screenshots and videos are named after, usually the application
+    path: Option<String>,
+
+    /// The colors of screenshots and videos
+    palette: Palette,
+
+    /// The video being recorded
+    video: Option<Recorder>,
+}
+
+impl Runner {
+    /// Creates a run loop executing the number of instructions per frame, paced to 60 frames per
+    /// second, without rewinding
+    pub const fn new(cycles_per_frame: u32) -> Self {
+        Self {
+            cycles_per_frame,
+            turbo: DEFAULT_TURBO,
+            real_time: true,
+            paused: false,
+            frame: 0,
+            rewind: None,
+            recording: None,
+            playback: None,
+            path: None,
+            palette: Palette::MONOCHROME,
+            video: None,
+        }
+    }
+
+    /// Retrieves the number of instructions executed per frame
+    pub const fn cycles_per_frame(&self) -> u32 {
+        self.cycles_per_frame
+    }
+
+    /// Sets the number of instructions executed per frame
+    pub const fn set_cycles_per_frame(&mut self, cycles: u32) {
+        self.cycles_per_frame = cycles;
+    }
+
+    /// Sets the number of frames emulated per presented frame while fast-forwarding
+    pub const fn set_turbo(&mut self, turbo: u32) {
+        self.turbo = turbo;
+    }
+
+    /// Sets whether frames are paced to 60 per second, instead of running as fast as possible
+    pub const fn set_real_time(&mut self, real_time: bool) {
+        self.real_time = real_time;
+    }
+
+    /// Sets the rewind buffer, None to disable rewinding
+    pub fn set_rewind(&mut self, rewind: Option<Rewind>) {
+        self.rewind = rewind;
+    }
+
+    /// Sets the path save slots, screenshots and videos are named after, None to disable them
+    pub fn set_path(&mut self, path: Option<String>) {
+        self.path = path;
+    }
+
+    /// Sets the colors of screenshots and videos
+    pub const fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
+
+    /// Starts recording a video, replacing the current one without finishing it
+    pub fn set_video(&mut self, video: Option<Recorder>) {
+        self.video = video;
+    }
+
+    /// Starts recording a movie of the machine, which just loaded the application
+    pub fn record_movie(&mut self, machine: &mut Machine, seed: u64) {
+        self.recording = Some(Movie::start_recording(machine, seed, self.cycles_per_frame));
+    }
+
+    /// Starts playing back a movie on the machine, which just loaded the application
+    pub fn play_movie(&mut self, machine: &mut Machine, movie: Movie) -> Result<(), MovieError> {
+        movie.start_playback(machine)?;
+        self.playback = Some(movie);
+        Ok(())
+    }
+
+    /// Retrieves the number of frames emulated so far
+    pub const fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Checks whether the emulation is paused
+    pub const fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Checks whether a movie is recorded or played back, which disables save states and
+    /// rewinding as they would desynchronize it
+    pub const fn movie_active(&self) -> bool {
+        self.recording.is_some() || self.playback.is_some()
+    }
+
+    /// Emulates and presents frames until the frontend quits or the application exits
+    pub fn run(
+        &mut self,
+        machine: &mut Machine,
+        frontend: &mut impl Frontend,
+    ) -> Result<(), RunError> {
+        let mut deadline = Instant::now();
+        while self.run_frame(machine, frontend)? {
+            if self.real_time {
+                // Falling behind drops the missed frames instead of catching up
+                deadline += FRAME;
+                let now = Instant::now();
+                if deadline > now {
+                    thread::sleep(deadline - now);
+                } else {
+                    deadline = now;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles the input of the frontend, emulates the frames and presents the last one.
+    /// Returns false once the frontend quits or the application exits.
+    pub fn run_frame(
+        &mut self,
+        machine: &mut Machine,
+        frontend: &mut impl Frontend,
+    ) -> Result<bool, RunError> {
+        let input = frontend.poll_input();
+        let mut advance = false;
+        for &command in &input.commands {
+            match command {
+                Command::TogglePause => self.paused = !self.paused,
+                Command::Advance => advance = true,
+                Command::Save(slot) => self.save(machine, frontend, slot),
+                Command::Load(slot) => self.load(machine, frontend, slot),
+                Command::Screenshot => self.screenshot(machine, frontend),
+                Command::ToggleVideo => self.toggle_video(frontend),
+                Command::Quit => return Ok(false),
+            }
+        }
+        if frontend.should_quit() {
+            return Ok(false);
+        }
+
+        // Only the last of the frames emulated while fast-forwarding is presented and played
+        let mut emulated = false;
+        let frames = match (self.paused, input.fast_forward) {
+            (true, _) => u32::from(advance),
+            (false, true) => self.turbo,
+            (false, false) => 1,
+        };
+        for _ in 0..frames {
+            if !self.movie_active()
+                && input.rewind
+                && let Some(rewind) = &mut self.rewind
+            {
+                if let Err(error) = rewind.rewind(machine) {
+                    frontend.notify(&format!("Failed to rewind: {error}"));
+                }
+                continue;
+            }
+            let step = match self
+                .playback
+                .as_ref()
+                .and_then(|movie| movie.play_frame(machine, self.frame))
+            {
+                Some(step) => step,
+                None => {
+                    *machine.keypad_mut() = input.keypad;
+                    if let Some(movie) = &mut self.recording {
+                        movie.record_frame(input.keypad);
+                    }
+                    machine.run_frame(self.cycles_per_frame)
+                }
+            }?;
+            self.frame += 1;
+            if !self.movie_active()
+                && let Some(rewind) = &mut self.rewind
+            {
+                rewind.record(machine);
+            }
+            if let Some(Err(error)) = self
+                .video
+                .as_mut()
+                .map(|video| video.record_frame(machine.display()))
+            {
+                frontend.notify(&format!("Failed to record video: {error}"));
+                self.video = None;
+            }
+            emulated = true;
+            if step == Step::Exit {
+                frontend.play_audio(machine.registers().sound_timer());
+                frontend.present(machine)?;
+                return Ok(false);
+            }
+        }
+        if emulated {
+            frontend.play_audio(machine.registers().sound_timer());
+        }
+        frontend.present(machine)?;
+        Ok(true)
+    }
+
+    /// Finishes the video and returns the recorded movie
+    pub fn finish(self) -> io::Result<Option<Movie>> {
+        if let Some(video) = self.video {
+            video.finish()?;
+        }
+        Ok(self.recording)
+    }
+
+    /// Retrieves the first unused path with the extension, numbered from 1
+    fn unused_path(&self, extension: &str) -> Option<String> {
+        let path = self.path.as_ref()?;
+        (1..)
+            .map(|index| format!("{path}-{index}.{extension}"))
+            .find(|path| !Path::new(path).exists())
+    }
+
+    /// Saves the state to the numbered save slot
+    fn save(&self, machine: &Machine, frontend: &mut impl Frontend, slot: usize) {
+        let Some(path) = self.path.as_ref().filter(|_| !self.movie_active()) else {
+            return;
+        };
+        let path = format!("{path}.state{slot}");
+        if let Err(error) = fs::write(&path, machine.save_state()) {
+            frontend.notify(&format!("Failed to write {path}: {error}"));
+        }
+    }
+
+    /// Loads the state from the numbered save slot
+    fn load(&self, machine: &mut Machine, frontend: &mut impl Frontend, slot: usize) {
+        let Some(path) = self.path.as_ref().filter(|_| !self.movie_active()) else {
+            return;
+        };
+        let path = format!("{path}.state{slot}");
+        match fs::read(&path) {
+            Ok(state) => {
+                if let Err(error) = machine.load_state(&state) {
+                    frontend.notify(&format!("Failed to load {path}: {error}"));
+                }
+            }
+            Err(error) => frontend.notify(&format!("Failed to read {path}: {error}")),
+        }
+    }
+
+    /// Saves a screenshot next to the application
+    fn screenshot(&self, machine: &Machine, frontend: &mut impl Frontend) {
+        let Some(path) = self.unused_path("png") else {
+            return;
+        };
+        if let Err(error) = screenshot::save(
+            machine.display(),
+            Path::new(&path),
+            SCREENSHOT_SCALE,
+            self.palette,
+        ) {
+            frontend.notify(&format!("Failed to save {path}: {error}"));
+        }
+    }
+
+    /// Starts or stops a video recording next to the application
+    fn toggle_video(&mut self, frontend: &mut impl Frontend) {
+        if let Some(video) = self.video.take() {
+            if let Err(error) = video.finish() {
+                frontend.notify(&format!("Failed to finish video: {error}"));
+            }
+            return;
+        }
+        let Some(path) = self.unused_path("gif") else {
+            return;
+        };
+        match Recorder::create(Path::new(&path), SCREENSHOT_SCALE, self.palette) {
+            Ok(recorder) => self.video = Some(recorder),
+            Err(error) => frontend.notify(&format!("Failed to create {path}: {error}")),
+        }
+    }
+}
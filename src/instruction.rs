@@ -1,10 +1,11 @@
 //! This module contains the implementation of the instruction set
 
 /// The word isn't a valid instruction code
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct InvalidInstruction(pub u16);
 
 /// This enum contains all supported instructions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Instruction {
     /// Jump to a machine code routine at the specified address (12-bit).
     /// This instruction is only used on the old computers on which Chip-8 was originally
@@ -104,9 +105,9 @@ pub enum Instruction {
     /// register indicated by the least significant 4-bits of the first byte.
     RandRange(u8, u8),
 
-    /// Most significant 4-bits of first byte indicate the x and most significant 4-bits of the
-    /// first byte indicate the y position of the location to start drawing at.
-    /// Draws a number of bytes specified by the least significant 4-bits of the second byte.
+    /// The 4 most significant bits of the first byte indicate register x, the least significant
+    /// bits register y. Those registers contain the position to start drawing at.
+    /// Draws a number of bytes specified by the second byte (4-bit).
     /// Reads the data to draw from the address indicated by the value of the I register.
     /// The data is read and displayed as a sprite.
     /// All data of a sprite is stored consecutively, but displayed as a 5 high x 8 wide image.
@@ -173,7 +174,7 @@ impl TryFrom<u16> for Instruction {
             0x3000..=0x3FFF => Self::SkipEqualRegByte((value >> 8) as u8 & 0xF, value as u8),
             0x4000..=0x4FFF => Self::SkipNotEqualRegByte((value >> 8) as u8 & 0xF, value as u8),
             0x5000..=0x5FFF if value & 0xF == 0 => Self::SkipEqualRegisters((value >> 4) as u8),
-            0x6000..=0x6FFF => Self::LoadByte((value >> 8) as u8, value as u8),
+            0x6000..=0x6FFF => Self::LoadByte((value >> 8) as u8 & 0xF, value as u8),
             0x7000..=0x7FFF => Self::AddByte((value >> 8) as u8 & 0xF, value as u8),
             0x8000..=0x8FFF if value & 0xF == 0 => Self::LoadRegister((value >> 4) as u8),
             0x8000..=0x8FFF if value & 0xF == 1 => Self::Or((value >> 4) as u8),
@@ -188,7 +189,7 @@ impl TryFrom<u16> for Instruction {
             0xA000..=0xAFFF => Self::LoadI(value & 0xFFF),
             0xB000..=0xBFFF => Self::JumpAddressOffset(value & 0xFFF),
             0xC000..=0xCFFF => Self::RandRange((value >> 8) as u8 & 0xF, value as u8),
-            0xD000..=0xDFFF => Self::Draw((value >> 8) as u8, value as u8),
+            0xD000..=0xDFFF => Self::Draw((value >> 4) as u8, value as u8 & 0xF),
             0xE000..=0xEFFF if value & 0xFF == 0x9E => Self::SkipPressed((value >> 8) as u8 & 0xF),
             0xE000..=0xEFFF if value & 0xFF == 0xA1 => {
                 Self::SkipNotPressed((value >> 8) as u8 & 0xF)
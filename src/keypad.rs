@@ -0,0 +1,48 @@
+//! This module contains the implementation of the hexadecimal keypad
+
+/// The state of the 16 keys of the chip-8 keypad
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Keypad {
+    /// One bit per key, bit 0 is key 0 and bit 15 is key F
+    state: u16,
+}
+
+impl Keypad {
+    /// Creates a keypad without any pressed keys
+    pub const fn new() -> Self {
+        Self { state: 0 }
+    }
+
+    /// Creates a keypad from a bitmask, bit 0 is key 0 and bit 15 is key F
+    pub const fn from_bits(state: u16) -> Self {
+        Self { state }
+    }
+
+    /// Retrieves the bitmask of pressed keys
+    pub const fn bits(&self) -> u16 {
+        self.state
+    }
+
+    /// Retrieves whether the key (4-bit) is pressed, only the least significant 4 bits are used
+    pub const fn is_pressed(&self, key: u8) -> bool {
+        self.state >> (key & 0xF) & 1 == 1
+    }
+
+    /// Sets whether the key (4-bit) is pressed, only the least significant 4 bits are used
+    pub const fn set(&mut self, key: u8, pressed: bool) {
+        if pressed {
+            self.state |= 1 << (key & 0xF);
+        } else {
+            self.state &= !(1 << (key & 0xF));
+        }
+    }
+
+    /// Retrieves the lowest key that is pressed, if any
+    pub const fn first_pressed(&self) -> Option<u8> {
+        if self.state == 0 {
+            None
+        } else {
+            Some(self.state.trailing_zeros() as u8)
+        }
+    }
+}
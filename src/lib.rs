@@ -1,6 +1,60 @@
 //! This crate contains all code needed to build a chip-8 emulator in Rust.
+//!
+//! The core (instructions, machine, memory, display, keypad, registers and timers) builds
+//! without the standard library or an allocator, so headless use only needs
+//! `chip_8 = { version = "0.1", default-features = false }`. Memory is then stored inside the
+//! machine, so machines created by [`Machine::sized`](machine::Machine::sized) with a smaller
+//! size, such as `Machine::<0x1000>`, only use the memory most applications need instead of
+//! 64 KiB. The optional features add:
+//!
+//! - `std`: loading applications from files, save states, movies, screenshots, videos, coverage
+//!   tracking, the Octo compiler and the alternative execution engines
+//! - `serde`: the game database and Octo cartridges, which are stored as JSON
+//! - `rng`: seeding the random instruction from the operating system instead of a fixed seed
+//! - `audio`: synthesizing the beeper, playing it on the audio device and exporting it as WAV
+//! - `frontend-minifb`: the windowed frontend, the `chip_8` binary
+//! - `frontend-tui`: the terminal frontend, the `chip8-tui` binary
+//!
+//! All of them are enabled by default.
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(clippy::missing_docs_in_private_items, missing_docs)]
 
+#[cfg(feature = "audio")]
+pub mod audio;
+#[cfg(feature = "std")]
+pub mod blocks;
+#[cfg(feature = "serde")]
+pub mod cartridge;
+#[cfg(feature = "std")]
+pub mod coverage;
+#[cfg(feature = "serde")]
+pub mod database;
+pub mod display;
+pub mod font;
+#[cfg(feature = "std")]
+pub mod frontend;
 pub mod instruction;
+pub mod keypad;
+#[cfg(feature = "std")]
+pub mod lockstep;
+pub mod machine;
 pub mod memory;
+#[cfg(feature = "std")]
+pub mod movie;
+#[cfg(feature = "std")]
+pub mod octo;
+pub mod quirks;
+#[cfg(feature = "std")]
+pub mod recompiler;
+#[cfg(feature = "std")]
+pub mod recording;
 pub mod registers;
+#[cfg(feature = "std")]
+pub mod rewind;
+pub mod rng;
+pub mod rom;
+#[cfg(feature = "std")]
+pub mod screenshot;
+pub mod stack;
+#[cfg(feature = "std")]
+pub mod state;
@@ -0,0 +1,292 @@
+//! This module contains the differential testing of two machine configurations, such as two
+//! engines or two quirk presets, running the same application and input in lockstep.
+//!
+//! Both machines execute one instruction at a time and are compared after every instruction, so
+//! the first instruction after which the machines differ is found as soon as it is executed.
+
+use std::fmt::{self, Write as _};
+
+use crate::{
+    display::{HEIGHT, WIDTH},
+    keypad::Keypad,
+    machine::{Machine, MachineError, Step},
+};
+
+/// The number of differing memory addresses shown in a diff
+const MEMORY_DIFF_LIMIT: usize = 16;
+
+/// The first difference between the two machines
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    /// The frame the machines diverged in, counted from 0
+    pub frame: usize,
+
+    /// The number of instructions executed in the frame when the machines first differed, None if
+    /// they only differed after updating the timers
+    pub cycle: Option<u32>,
+
+    /// The first machine when the difference was found
+    pub left: Box<Machine>,
+
+    /// The second machine when the difference was found
+    pub right: Box<Machine>,
+
+    /// The result of running the first machine
+    pub left_result: Result<Step, MachineError>,
+
+    /// The result of running the second machine
+    pub right_result: Result<Step, MachineError>,
+}
+
+/// Checks whether the emulated state of both machines matches, ignoring their configuration.
+/// The registers include I and the timers.
+fn same_state(left: &Machine, right: &Machine) -> bool {
+    left.program_counter() == right.program_counter()
+        && left.registers() == right.registers()
+        && left.keypad() == right.keypad()
+        && left.key_wait == right.key_wait
+        && left.stack().entries() == right.stack().entries()
+        && left.memory().data() == right.memory().data()
+        && left.display() == right.display()
+}
+
+/// Writes a row of the side-by-side diff, marking differing values
+fn row(f: &mut fmt::Formatter<'_>, name: &str, left: &str, right: &str) -> fmt::Result {
+    let marker = if left == right { "" } else { "<" };
+    let line = format!("{name:<8}{left:<20}{right:<20}{marker}");
+    writeln!(f, "{}", line.trim_end())
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.cycle {
+            Some(cycle) => writeln!(
+                f,
+                "Diverged in frame {} after {cycle} instructions",
+                self.frame
+            )?,
+            None => writeln!(
+                f,
+                "Diverged in frame {} when updating the timers",
+                self.frame
+            )?,
+        }
+        let (left, right) = (&self.left, &self.right);
+        writeln!(f, "{:<8}{:<20}right", "", "left")?;
+        row(
+            f,
+            "Result",
+            &format!("{:?}", self.left_result),
+            &format!("{:?}", self.right_result),
+        )?;
+        row(
+            f,
+            "PC",
+            &format!("{:03X}", left.program_counter()),
+            &format!("{:03X}", right.program_counter()),
+        )?;
+        row(
+            f,
+            "I",
+            &format!("{:03X}", left.registers().address()),
+            &format!("{:03X}", right.registers().address()),
+        )?;
+        let registers = (0..16).map(|index| {
+            (
+                format!("V{index:X}"),
+                left.registers().data()[index],
+                right.registers().data()[index],
+            )
+        });
+        let timers = [
+            (
+                "DT".to_owned(),
+                left.registers().delay(),
+                right.registers().delay(),
+            ),
+            (
+                "ST".to_owned(),
+                left.registers().sound_timer(),
+                right.registers().sound_timer(),
+            ),
+        ];
+        for (name, left, right) in registers.chain(timers) {
+            row(f, &name, &format!("{left:02X}"), &format!("{right:02X}"))?;
+        }
+        let stack = |machine: &Machine| {
+            machine
+                .stack()
+                .entries()
+                .iter()
+                .map(|address| format!("{address:03X}"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        row(f, "Stack", &stack(left), &stack(right))?;
+        let keys = |machine: &Machine| {
+            (0..16)
+                .filter(|&key| machine.keypad().is_pressed(key))
+                .map(|key| format!("{key:X}"))
+                .collect::<String>()
+        };
+        row(f, "Keys", &keys(left), &keys(right))?;
+        let key_wait = |machine: &Machine| {
+            machine
+                .key_wait
+                .map_or_else(String::new, |wait| match wait.pressed {
+                    Some(key) => format!("V{:X} {key:X} held", wait.register),
+                    None => format!("V{:X}", wait.register),
+                })
+        };
+        row(f, "Key wait", &key_wait(left), &key_wait(right))?;
+
+        let differing = left
+            .memory()
+            .data()
+            .iter()
+            .zip(right.memory().data())
+            .enumerate()
+            .filter(|(_, (left, right))| left != right)
+            .collect::<Vec<_>>();
+        for &(address, (left, right)) in differing.iter().take(MEMORY_DIFF_LIMIT) {
+            row(
+                f,
+                &format!("[{address:03X}]"),
+                &format!("{left:02X}"),
+                &format!("{right:02X}"),
+            )?;
+        }
+        if differing.len() > MEMORY_DIFF_LIMIT {
+            writeln!(
+                f,
+                "... {} more differing bytes",
+                differing.len() - MEMORY_DIFF_LIMIT
+            )?;
+        }
+
+        if left.display() != right.display() {
+            writeln!(f)?;
+            for y in 0..HEIGHT {
+                let render = |machine: &Machine| {
+                    (0..WIDTH)
+                        .map(|x| match machine.display().pixel(x, y) {
+                            Some(true) => '#',
+                            _ => '.',
+                        })
+                        .collect::<String>()
+                };
+                let (left, right) = (render(left), render(right));
+                let mut line = format!("{left} | {right}");
+                if left != right {
+                    line.write_str("  <")?;
+                }
+                writeln!(f, "{line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs two machines in lockstep
+#[derive(Debug, Clone)]
+pub struct Lockstep {
+    /// The first machine
+    left: Machine,
+
+    /// The second machine
+    right: Machine,
+
+    /// The number of instructions executed per frame
+    cycles_per_frame: u32,
+
+    /// The number of frames run so far
+    frame: usize,
+}
+
+impl Lockstep {
+    /// Creates a lockstep run of two machines, which should have loaded the same application and
+    /// use the same seed
+    pub const fn new(left: Machine, right: Machine, cycles_per_frame: u32) -> Self {
+        Self {
+            left,
+            right,
+            cycles_per_frame,
+            frame: 0,
+        }
+    }
+
+    /// Retrieves the first machine
+    pub const fn left(&self) -> &Machine {
+        &self.left
+    }
+
+    /// Retrieves the second machine
+    pub const fn right(&self) -> &Machine {
+        &self.right
+    }
+
+    /// Retrieves the number of frames run so far
+    pub const fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Runs a frame with the keys pressed on both machines, one instruction at a time.
+    /// Returns the result both machines agree on, or where they first differ.
+    pub fn run_frame(&mut self, keypad: Keypad) -> Result<Result<Step, MachineError>, Divergence> {
+        *self.left.keypad_mut() = keypad;
+        *self.right.keypad_mut() = keypad;
+        let mut result = Ok(Step::Continue);
+        for cycle in 1..=self.cycles_per_frame {
+            let left_result = self.left.run_cycles(1);
+            let right_result = self.right.run_cycles(1);
+            if left_result != right_result || !same_state(&self.left, &self.right) {
+                return Err(self.divergence(Some(cycle), left_result, right_result));
+            }
+            result = left_result;
+            if result != Ok(Step::Continue) {
+                break;
+            }
+        }
+
+        // Like running a frame, the timers are only updated if the instructions succeeded
+        if result.is_ok() {
+            self.left.registers_mut().cycle();
+            self.right.registers_mut().cycle();
+            if !same_state(&self.left, &self.right) {
+                return Err(self.divergence(None, result, result));
+            }
+        }
+        self.frame += 1;
+        Ok(result)
+    }
+
+    /// Creates the divergence of the machines in their current state
+    fn divergence(
+        &self,
+        cycle: Option<u32>,
+        left_result: Result<Step, MachineError>,
+        right_result: Result<Step, MachineError>,
+    ) -> Divergence {
+        Divergence {
+            frame: self.frame,
+            cycle,
+            left: Box::new(self.left.clone()),
+            right: Box::new(self.right.clone()),
+            left_result,
+            right_result,
+        }
+    }
+
+    /// Runs a frame for every keypad state until the frames run out, the application exits or
+    /// fails on both machines. Returns where the machines first differ, if they do.
+    pub fn run(&mut self, frames: impl IntoIterator<Item = Keypad>) -> Option<Divergence> {
+        for keypad in frames {
+            match self.run_frame(keypad) {
+                Ok(Ok(Step::Exit) | Err(_)) => return None,
+                Ok(_) => {}
+                Err(divergence) => return Some(divergence),
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,902 @@
+//! This module contains the chip-8 machine, which combines all components and executes
+//! instructions.
+
+#[cfg(feature = "serde")]
+use std::sync::Arc;
+
+use sha1::{Digest, Sha1};
+
+#[cfg(feature = "serde")]
+use crate::database::{Database, GameInfo};
+#[cfg(feature = "std")]
+use crate::{
+    blocks::{BlockCache, Engine},
+    coverage::Coverage,
+    rom::Rom,
+};
+use crate::{
+    display::Display,
+    font::Font,
+    instruction::{Instruction, InvalidInstruction},
+    keypad::Keypad,
+    memory::{MAX_SIZE, Memory, MemoryLayout},
+    quirks::Quirks,
+    registers::Registers,
+    rng::{Rng, Xorshift},
+    rom::RomError,
+    stack::Stack,
+};
+
+/// The number of instructions executed per frame by default, running at 60 frames per second
+pub const DEFAULT_CYCLES_PER_FRAME: u32 = 10;
+
+/// The error returned if the machine can't execute the next instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MachineError {
+    /// The word at the address isn't a valid instruction
+    InvalidInstruction {
+        /// The address of the instruction
+        address: u16,
+
+        /// The invalid instruction itself
+        instruction: InvalidInstruction,
+    },
+
+    /// The program counter points outside of memory
+    ProgramCounterOutOfBounds(u16),
+
+    /// A subroutine was called while the stack was full
+    StackOverflow(u16),
+
+    /// A return was executed while the stack was empty
+    StackUnderflow(u16),
+
+    /// An instruction tried to read or write memory it isn't allowed to access
+    MemoryAccess(u16),
+}
+
+impl core::fmt::Display for MachineError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidInstruction {
+                address,
+                instruction,
+            } => write!(
+                f,
+                "Invalid instruction {:04X} at {address:03X}",
+                instruction.0
+            ),
+            Self::ProgramCounterOutOfBounds(address) => {
+                write!(f, "Program counter out of bounds: {address:03X}")
+            }
+            Self::StackOverflow(address) => write!(f, "Stack overflow at {address:03X}"),
+            Self::StackUnderflow(address) => write!(f, "Stack underflow at {address:03X}"),
+            Self::MemoryAccess(address) => write!(f, "Invalid memory access at {address:03X}"),
+        }
+    }
+}
+
+impl core::error::Error for MachineError {}
+
+/// The result of successfully executing an instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// The instruction was executed, the machine can continue
+    Continue,
+
+    /// The machine is waiting for a key to be pressed and released
+    WaitingForKey,
+
+    /// A sprite was drawn and the machine waits for the display to refresh, which ends the frame
+    WaitingForDisplay,
+
+    /// The application executed the exit instruction
+    Exit,
+}
+
+/// The state of an instruction waiting for a key press
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct KeyWait {
+    /// The register to store the key in
+    pub(crate) register: u8,
+
+    /// The key that has been pressed, but not yet released
+    pub(crate) pressed: Option<u8>,
+}
+
+/// The complete chip-8 machine, supporting memory maps of up to `SIZE` bytes
+#[derive(Debug, Clone)]
+pub struct Machine<const SIZE: usize = MAX_SIZE> {
+    /// The memory
+    pub(crate) memory: Memory<SIZE>,
+
+    /// The return addresses of the called subroutines
+    pub(crate) stack: Stack,
+
+    /// The general purpose, address and timer registers
+    pub(crate) registers: Registers,
+
+    /// The address of the next instruction
+    pub(crate) program_counter: u16,
+
+    /// The framebuffer
+    pub(crate) display: Display,
+
+    /// The currently pressed keys
+    pub(crate) keypad: Keypad,
+
+    /// The font stored in memory, restored whenever an application is loaded
+    pub(crate) font: Font,
+
+    /// The behaviour of ambiguous instructions
+    pub(crate) quirks: Quirks,
+
+    /// The source of random bytes for the random instruction
+    #[cfg(feature = "std")]
+    pub(crate) rng: Box<dyn Rng>,
+
+    /// The source of random bytes for the random instruction
+    #[cfg(not(feature = "std"))]
+    pub(crate) rng: Xorshift,
+
+    /// The pending key wait, if the machine is waiting for a key
+    pub(crate) key_wait: Option<KeyWait>,
+
+    /// The CRC-32 checksum of the loaded application
+    pub(crate) rom_checksum: u32,
+
+    /// The SHA-1 hash of the loaded application
+    pub(crate) rom_sha1: [u8; 20],
+
+    /// The number of instructions executed since the application was loaded
+    pub(crate) instruction_count: u64,
+
+    /// The accesses of every memory address, if they are tracked
+    #[cfg(feature = "std")]
+    pub(crate) coverage: Option<Coverage>,
+
+    /// The database the settings of applications are looked up in, None to disable the lookup
+    #[cfg(feature = "serde")]
+    pub(crate) database: Option<Arc<Database>>,
+
+    /// The description and settings of the loaded application, if it is in the database
+    #[cfg(feature = "serde")]
+    pub(crate) game: Option<GameInfo>,
+
+    /// The way instructions are executed
+    #[cfg(feature = "std")]
+    pub(crate) engine: Engine,
+
+    /// The compiled blocks, used by the block engine
+    #[cfg(feature = "std")]
+    pub(crate) blocks: BlockCache<SIZE>,
+}
+
+impl Default for Machine {
+    fn default() -> Self {
+        Self::new(Quirks::default())
+    }
+}
+
+/// Machines are compared by their emulated state, so the instruction count, coverage map, database
+/// and engine are ignored
+impl<const SIZE: usize> PartialEq for Machine<SIZE> {
+    fn eq(&self, other: &Self) -> bool {
+        self.memory == other.memory
+            && self.stack == other.stack
+            && self.registers == other.registers
+            && self.program_counter == other.program_counter
+            && self.display == other.display
+            && self.keypad == other.keypad
+            && self.font == other.font
+            && self.quirks == other.quirks
+            && self.same_rng(other)
+            && self.key_wait == other.key_wait
+            && self.rom_checksum == other.rom_checksum
+            && self.rom_sha1 == other.rom_sha1
+    }
+}
+
+impl<const SIZE: usize> Eq for Machine<SIZE> {}
+
+impl Machine {
+    /// Creates a new machine without an application, using the memory map of the COSMAC VIP
+    pub fn new(quirks: Quirks) -> Self {
+        Self::with_layout(quirks, MemoryLayout::default())
+            .expect("The memory map of the COSMAC VIP is valid")
+    }
+
+    /// Creates a new machine without an application, using the memory map.
+    /// Returns None if the memory map is invalid, see [`MemoryLayout::is_valid`].
+    pub fn with_layout(quirks: Quirks, layout: MemoryLayout) -> Option<Self> {
+        Self::sized(quirks, layout)
+    }
+}
+
+impl<const SIZE: usize> Machine<SIZE> {
+    /// Creates a new machine without an application, using the memory map and storing at most
+    /// `SIZE` bytes of memory. Returns None if the memory map is invalid, see
+    /// [`MemoryLayout::is_valid`], or larger than `SIZE`.
+    pub fn sized(quirks: Quirks, layout: MemoryLayout) -> Option<Self> {
+        Some(Self {
+            program_counter: layout.program_start,
+            memory: Memory::sized(layout)?,
+            stack: Stack::default(),
+            registers: Registers::new(),
+            display: Display::new(),
+            keypad: Keypad::new(),
+            font: Font::STANDARD,
+            quirks,
+            #[cfg(feature = "std")]
+            rng: Box::new(Xorshift::default()),
+            #[cfg(not(feature = "std"))]
+            rng: Xorshift::default(),
+            key_wait: None,
+            rom_checksum: 0,
+            rom_sha1: [0; 20],
+            instruction_count: 0,
+            #[cfg(feature = "std")]
+            coverage: None,
+            #[cfg(feature = "serde")]
+            database: Some(Database::builtin()),
+            #[cfg(feature = "serde")]
+            game: None,
+            #[cfg(feature = "std")]
+            engine: Engine::Interpreter,
+            #[cfg(feature = "std")]
+            blocks: BlockCache::default(),
+        })
+    }
+
+    /// Resets the machine and loads the application at the program start of the memory map.
+    /// If the application is in the database, its quirks are applied. Otherwise the quirks are
+    /// kept, just like the font, stack configuration, keypad and random number generator.
+    /// The coverage map is cleared if it is tracked.
+    /// Applications can be read from bytes, files or readers using [`Rom`].
+    #[cfg(feature = "std")]
+    pub fn load_rom(&mut self, rom: &Rom) -> Result<(), RomError> {
+        self.reset(rom.data(), rom.crc32(), rom.sha1())?;
+        if let Some(coverage) = &mut self.coverage {
+            *coverage = Coverage::new(self.memory.layout().size);
+        }
+        #[cfg(feature = "serde")]
+        self.look_up(rom);
+        Ok(())
+    }
+
+    /// Looks up the application in the database and applies its quirks
+    #[cfg(feature = "serde")]
+    fn look_up(&mut self, rom: &Rom) {
+        self.game = self
+            .database
+            .as_ref()
+            .and_then(|database| database.lookup(rom.sha1()));
+        if let Some(quirks) = self.game.as_ref().and_then(|game| game.quirks) {
+            self.quirks = quirks;
+        }
+    }
+
+    /// Resets the machine and loads the application bytes at the program start of the memory
+    /// map, keeping the quirks, font, stack configuration, keypad and random number generator.
+    /// Unlike [`Machine::load_rom`], this doesn't allocate and never consults the database.
+    pub fn load_program(&mut self, program: &[u8]) -> Result<(), RomError> {
+        if program.is_empty() {
+            return Err(RomError::Empty);
+        }
+        self.reset(
+            program,
+            crc32fast::hash(program),
+            Sha1::digest(program).into(),
+        )
+    }
+
+    /// Resets the machine and loads the application with the given checksums
+    fn reset(&mut self, program: &[u8], crc32: u32, sha1: [u8; 20]) -> Result<(), RomError> {
+        let mut memory = Memory::sized(self.memory.layout().clone())
+            .expect("The memory map was valid when the memory was created");
+        memory.set_font(&self.font);
+        memory.set_cache_enabled(self.memory.cache_enabled());
+        let capacity = memory.program_capacity();
+        if !memory.load_program(program) {
+            return Err(RomError::TooLarge {
+                size: program.len(),
+                capacity,
+            });
+        }
+        let mut stack = Stack::new(self.stack.limit());
+        stack.set_vip_mirror(self.stack.vip_mirror());
+
+        self.program_counter = memory.layout().program_start;
+        self.memory = memory;
+        self.stack = stack;
+        self.registers = Registers::new();
+        self.display.clear();
+        self.key_wait = None;
+        self.rom_checksum = crc32;
+        self.rom_sha1 = sha1;
+        self.instruction_count = 0;
+        Ok(())
+    }
+
+    /// Retrieves the memory
+    pub const fn memory(&self) -> &Memory<SIZE> {
+        &self.memory
+    }
+
+    /// Retrieves the memory mutably
+    pub const fn memory_mut(&mut self) -> &mut Memory<SIZE> {
+        &mut self.memory
+    }
+
+    /// Retrieves the call stack
+    pub const fn stack(&self) -> &Stack {
+        &self.stack
+    }
+
+    /// Retrieves the call stack mutably, to configure its depth
+    pub const fn stack_mut(&mut self) -> &mut Stack {
+        &mut self.stack
+    }
+
+    /// Retrieves the registers
+    pub const fn registers(&self) -> &Registers {
+        &self.registers
+    }
+
+    /// Retrieves the registers mutably
+    pub const fn registers_mut(&mut self) -> &mut Registers {
+        &mut self.registers
+    }
+
+    /// Retrieves the address of the next instruction
+    pub const fn program_counter(&self) -> u16 {
+        self.program_counter
+    }
+
+    /// Sets the address of the next instruction
+    pub const fn set_program_counter(&mut self, address: u16) {
+        self.program_counter = address;
+    }
+
+    /// Retrieves the framebuffer
+    pub const fn display(&self) -> &Display {
+        &self.display
+    }
+
+    /// Retrieves the keypad
+    pub const fn keypad(&self) -> &Keypad {
+        &self.keypad
+    }
+
+    /// Retrieves the keypad mutably, to press and release keys
+    pub const fn keypad_mut(&mut self) -> &mut Keypad {
+        &mut self.keypad
+    }
+
+    /// Retrieves the font
+    pub const fn font(&self) -> &Font {
+        &self.font
+    }
+
+    /// Sets the font and writes it to the font addresses of the memory map
+    pub fn set_font(&mut self, font: Font) {
+        self.memory.set_font(&font);
+        self.font = font;
+    }
+
+    /// Retrieves the description and recommended settings of the loaded application, None if it
+    /// isn't in the database
+    #[cfg(feature = "serde")]
+    pub const fn game(&self) -> Option<&GameInfo> {
+        self.game.as_ref()
+    }
+
+    /// Sets the database applications are looked up in when they are loaded, None to always keep
+    /// the current settings
+    #[cfg(feature = "serde")]
+    pub fn set_database(&mut self, database: Option<Arc<Database>>) {
+        self.database = database;
+    }
+
+    /// Retrieves the way instructions are executed
+    #[cfg(feature = "std")]
+    pub const fn engine(&self) -> Engine {
+        self.engine
+    }
+
+    /// Sets the way instructions are executed, which doesn't change the behaviour of the machine
+    #[cfg(feature = "std")]
+    pub fn set_engine(&mut self, engine: Engine) {
+        self.engine = engine;
+        self.blocks = BlockCache::default();
+    }
+
+    /// Retrieves the coverage map, None if it isn't tracked
+    #[cfg(feature = "std")]
+    pub const fn coverage(&self) -> Option<&Coverage> {
+        self.coverage.as_ref()
+    }
+
+    /// Sets whether the accesses of every memory address are tracked, which slows down execution.
+    /// Enabling it starts a new coverage map, disabling it discards the current one.
+    #[cfg(feature = "std")]
+    pub fn set_coverage_tracking(&mut self, enabled: bool) {
+        self.coverage = enabled.then(|| Coverage::new(self.memory.layout().size));
+    }
+
+    /// Retrieves the quirks
+    pub const fn quirks(&self) -> Quirks {
+        self.quirks
+    }
+
+    /// Sets the quirks
+    pub const fn set_quirks(&mut self, quirks: Quirks) {
+        self.quirks = quirks;
+    }
+
+    /// Retrieves the CRC-32 checksum of the loaded application
+    pub const fn rom_checksum(&self) -> u32 {
+        self.rom_checksum
+    }
+
+    /// Retrieves the SHA-1 hash of the loaded application
+    pub const fn rom_sha1(&self) -> [u8; 20] {
+        self.rom_sha1
+    }
+
+    /// Retrieves the random number generator
+    #[cfg(feature = "std")]
+    pub fn rng(&self) -> &dyn Rng {
+        self.rng.as_ref()
+    }
+
+    /// Replaces the random number generator
+    #[cfg(feature = "std")]
+    pub fn set_rng(&mut self, rng: impl Rng + 'static) {
+        self.rng = Box::new(rng);
+    }
+
+    /// Replaces the random number generator by a fast generator with the given seed, making the
+    /// random instruction reproducible
+    pub fn set_seed(&mut self, seed: u64) {
+        #[cfg(feature = "std")]
+        self.set_rng(Xorshift::new(seed));
+        #[cfg(not(feature = "std"))]
+        {
+            self.rng = Xorshift::new(seed);
+        }
+    }
+
+    /// Restarts the random number generator from the seed, keeping its kind and configuration
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng.reseed(seed);
+    }
+
+    /// Checks whether both random number generators are in the same state
+    fn same_rng(&self, other: &Self) -> bool {
+        #[cfg(feature = "std")]
+        {
+            self.rng.save() == other.rng.save()
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            self.rng == other.rng
+        }
+    }
+
+    /// Retrieves the value of general purpose register (4-bit)
+    pub(crate) const fn value(&self, register: u8) -> u8 {
+        self.registers.data()[(register & 0xF) as usize]
+    }
+
+    /// Sets the value of general purpose register (4-bit)
+    pub(crate) const fn set_value(&mut self, register: u8, value: u8) {
+        self.registers.data_mut()[(register & 0xF) as usize] = value;
+    }
+
+    /// Retrieves the number of instructions executed since the application was loaded, including
+    /// every time a key wait is executed again. Save states don't store it.
+    pub const fn instruction_count(&self) -> u64 {
+        self.instruction_count
+    }
+
+    /// Fetches and decodes the instruction at the program counter
+    pub fn fetch(&self) -> Result<Instruction, MachineError> {
+        Self::check_instruction(
+            self.program_counter,
+            self.memory.decode(self.program_counter),
+        )
+    }
+
+    /// Decodes the next instruction, caching it for the next time it is executed
+    fn fetch_cached(&mut self) -> Result<Instruction, MachineError> {
+        Self::check_instruction(
+            self.program_counter,
+            self.memory.instruction(self.program_counter),
+        )
+    }
+
+    /// Converts the result of decoding the instruction at the address to a machine error
+    pub(crate) fn check_instruction(
+        address: u16,
+        decoded: Option<Result<Instruction, InvalidInstruction>>,
+    ) -> Result<Instruction, MachineError> {
+        decoded
+            .ok_or(MachineError::ProgramCounterOutOfBounds(address))?
+            .map_err(|instruction| MachineError::InvalidInstruction {
+                address,
+                instruction,
+            })
+    }
+
+    /// Retrieves whether the machine halted by jumping to the current instruction, which most
+    /// applications do when they are finished
+    pub fn is_halted(&self) -> bool {
+        self.fetch() == Ok(Instruction::JumpAddress(self.program_counter))
+    }
+
+    /// Executes a single instruction
+    pub fn step(&mut self) -> Result<Step, MachineError> {
+        let instruction = self.fetch_cached()?;
+        self.execute_at(self.program_counter, instruction)
+    }
+
+    /// Executes a decoded instruction as if it was fetched from the address, which is how
+    /// recompiled applications execute the instructions they don't translate
+    pub fn execute_at(
+        &mut self,
+        address: u16,
+        instruction: Instruction,
+    ) -> Result<Step, MachineError> {
+        #[cfg(feature = "std")]
+        if let Some(coverage) = &mut self.coverage {
+            coverage.execute(address);
+        }
+        self.program_counter = address
+            .checked_add(2)
+            .ok_or(MachineError::ProgramCounterOutOfBounds(address))?;
+        self.execute(address, instruction)
+    }
+
+    /// Executes up to the given number of instructions, then updates the timers.
+    /// Stops early if the machine waits for a key or the application exits.
+    pub fn run_frame(&mut self, cycles: u32) -> Result<Step, MachineError> {
+        let step = self.run_cycles(cycles)?;
+        self.registers.cycle();
+        Ok(step)
+    }
+
+    /// Executes up to the given number of instructions with the engine, without updating the
+    /// timers. Stops early if the machine waits for a key or the application exits.
+    pub fn run_cycles(&mut self, cycles: u32) -> Result<Step, MachineError> {
+        #[cfg(feature = "std")]
+        match self.engine {
+            Engine::Interpreter => self.run_steps(cycles),
+            Engine::Blocks => self.run_blocks(cycles),
+        }
+        #[cfg(not(feature = "std"))]
+        self.run_steps(cycles)
+    }
+
+    /// Executes up to the given number of instructions one by one.
+    /// Stops early if the machine waits for a key or the application exits.
+    fn run_steps(&mut self, cycles: u32) -> Result<Step, MachineError> {
+        for _ in 0..cycles {
+            let step = self.step()?;
+            if step != Step::Continue {
+                return Ok(step);
+            }
+        }
+        Ok(Step::Continue)
+    }
+
+    /// Executes a decoded instruction, the program counter already points to the next one
+    pub(crate) fn execute(
+        &mut self,
+        address: u16,
+        instruction: Instruction,
+    ) -> Result<Step, MachineError> {
+        self.instruction_count += 1;
+        match instruction {
+            Instruction::SystemAddress(_) => {}
+            Instruction::ClearScreen => self.display.clear(),
+            Instruction::Return => self.return_from(address)?,
+            Instruction::JumpAddress(target) => self.program_counter = target,
+            Instruction::CallAddress(target) => self.call(address, target)?,
+            Instruction::SkipEqualRegByte(reg, byte) => {
+                self.skip_if(address, self.value(reg) == byte)?;
+            }
+            Instruction::SkipNotEqualRegByte(reg, byte) => {
+                self.skip_if(address, self.value(reg) != byte)?;
+            }
+            Instruction::SkipEqualRegisters(regs) => {
+                self.skip_if(address, self.value(regs >> 4) == self.value(regs & 0xF))?;
+            }
+            Instruction::LoadByte(reg, byte) => self.set_value(reg, byte),
+            Instruction::AddByte(reg, byte) => {
+                self.set_value(reg, self.value(reg).wrapping_add(byte));
+            }
+            Instruction::LoadRegister(regs) => self.set_value(regs >> 4, self.value(regs & 0xF)),
+            Instruction::Or(regs) => self.logical(regs, |left, right| left | right),
+            Instruction::And(regs) => self.logical(regs, |left, right| left & right),
+            Instruction::Xor(regs) => self.logical(regs, |left, right| left ^ right),
+            Instruction::Add(regs) => {
+                let (result, carry) = self
+                    .value(regs >> 4)
+                    .overflowing_add(self.value(regs & 0xF));
+                self.set_value(regs >> 4, result);
+                self.set_value(0xF, u8::from(carry));
+            }
+            Instruction::Sub(regs) => {
+                let (result, borrow) = self
+                    .value(regs >> 4)
+                    .overflowing_sub(self.value(regs & 0xF));
+                self.set_value(regs >> 4, result);
+                self.set_value(0xF, u8::from(!borrow));
+            }
+            Instruction::ShiftRight(regs) => {
+                let source = self.shift_source(regs);
+                self.set_value(regs >> 4, source >> 1);
+                self.set_value(0xF, source & 1);
+            }
+            Instruction::SubInverted(regs) => {
+                let (result, borrow) = self
+                    .value(regs & 0xF)
+                    .overflowing_sub(self.value(regs >> 4));
+                self.set_value(regs >> 4, result);
+                self.set_value(0xF, u8::from(!borrow));
+            }
+            Instruction::ShiftLeft(regs) => {
+                let source = self.shift_source(regs);
+                self.set_value(regs >> 4, source << 1);
+                self.set_value(0xF, source >> 7);
+            }
+            Instruction::SkipNotEqualReg(regs) => {
+                self.skip_if(address, self.value(regs >> 4) != self.value(regs & 0xF))?;
+            }
+            Instruction::LoadI(target) => *self.registers.address_mut() = target,
+            Instruction::JumpAddressOffset(target) => {
+                let offset = if self.quirks.jump_uses_vx {
+                    self.value((target >> 8) as u8)
+                } else {
+                    self.value(0)
+                };
+                self.program_counter = target + u16::from(offset);
+            }
+            Instruction::RandRange(reg, anded) => {
+                let random = self.rng.next_byte();
+                self.set_value(reg, random & anded);
+            }
+            Instruction::Draw(position, bytes) => {
+                self.draw(
+                    position >> 4,
+                    position & 0xF,
+                    bytes,
+                    self.quirks.clip_sprites,
+                )?;
+                if self.quirks.display_wait {
+                    return Ok(Step::WaitingForDisplay);
+                }
+            }
+            Instruction::SkipPressed(reg) => {
+                self.skip_if(address, self.keypad.is_pressed(self.value(reg)))?;
+            }
+            Instruction::SkipNotPressed(reg) => {
+                self.skip_if(address, !self.keypad.is_pressed(self.value(reg)))?;
+            }
+            Instruction::LoadRegisterDelayTimer(reg) => self.set_value(reg, self.registers.delay()),
+            Instruction::LoadKeyPress(reg) => return Ok(self.wait_for_key(address, reg)),
+            Instruction::LoadDelayTimerRegister(reg) => self.registers.set_delay(self.value(reg)),
+            Instruction::LoadSoundTimerRegister(reg) => {
+                self.registers.set_sound_timer(self.value(reg));
+            }
+            Instruction::AddAddresssRegister(reg) => self.add_address(reg),
+            Instruction::LoadSpriteAddress(reg) => {
+                *self.registers.address_mut() =
+                    self.memory.layout().small_font + u16::from(self.value(reg) & 0xF) * 5;
+            }
+            Instruction::LoadRegisterSprites(reg) => self.store_digits(address, reg)?,
+            Instruction::LoadMemoryRegisters(reg) => {
+                self.store_registers(address, reg)?;
+                self.increment_address(reg);
+            }
+            Instruction::LoadRegistersMemory(reg) => {
+                self.load_registers(reg)?;
+                self.increment_address(reg);
+            }
+            Instruction::Exit => {
+                self.program_counter = address;
+                return Ok(Step::Exit);
+            }
+        }
+        Ok(Step::Continue)
+    }
+
+    /// Returns from the current subroutine on behalf of the instruction at the address
+    pub(crate) fn return_from(&mut self, address: u16) -> Result<(), MachineError> {
+        self.program_counter = self
+            .stack
+            .pop()
+            .map_err(|_| MachineError::StackUnderflow(address))?;
+        Ok(())
+    }
+
+    /// Calls the subroutine at the target on behalf of the instruction at the address, mirroring
+    /// the return address in memory if the stack does
+    pub(crate) fn call(&mut self, address: u16, target: u16) -> Result<(), MachineError> {
+        self.stack
+            .push(self.program_counter)
+            .map_err(|_| MachineError::StackOverflow(address))?;
+        if self.stack.vip_mirror() {
+            let mirror = Stack::mirror_address(self.stack.depth() - 1);
+            for (offset, byte) in self.program_counter.to_be_bytes().into_iter().enumerate() {
+                self.store(address, mirror.wrapping_add(offset as u16), byte);
+            }
+        }
+        self.program_counter = target;
+        Ok(())
+    }
+
+    /// Draws the sprite with the number of rows at I at the position in the registers X and Y,
+    /// setting VF if it collided
+    pub(crate) fn draw(&mut self, x: u8, y: u8, rows: u8, clip: bool) -> Result<(), MachineError> {
+        let start = self.registers.address();
+        let sprite = start
+            .checked_add(u16::from(rows))
+            .and_then(|end| self.memory.slice(start..end))
+            .ok_or(MachineError::MemoryAccess(start))?;
+        #[cfg(feature = "std")]
+        if let Some(coverage) = &mut self.coverage {
+            coverage.read(usize::from(start)..usize::from(start) + usize::from(rows));
+        }
+        let collision = self.display.draw_sprite(
+            usize::from(self.value(x)),
+            usize::from(self.value(y)),
+            sprite,
+            clip,
+        );
+        self.set_value(0xF, u8::from(collision));
+        Ok(())
+    }
+
+    /// Adds the value of the register to I, wrapping around at the end of memory
+    pub(crate) fn add_address(&mut self, register: u8) {
+        let value = self.value(register);
+        let size = self.memory.layout().size;
+        let address = self.registers.address_mut();
+        *address = ((usize::from(*address) + usize::from(value)) % size) as u16;
+    }
+
+    /// Stores the decimal digits of the register at I on behalf of the instruction at the
+    /// address
+    pub(crate) fn store_digits(&mut self, address: u16, register: u8) -> Result<(), MachineError> {
+        let value = self.value(register);
+        let start = self.registers.address();
+        for (offset, digit) in [value / 100, value / 10 % 10, value % 10]
+            .into_iter()
+            .enumerate()
+        {
+            let target = start
+                .checked_add(offset as u16)
+                .ok_or(MachineError::MemoryAccess(start))?;
+            if !self.store(address, target, digit) {
+                return Err(MachineError::MemoryAccess(target));
+            }
+        }
+        Ok(())
+    }
+
+    /// Stores V0 up to the last register at I on behalf of the instruction at the address,
+    /// without incrementing I
+    pub(crate) fn store_registers(&mut self, address: u16, last: u8) -> Result<(), MachineError> {
+        let start = self.registers.address();
+        for offset in 0..=last & 0xF {
+            let target = start
+                .checked_add(u16::from(offset))
+                .ok_or(MachineError::MemoryAccess(start))?;
+            if !self.store(address, target, self.value(offset)) {
+                return Err(MachineError::MemoryAccess(target));
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads V0 up to the last register from I, without incrementing I
+    pub(crate) fn load_registers(&mut self, last: u8) -> Result<(), MachineError> {
+        let start = self.registers.address();
+        for offset in 0..=last & 0xF {
+            let source = start
+                .checked_add(u16::from(offset))
+                .ok_or(MachineError::MemoryAccess(start))?;
+            let value = self
+                .memory
+                .load(source)
+                .ok_or(MachineError::MemoryAccess(source))?;
+            self.set_value(offset, value);
+        }
+        #[cfg(feature = "std")]
+        if let Some(coverage) = &mut self.coverage {
+            let start = usize::from(start);
+            coverage.read(start..start + usize::from(last & 0xF) + 1);
+        }
+        Ok(())
+    }
+
+    /// Stores a byte on behalf of the instruction at the address, tracking the write if coverage
+    /// is tracked. Returns whether the value was stored.
+    #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+    pub(crate) fn store(&mut self, address: u16, target: u16, value: u8) -> bool {
+        let stored = self.memory.store(target, value);
+        #[cfg(feature = "std")]
+        if stored && let Some(coverage) = &mut self.coverage {
+            coverage.write(target, address);
+        }
+        stored
+    }
+
+    /// Skips the next instruction if the condition holds, on behalf of the instruction at the
+    /// address
+    pub(crate) const fn skip_if(
+        &mut self,
+        address: u16,
+        condition: bool,
+    ) -> Result<(), MachineError> {
+        if condition {
+            let Some(target) = self.program_counter.checked_add(2) else {
+                return Err(MachineError::ProgramCounterOutOfBounds(address));
+            };
+            self.program_counter = target;
+        }
+        Ok(())
+    }
+
+    /// Executes a logical instruction, resetting VF if required by the quirks
+    fn logical(&mut self, regs: u8, operation: impl FnOnce(u8, u8) -> u8) {
+        self.set_value(
+            regs >> 4,
+            operation(self.value(regs >> 4), self.value(regs & 0xF)),
+        );
+        if self.quirks.vf_reset {
+            self.set_value(0xF, 0);
+        }
+    }
+
+    /// Retrieves the value to shift, depending on the quirks
+    const fn shift_source(&self, regs: u8) -> u8 {
+        if self.quirks.shift_uses_vy {
+            self.value(regs & 0xF)
+        } else {
+            self.value(regs >> 4)
+        }
+    }
+
+    /// Increments I after loading or storing registers, if required by the quirks
+    fn increment_address(&mut self, reg: u8) {
+        if self.quirks.load_store_increments_i {
+            let increment = u16::from(reg & 0xF) + u16::from(!self.quirks.increment_i_by_x);
+            let address = self.registers.address_mut();
+            *address = address.wrapping_add(increment);
+        }
+    }
+
+    /// Waits for a key to be pressed and released, like the original interpreter.
+    /// The instruction is repeated until the key is released.
+    pub(crate) fn wait_for_key(&mut self, address: u16, register: u8) -> Step {
+        let wait = self.key_wait.get_or_insert(KeyWait {
+            register,
+            pressed: None,
+        });
+        match wait.pressed {
+            Some(key) if !self.keypad.is_pressed(key) => {
+                self.key_wait = None;
+                self.set_value(register, key);
+                Step::Continue
+            }
+            pressed => {
+                wait.pressed = pressed.or(self.keypad.first_pressed());
+                self.program_counter = address;
+                Step::WaitingForKey
+            }
+        }
+    }
+}
@@ -1,164 +1,273 @@
-use std::fs;
-
-use chip_8::{instruction::Instruction, memory::Memory, registers::Registers};
-use minifb::{Key, Window, WindowOptions};
-
-pub const SCREEN_WIDTH: usize = 64;
-pub const SCREEN_HEIGHT: usize = 32;
-
-fn draw_byte(buffer: &mut [u32], x: usize, y: usize, byte: u8) -> bool {
-    let mut vf = false;
-    for j in 0..8 {
-        let pixel = &mut buffer[y * SCREEN_WIDTH + (x + j) % SCREEN_WIDTH];
-        let value = byte >> (7 - j) & 1;
-        let value = (0..u32::BITS).fold(0, |result, bit| result | (u32::from(value) << bit));
-        vf = vf || value & *pixel != 0;
-        *pixel = value;
-    }
-    vf
+use std::{env, fs, io, path::Path, sync::Arc};
+
+#[cfg(feature = "audio")]
+use chip_8::audio::Speaker;
+use chip_8::{
+    blocks::Engine,
+    cartridge::Cartridge,
+    database::Database,
+    display::{HEIGHT, WIDTH},
+    font::Font,
+    frontend::{Command, DEFAULT_TURBO, Frontend, Input, Runner, SCREENSHOT_SCALE},
+    machine::{DEFAULT_CYCLES_PER_FRAME, Machine},
+    movie::Movie,
+    quirks::Quirks,
+    recording::Recorder,
+    rewind::{self, Rewind},
+    rom::Rom,
+    screenshot::Palette,
+};
+use minifb::{Key, KeyRepeat, Scale, Window, WindowOptions};
+
+/// The keyboard keys mapped to the chip-8 keys 0 through F
+const KEYMAP: [Key; 16] = [
+    Key::X,
+    Key::Key1,
+    Key::Key2,
+    Key::Key3,
+    Key::Q,
+    Key::W,
+    Key::E,
+    Key::A,
+    Key::S,
+    Key::D,
+    Key::Z,
+    Key::C,
+    Key::Key4,
+    Key::R,
+    Key::F,
+    Key::V,
+];
+
+/// The keyboard keys of the buttons of the database, pressing the chip-8 key the application
+/// uses for that button
+const BUTTONS: [(&str, Key); 6] = [
+    ("up", Key::Up),
+    ("down", Key::Down),
+    ("left", Key::Left),
+    ("right", Key::Right),
+    ("a", Key::Space),
+    ("b", Key::Enter),
+];
+
+/// The key running the emulation faster while held
+const TURBO_KEY: Key = Key::Tab;
+
+/// The key stepping backwards in time while held
+const REWIND_KEY: Key = Key::Backspace;
+
+/// The key pausing and resuming the emulation
+const PAUSE_KEY: Key = Key::F5;
+
+/// The key emulating a single frame while paused
+const ADVANCE_KEY: Key = Key::F6;
+
+/// The key saving a screenshot next to the application
+const SCREENSHOT_KEY: Key = Key::F12;
+
+/// The key starting and stopping a video recording next to the application
+const VIDEO_KEY: Key = Key::F10;
+
+/// The keys saving to the save slots, holding shift loads from the slot instead
+const SLOT_KEYS: [Key; 4] = [Key::F1, Key::F2, Key::F3, Key::F4];
+
+/// A window showing the display
+struct WindowFrontend {
+    /// The window
+    window: Window,
+
+    /// The pixels of the window, as 0RGB
+    buffer: Vec<u32>,
+
+    /// The colors of the pixels
+    palette: Palette,
+
+    /// The chip-8 keys of the buttons of the application, and the keyboard keys pressing them
+    buttons: Vec<(u8, Key)>,
+
+    /// Plays the beeper, None if the audio device couldn't be opened
+    #[cfg(feature = "audio")]
+    speaker: Option<Speaker>,
 }
 
-fn main() {
-    let mut window = Window::new(
-        "Chip-8",
-        SCREEN_WIDTH,
-        SCREEN_HEIGHT,
-        WindowOptions::default(),
-    )
-    .unwrap();
-    let mut buffer = [0; SCREEN_WIDTH * SCREEN_HEIGHT];
-    let mut memory = Memory::new();
-    let mut registers = Registers::new();
-    let application = fs::read("roms/RPS.ch8").unwrap();
-    memory
-        .slice_mut(0x200..0x1000)
-        .unwrap()
-        .copy_from_slice(&application);
-    let mut pointer = 0x200;
-    let mut vf = false;
-    while window.is_open() && !window.is_key_down(Key::Escape) {
-        if pointer >= 0xFFF {
-            break;
-        }
-        let Ok(instruction) = Instruction::try_from(u16::from_le_bytes(
-            memory
-                .slice(pointer..pointer + 2)
-                .unwrap()
-                .try_into()
-                .unwrap(),
-        )) else {
-            pointer += 2;
-            continue;
+impl Frontend for WindowFrontend {
+    fn poll_input(&mut self) -> Input {
+        let window = &self.window;
+        let mut input = Input {
+            fast_forward: window.is_key_down(TURBO_KEY),
+            rewind: window.is_key_down(REWIND_KEY),
+            ..Input::default()
         };
-        match instruction {
-            Instruction::SystemAddress(_) => continue,
-            Instruction::ClearScreen => buffer.fill(0),
-            Instruction::Return => pointer = memory.pop().unwrap() - 2,
-            Instruction::JumpAddress(address) => pointer = address,
-            Instruction::CallAddress(address) => {
-                memory.push(pointer);
-                pointer = address;
-            }
-            Instruction::SkipEqualRegByte(reg, byte) => {
-                if registers.get_value(reg).unwrap() == byte {
-                    pointer += 2;
-                }
-            }
-            Instruction::SkipNotEqualRegByte(reg, byte) => {
-                if registers.get_value(reg).unwrap() != byte {
-                    pointer += 2;
-                }
-            }
-            Instruction::SkipEqualRegisters(regs) => {
-                if registers.get_value(regs & 0xF).unwrap()
-                    == registers.get_value(regs >> 4).unwrap()
-                {
-                    pointer += 2;
-                }
-            }
-            Instruction::LoadByte(reg, byte) => *registers.get_value_mut(reg).unwrap() = byte,
-            Instruction::AddByte(reg, byte) => *registers.get_value_mut(reg).unwrap() += byte,
-            Instruction::LoadRegister(reg) => {
-                *registers.get_value_mut(reg >> 4).unwrap() =
-                    registers.get_value(reg & 0xF).unwrap()
-            }
-            Instruction::Or(regs) => {
-                *registers.get_value_mut(regs >> 4).unwrap() |=
-                    registers.get_value(regs & 0xF).unwrap()
-            }
-            Instruction::And(regs) => {
-                *registers.get_value_mut(regs >> 4).unwrap() &=
-                    registers.get_value(regs & 0xF).unwrap()
-            }
-            Instruction::Xor(regs) => {
-                *registers.get_value_mut(regs >> 4).unwrap() ^=
-                    registers.get_value(regs & 0xF).unwrap()
-            }
-            Instruction::Add(regs) => {
-                let right = registers.get_value(regs & 0xF).unwrap();
-                let left = registers.get_value_mut(regs >> 4).unwrap();
-                (*left, vf) = left.overflowing_add(right);
-            }
-            Instruction::Sub(regs) => {
-                let right = registers.get_value(regs & 0xF).unwrap();
-                let left = registers.get_value_mut(regs >> 4).unwrap();
-                (*left, vf) = left.overflowing_sub(right);
+        for (key, &host_key) in KEYMAP.iter().enumerate() {
+            input.keypad.set(key as u8, window.is_key_down(host_key));
+        }
+        for &(key, host_key) in &self.buttons {
+            if window.is_key_down(host_key) {
+                input.keypad.set(key, true);
             }
-            Instruction::ShiftRight(regs) => {
-                let register = registers.get_value_mut(regs & 0xF).unwrap();
-                (vf, *register) = (*register & 1 == 1, *register >> 1);
+        }
+        let shift = window.is_key_down(Key::LeftShift) || window.is_key_down(Key::RightShift);
+        for (slot, key) in SLOT_KEYS.into_iter().enumerate() {
+            if window.is_key_pressed(key, KeyRepeat::No) {
+                input.commands.push(if shift {
+                    Command::Load(slot + 1)
+                } else {
+                    Command::Save(slot + 1)
+                });
             }
-            Instruction::SubInverted(regs) => {
-                let right = registers.get_value(regs & 0xF).unwrap();
-                let left = registers.get_value_mut(regs >> 4).unwrap();
-                (*left, vf) = right.overflowing_sub(*left);
+        }
+        let hotkeys = [
+            (PAUSE_KEY, Command::TogglePause),
+            (ADVANCE_KEY, Command::Advance),
+            (SCREENSHOT_KEY, Command::Screenshot),
+            (VIDEO_KEY, Command::ToggleVideo),
+        ];
+        for (key, command) in hotkeys {
+            if window.is_key_pressed(key, KeyRepeat::No) {
+                input.commands.push(command);
             }
-            Instruction::ShiftLeft(reg) => {
-                let register = registers.get_value_mut(reg & 0xF).unwrap();
-                (vf, *register) = (*register & 0x80 == 0x80, *register << 1);
+        }
+        input
+    }
+
+    fn present(&mut self, machine: &Machine) -> io::Result<()> {
+        for (pixel, lit) in self.buffer.iter_mut().zip(machine.display().pixels()) {
+            *pixel = self.palette.color_u32(lit);
+        }
+        self.window
+            .update_with_buffer(&self.buffer, WIDTH, HEIGHT)
+            .map_err(io::Error::other)
+    }
+
+    #[cfg(feature = "audio")]
+    fn play_audio(&mut self, sound_timer: u8) {
+        if let Some(speaker) = &mut self.speaker {
+            speaker.play_frame(sound_timer);
+        }
+    }
+
+    fn should_quit(&self) -> bool {
+        !self.window.is_open() || self.window.is_key_down(Key::Escape)
+    }
+}
+
+fn main() {
+    let mut rom = "roms/RPS.ch8".to_owned();
+    let (mut seed, mut font, mut engine) = (None, None, Engine::default());
+    let (mut record, mut play, mut video_path, mut database) = (None, None, None, None);
+    let mut turbo = DEFAULT_TURBO;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => seed = Some(args.next().expect("Missing seed").parse::<u64>().unwrap()),
+            "--font" => {
+                let name = args.next().expect("Missing font");
+                font =
+                    Some(Font::from_name(&name).unwrap_or_else(|| Font::from_path(&name).unwrap()));
             }
-            Instruction::SkipNotEqualReg(regs) => {
-                if registers.get_value(regs & 0xF).unwrap()
-                    != registers.get_value(regs >> 4).unwrap()
-                {
-                    pointer += 2;
-                }
+            "--engine" => {
+                let name = args.next().expect("Missing engine");
+                engine = Engine::from_name(&name).expect("Unknown engine");
             }
-            Instruction::LoadI(address) => *registers.address_mut() = address,
-            Instruction::JumpAddressOffset(address) => pointer = address + registers.address(),
-            Instruction::RandRange(reg, anded) => {
-                *registers.get_value_mut(reg & 0xF).unwrap() = rand::random::<u8>() & anded
+            "--turbo" => {
+                turbo = args.next().expect("Missing turbo speed").parse().unwrap();
             }
-            Instruction::Draw(position, bytes) => {
-                let (x, y) = (
-                    usize::from(registers.get_value(position >> 4).unwrap()),
-                    usize::from(registers.get_value(position & 0xF).unwrap()),
-                );
-                for i in 0..u16::from(bytes & 0xF) {
-                    draw_byte(
-                        &mut buffer,
-                        x + usize::from(i / 5),
-                        (usize::from(i % 5) + y) % SCREEN_HEIGHT,
-                        memory.load(registers.address() + i).unwrap(),
-                    );
-                }
+            "--record" => record = Some(args.next().expect("Missing movie path")),
+            "--play" => play = Some(args.next().expect("Missing movie path")),
+            "--video" => video_path = Some(args.next().expect("Missing video path")),
+            "--database" => {
+                let path = args.next().expect("Missing database path");
+                database = Some(Database::from_path(path).unwrap());
             }
-            Instruction::SkipPressed(_) => todo!(),
-            Instruction::SkipNotPressed(_) => todo!(),
-            Instruction::LoadRegisterDelayTimer(_) => todo!(),
-            Instruction::LoadKeyPress(_) => todo!(),
-            Instruction::LoadDelayTimerRegister(_) => todo!(),
-            Instruction::LoadSoundTimerRegister(_) => todo!(),
-            Instruction::AddAddresssRegister(_) => todo!(),
-            Instruction::LoadSpriteAddress(_) => todo!(),
-            Instruction::LoadRegisterSprites(_) => todo!(),
-            Instruction::LoadMemoryRegisters(_) => todo!(),
-            Instruction::LoadRegistersMemory(_) => todo!(),
-            Instruction::Exit => todo!(),
+            _ => rom = arg,
         }
-        pointer += 2;
-        window
-            .update_with_buffer(&buffer, SCREEN_WIDTH, SCREEN_HEIGHT)
-            .unwrap();
+    }
+    let mut machine = Machine::new(Quirks::default());
+    if let Some(seed) = seed {
+        machine.set_seed(seed);
+    }
+    machine.set_engine(engine);
+    if let Some(database) = database {
+        machine.set_database(Some(Arc::new(database)));
+    }
+
+    // Cartridges bring their own settings, which take precedence over the database
+    let options = if Cartridge::is_cartridge_path(Path::new(&rom)) {
+        let cartridge = Cartridge::from_path(&rom).unwrap();
+        machine.load_rom(&cartridge.rom).unwrap();
+        cartridge.options.apply(&mut machine);
+        Some(cartridge.options)
+    } else {
+        machine.load_rom(&Rom::from_path(&rom).unwrap()).unwrap();
+        None
+    };
+    if let Some(font) = font {
+        machine.set_font(font);
+    }
+    let game = machine.game().cloned();
+    let cycles_per_frame = options.as_ref().map_or_else(
+        || {
+            game.as_ref()
+                .and_then(|game| game.cycles_per_frame)
+                .unwrap_or(DEFAULT_CYCLES_PER_FRAME)
+        },
+        |options| options.cycles_per_frame,
+    );
+    let palette = options
+        .as_ref()
+        .map(|options| options.palette)
+        .or_else(|| game.as_ref().and_then(|game| game.palette))
+        .unwrap_or_default();
+    let buttons = game.as_ref().map_or_else(Vec::new, |game| {
+        BUTTONS
+            .into_iter()
+            .filter_map(|(name, host_key)| {
+                let (_, key) = game.keys.iter().find(|(button, _)| button == name)?;
+                Some((*key, host_key))
+            })
+            .collect()
+    });
+
+    let mut runner = Runner::new(cycles_per_frame);
+    runner.set_turbo(turbo);
+    runner.set_rewind(Some(Rewind::new(rewind::DEFAULT_BUDGET)));
+    runner.set_path(Some(rom.clone()));
+    runner.set_palette(palette);
+    runner.set_video(
+        video_path
+            .map(|path| Recorder::create(Path::new(&path), SCREENSHOT_SCALE, palette).unwrap()),
+    );
+    if record.is_some() {
+        runner.record_movie(&mut machine, seed.unwrap_or_else(rand::random));
+    }
+    if let Some(path) = play {
+        let movie = Movie::from_bytes(&fs::read(path).unwrap()).unwrap();
+        runner.play_movie(&mut machine, movie).unwrap();
+    }
+
+    let mut frontend = WindowFrontend {
+        window: Window::new(
+            game.as_ref().map_or("Chip-8", |game| &game.title),
+            WIDTH,
+            HEIGHT,
+            WindowOptions {
+                scale: Scale::X8,
+                ..WindowOptions::default()
+            },
+        )
+        .unwrap(),
+        buffer: vec![0; WIDTH * HEIGHT],
+        palette,
+        buttons,
+        #[cfg(feature = "audio")]
+        speaker: Speaker::new()
+            .inspect_err(|error| eprintln!("Sound is disabled: {error}"))
+            .ok(),
+    };
+    runner.run(&mut machine, &mut frontend).unwrap();
+
+    let movie = runner.finish().unwrap();
+    if let (Some(path), Some(movie)) = (record, movie) {
+        fs::write(path, movie.to_bytes()).unwrap();
     }
 }
@@ -1,16 +1,144 @@
 //! This module contains the implementation of chip-8 memory
 
-use std::ops::{Index, IndexMut, Range};
+use core::ops::{Index, IndexMut, Range};
+#[cfg(feature = "std")]
+use core::sync::atomic::{AtomicU64, Ordering};
 
-/// The memory struct contains the full chip-8 memory and stack pointer
-pub struct Memory {
-    /// The data stored in memory
-    data: [u8; 0x1000],
+use crate::{
+    font::{Font, LARGE_GLYPH_SIZE, SMALL_GLYPH_SIZE},
+    instruction::{Instruction, InvalidInstruction},
+};
 
-    /// The current address of the end of the call stack
-    stack_pointer: u16,
+/// The largest supported memory size, 64 KiB as used by XO-CHIP
+pub const MAX_SIZE: usize = 0x10000;
+
+/// The source of code generations, unique across all memories so compiled code of one memory is
+/// never mistaken for compiled code of another
+#[cfg(feature = "std")]
+static NEXT_CODE_GENERATION: AtomicU64 = AtomicU64::new(1);
+
+/// The memory map: where applications and fonts are located, and which memory exists and is
+/// writable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryLayout {
+    /// The address applications are loaded at and start executing from
+    pub program_start: u16,
+
+    /// The address of the small 4x5 hexadecimal font
+    pub small_font: u16,
+
+    /// The address of the large 8x10 font
+    pub large_font: u16,
+
+    /// The total size of memory in bytes, at most [`MAX_SIZE`]
+    pub size: usize,
+
+    /// The addresses applications are allowed to write to
+    pub writable: Range<usize>,
+}
+
+impl Default for MemoryLayout {
+    fn default() -> Self {
+        Self::VIP
+    }
+}
+
+impl MemoryLayout {
+    /// The memory map of the COSMAC VIP: 4 KiB, applications at 0x200
+    pub const VIP: Self = Self {
+        program_start: 0x200,
+        small_font: 0,
+        large_font: 80,
+        size: 0x1000,
+        writable: 0x200..0x1000,
+    };
+
+    /// The memory map of the ETI-660: 4 KiB, applications at 0x600
+    pub const ETI_660: Self = Self {
+        program_start: 0x600,
+        small_font: 0,
+        large_font: 80,
+        size: 0x1000,
+        writable: 0x600..0x1000,
+    };
+
+    /// The memory map of XO-CHIP: 64 KiB, applications at 0x200
+    pub const XO_CHIP: Self = Self {
+        program_start: 0x200,
+        small_font: 0,
+        large_font: 80,
+        size: MAX_SIZE,
+        writable: 0x200..MAX_SIZE,
+    };
+
+    /// Retrieves the preset with the given name (vip, eti-660 or xo-chip), case insensitive
+    #[cfg(feature = "std")]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "vip" => Some(Self::VIP),
+            "eti-660" | "eti660" => Some(Self::ETI_660),
+            "xo-chip" | "xochip" => Some(Self::XO_CHIP),
+            _ => None,
+        }
+    }
+
+    /// Retrieves whether memory can be created with the memory map: it's at most [`MAX_SIZE`]
+    /// bytes large, applications start inside it and both fonts fit in it
+    pub const fn is_valid(&self) -> bool {
+        self.size <= MAX_SIZE
+            && (self.program_start as usize) < self.size
+            && self.small_font as usize + 16 * SMALL_GLYPH_SIZE <= self.size
+            && self.large_font as usize + 16 * LARGE_GLYPH_SIZE <= self.size
+    }
+
+    /// Retrieves whether the address exists and is writable
+    pub const fn is_writable(&self, address: usize) -> bool {
+        address >= self.writable.start && address < self.writable.end && address < self.size
+    }
+}
+
+/// The memory struct contains the full chip-8 memory, supporting memory maps of up to `SIZE`
+/// bytes. Without the `std` feature all of it is stored inline, so smaller machines can limit it.
+#[derive(Debug, Clone)]
+pub struct Memory<const SIZE: usize = MAX_SIZE> {
+    /// The data stored in memory, as many bytes as the memory map is large
+    #[cfg(feature = "std")]
+    data: Vec<u8>,
+
+    /// The data stored in memory, only the first bytes up to the size of the memory map exist
+    #[cfg(not(feature = "std"))]
+    data: [u8; SIZE],
+
+    /// The memory map
+    layout: MemoryLayout,
+
+    /// The decoded instruction starting at every address, None if it hasn't been decoded since
+    /// the last write. Empty until the first instruction is decoded or if caching is disabled.
+    #[cfg(feature = "std")]
+    decoded: Vec<Option<Instruction>>,
+
+    /// Whether decoded instructions are cached
+    cache_enabled: bool,
+
+    /// Whether every address is part of compiled code. Empty if no code has been compiled.
+    #[cfg(feature = "std")]
+    code: Vec<bool>,
+
+    /// The generation of the compiled code, which changes whenever compiled code is overwritten.
+    /// 0 if no code has been compiled.
+    #[cfg(feature = "std")]
+    code_generation: u64,
 }
 
+/// Memories are compared by their contents, so the instruction cache is ignored
+impl<const SIZE: usize> PartialEq for Memory<SIZE> {
+    fn eq(&self, other: &Self) -> bool {
+        self.data() == other.data() && self.layout == other.layout
+    }
+}
+
+impl<const SIZE: usize> Eq for Memory<SIZE> {}
+
 impl Default for Memory {
     fn default() -> Self {
         Self::new()
@@ -18,172 +146,107 @@ impl Default for Memory {
 }
 
 impl Memory {
-    /// Initializes the memory
-    pub const fn new() -> Self {
-        // Data vector
-        let mut data = [0; 0x1000];
-
-        // The default sprites for numbers
-        let sprites = [
-            // 0
-            [
-                0b1111_0000,
-                0b1001_0000,
-                0b1001_0000,
-                0b1001_0000,
-                0b1111_0000,
-            ],
-            // 1
-            [
-                0b0010_0000,
-                0b0110_0000,
-                0b0010_0000,
-                0b0010_0000,
-                0b0111_0000,
-            ],
-            // 2
-            [
-                0b1111_0000,
-                0b0001_0000,
-                0b1111_0000,
-                0b1000_0000,
-                0b1111_0000,
-            ],
-            // 3
-            [
-                0b1111_0000,
-                0b0001_0000,
-                0b1111_0000,
-                0b0001_0000,
-                0b1111_0000,
-            ],
-            // 4
-            [
-                0b1001_0000,
-                0b1001_0000,
-                0b1111_0000,
-                0b0001_0000,
-                0b0001_0000,
-            ],
-            // 5
-            [
-                0b1111_0000,
-                0b1000_0000,
-                0b1111_0000,
-                0b0001_0000,
-                0b1111_0000,
-            ],
-            // 6
-            [
-                0b1111_0000,
-                0b1000_0000,
-                0b1111_0000,
-                0b1001_0000,
-                0b1111_0000,
-            ],
-            // 7
-            [
-                0b1111_0000,
-                0b0001_0000,
-                0b0010_0000,
-                0b0100_0000,
-                0b0100_0000,
-            ],
-            // 8
-            [
-                0b1111_0000,
-                0b1001_0000,
-                0b1111_0000,
-                0b1001_0000,
-                0b1111_0000,
-            ],
-            // 9
-            [
-                0b1111_0000,
-                0b1001_0000,
-                0b1111_0000,
-                0b0001_0000,
-                0b1111_0000,
-            ],
-            // A
-            [
-                0b1111_0000,
-                0b1001_0000,
-                0b1111_0000,
-                0b1001_0000,
-                0b1001_0000,
-            ],
-            // B
-            [
-                0b1110_0000,
-                0b1001_0000,
-                0b1110_0000,
-                0b1001_0000,
-                0b1110_0000,
-            ],
-            // C
-            [
-                0b1111_0000,
-                0b1000_0000,
-                0b1000_0000,
-                0b1000_0000,
-                0b1111_0000,
-            ],
-            // D
-            [
-                0b1110_0000,
-                0b1001_0000,
-                0b1001_0000,
-                0b1001_0000,
-                0b1110_0000,
-            ],
-            // E
-            [
-                0b1111_0000,
-                0b1000_0000,
-                0b1111_0000,
-                0b1000_0000,
-                0b1111_0000,
-            ],
-            // F
-            [
-                0b1111_0000,
-                0b1000_0000,
-                0b1111_0000,
-                0b1000_0000,
-                0b1000_0000,
-            ],
-        ];
-
-        // Load the sprites into memory
-        let mut sprite_index = 0;
-        while sprite_index < sprites.len() {
-            // Copy the current slice into memory
-            let mut byte_index = 0;
-            let sprite = &sprites[sprite_index];
-            while byte_index < sprite.len() {
-                data[sprite_index * sprite.len() + byte_index] = sprite[byte_index];
-                byte_index += 1;
-            }
+    /// Initializes the memory with the memory map of the COSMAC VIP
+    pub fn new() -> Self {
+        Self::with_layout(MemoryLayout::VIP).expect("The memory map of the COSMAC VIP is valid")
+    }
+
+    /// Initializes the memory with the memory map and the standard font.
+    /// Returns None if the memory map is invalid, see [`MemoryLayout::is_valid`].
+    pub fn with_layout(layout: MemoryLayout) -> Option<Self> {
+        Self::sized(layout)
+    }
 
-            // Continue to the next slice
-            sprite_index += 1;
+    /// Recreates memory from previously saved contents.
+    /// Returns None if the memory map is invalid or the length of the contents doesn't match its
+    /// size.
+    pub fn from_raw_parts(layout: MemoryLayout, contents: &[u8]) -> Option<Self> {
+        if contents.len() != layout.size {
+            return None;
         }
+        let mut memory = Self::empty(layout)?;
+        memory.data[..contents.len()].copy_from_slice(contents);
+        Some(memory)
+    }
+}
 
-        // Create the memory object
-        Self {
-            data,
-            stack_pointer: (sprites.len() * sprites[0].len()) as u16,
+impl<const SIZE: usize> Memory<SIZE> {
+    /// Initializes the memory with the memory map and the standard font, storing at most `SIZE`
+    /// bytes. Returns None if the memory map is invalid, see [`MemoryLayout::is_valid`], or
+    /// larger than `SIZE`.
+    pub fn sized(layout: MemoryLayout) -> Option<Self> {
+        let mut memory = Self::empty(layout)?;
+        write_font(&mut memory.data, &memory.layout, &Font::STANDARD);
+        Some(memory)
+    }
+
+    /// Creates zeroed memory with the memory map, None if the memory map is invalid or too large
+    fn empty(layout: MemoryLayout) -> Option<Self> {
+        if !layout.is_valid() || layout.size > SIZE {
+            return None;
         }
+        Some(Self {
+            #[cfg(feature = "std")]
+            data: vec![0; layout.size],
+            #[cfg(not(feature = "std"))]
+            data: [0; SIZE],
+            layout,
+            #[cfg(feature = "std")]
+            decoded: Vec::new(),
+            cache_enabled: true,
+            #[cfg(feature = "std")]
+            code: Vec::new(),
+            #[cfg(feature = "std")]
+            code_generation: 0,
+        })
+    }
+
+    /// Writes the font at the font addresses of the memory map, regardless of write protection
+    pub fn set_font(&mut self, font: &Font) {
+        write_font(&mut self.data, &self.layout, font);
+        self.invalidate(0..self.layout.size);
+    }
+
+    /// Retrieves the memory map
+    pub const fn layout(&self) -> &MemoryLayout {
+        &self.layout
+    }
+
+    /// Retrieves all bytes stored in memory, including the protected area
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.layout.size]
+    }
+
+    /// Retrieves the number of bytes available for an application, from the program start to the
+    /// end of the writable memory
+    pub fn program_capacity(&self) -> usize {
+        let start = usize::from(self.layout.program_start);
+        if self.layout.is_writable(start) {
+            self.layout.writable.end.min(self.layout.size) - start
+        } else {
+            0
+        }
+    }
+
+    /// Copies the application to the program start.
+    /// Returns false if the application doesn't fit in the writable memory.
+    pub fn load_program(&mut self, program: &[u8]) -> bool {
+        if program.len() > self.program_capacity() {
+            return false;
+        }
+        let start = usize::from(self.layout.program_start);
+        self.data[start..start + program.len()].copy_from_slice(program);
+        self.invalidate(start..start + program.len());
+        true
     }
 
     /// Loads a value from memory if possible
-    pub const fn load(&self, index: u16) -> Option<u8> {
+    pub fn load(&self, index: u16) -> Option<u8> {
         // Convert the index to a usize, so it can be compared to memory size and used as index
         let index = index as usize;
 
         // Return the requested byte if possible, None otherwise
-        if index < self.data.len() {
+        if index < self.layout.size {
             Some(self.data[index])
         } else {
             None
@@ -191,57 +254,21 @@ impl Memory {
     }
 
     /// Stores the requested byte if possible and allowed, returns whether the value was stored.
-    pub const fn store(&mut self, index: u16, value: u8) -> bool {
-        match index {
-            // If the index points to protected memory or non-existing, the value can't be stored.
-            ..0x200 | 0x1000.. => false,
-
-            // Otherwise, set it
-            index => {
-                self.data[index as usize] = value;
-                true
-            }
-        }
-    }
-
-    /// Pushes a new code address on the stack
-    pub fn push(&mut self, address: u16) -> bool {
-        // Only data and code addresses can be stored.
-        // Return false for other addresses or if the stack is full
-        if self.stack_pointer + 2 >= 0x200 || !(0x200..0x1000).contains(&address) {
+    pub fn store(&mut self, index: u16, value: u8) -> bool {
+        // If the index points to protected memory or non-existing, the value can't be stored.
+        if !self.layout.is_writable(index as usize) {
             return false;
         }
 
-        // Convert the stack pointer to a usize, so it can actually be used as an index
-        let stack_pointer = self.stack_pointer as usize;
-
-        // Copy the address to the location pointed to by the stack pointer
-        self.data[stack_pointer..stack_pointer + 2].copy_from_slice(&address.to_ne_bytes());
-
-        // Increment the stack pointer
-        self.stack_pointer += 2;
+        // Otherwise, set it
+        self.data[index as usize] = value;
+        self.invalidate(index as usize..index as usize + 1);
         true
     }
 
-    /// Pops an address from the stack
-    pub fn pop(&mut self) -> Option<u16> {
-        // Return None if no address has been stored on the stack yet
-        if self.stack_pointer < 82 {
-            return None;
-        }
-
-        // Decrement the stack pointer
-        self.stack_pointer -= 2;
-
-        // Load the memory from the stack and convert it to an address again
-        let mut word = [0; 2];
-        word.copy_from_slice(&self.data[self.stack_pointer as usize..][..2]);
-        Some(u16::from_ne_bytes(word))
-    }
-
     /// Takes a slice of memory to load multiple bytes easily and quickly
     pub fn slice(&self, range: Range<u16>) -> Option<&[u8]> {
-        if range.end <= 0xFFF {
+        if range.start <= range.end && usize::from(range.end) <= self.layout.size {
             Some(&self.data[range.start as usize..range.end as usize])
         } else {
             None
@@ -250,31 +277,162 @@ impl Memory {
 
     /// Takes a mutable slice of memory to store multiple bytes easily and quickly
     pub fn slice_mut(&mut self, range: Range<u16>) -> Option<&mut [u8]> {
-        if range.start >= 200 && range.end <= 0xFFF {
-            Some(&mut self.data[range.start as usize..range.end as usize])
+        let (start, end) = (usize::from(range.start), usize::from(range.end));
+        if start <= end
+            && start >= self.layout.writable.start
+            && end <= self.layout.writable.end
+            && end <= self.layout.size
+        {
+            self.invalidate(start..end);
+            Some(&mut self.data[start..end])
         } else {
             None
         }
     }
+
+    /// Retrieves whether decoded instructions are cached
+    pub const fn cache_enabled(&self) -> bool {
+        self.cache_enabled
+    }
+
+    /// Sets whether decoded instructions are cached, which speeds up execution at the cost of
+    /// memory. Disabling the cache discards all cached instructions.
+    /// Without the `std` feature there is no cache.
+    pub fn set_cache_enabled(&mut self, enabled: bool) {
+        self.cache_enabled = enabled;
+        #[cfg(feature = "std")]
+        if !enabled {
+            self.decoded = Vec::new();
+        }
+    }
+
+    /// Decodes the instruction starting at the address, from the cache if it hasn't been written
+    /// since it was last decoded. Returns None if the instruction doesn't fit in memory.
+    pub fn instruction(&mut self, address: u16) -> Option<Result<Instruction, InvalidInstruction>> {
+        #[cfg(feature = "std")]
+        if let Some(&Some(instruction)) = self.decoded.get(usize::from(address)) {
+            return Some(Ok(instruction));
+        }
+        let result = self.decode(address)?;
+        #[cfg(feature = "std")]
+        if let (Ok(instruction), true) = (result, self.cache_enabled) {
+            if self.decoded.len() != self.layout.size {
+                self.decoded = vec![None; self.layout.size];
+            }
+            self.decoded[usize::from(address)] = Some(instruction);
+        }
+        Some(result)
+    }
+
+    /// Decodes the instruction starting at the address without filling the cache.
+    /// Returns None if the instruction doesn't fit in memory.
+    pub fn decode(&self, address: u16) -> Option<Result<Instruction, InvalidInstruction>> {
+        #[cfg(feature = "std")]
+        if let Some(&Some(instruction)) = self.decoded.get(usize::from(address)) {
+            return Some(Ok(instruction));
+        }
+        let high = self.load(address)?;
+        let low = self.load(address.checked_add(1)?)?;
+        Some(Instruction::try_from(u16::from_be_bytes([high, low])))
+    }
+
+    /// Retrieves the generation of the compiled code, which changes whenever compiled code is
+    /// overwritten
+    #[cfg(feature = "std")]
+    pub(crate) const fn code_generation(&self) -> u64 {
+        self.code_generation
+    }
+
+    /// Marks the addresses as part of compiled code, so writing them starts a new generation
+    #[cfg(feature = "std")]
+    pub(crate) fn mark_code(&mut self, range: Range<usize>) {
+        if self.code_generation == 0 {
+            self.code_generation = NEXT_CODE_GENERATION.fetch_add(1, Ordering::Relaxed);
+        }
+        if self.code.len() != self.layout.size {
+            self.code = vec![false; self.layout.size];
+        }
+        let end = range.end.min(self.code.len());
+        if let Some(code) = self.code.get_mut(range.start..end) {
+            code.fill(true);
+        }
+    }
+
+    /// Forgets all compiled code and starts a new generation if there was any
+    #[cfg(feature = "std")]
+    fn flush_code(&mut self) {
+        if !self.code.is_empty() {
+            self.code = Vec::new();
+            self.code_generation = NEXT_CODE_GENERATION.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Discards the cached instructions and compiled code overlapping the addresses
+    #[cfg(feature = "std")]
+    fn invalidate(&mut self, range: Range<usize>) {
+        if self
+            .code
+            .get(range.start..range.end.min(self.code.len()))
+            .is_some_and(|code| code.contains(&true))
+        {
+            self.flush_code();
+        }
+        if self.decoded.is_empty() {
+            return;
+        }
+        let start = range.start.saturating_sub(1);
+        let end = range.end.min(self.decoded.len());
+        if let Some(decoded) = self.decoded.get_mut(start..end) {
+            decoded.fill(None);
+        }
+    }
+
+    /// Nothing is cached without the `std` feature, so there is nothing to discard
+    #[cfg(not(feature = "std"))]
+    const fn invalidate(&mut self, _range: Range<usize>) {}
 }
 
-impl Index<u16> for Memory {
+/// Writes the small and large glyphs at their addresses, which are inside memory in valid memory
+/// maps
+const fn write_font(data: &mut [u8], layout: &MemoryLayout, font: &Font) {
+    let mut glyph_index = 0;
+    while glyph_index < 16 {
+        let mut byte_index = 0;
+        while byte_index < SMALL_GLYPH_SIZE {
+            let address = layout.small_font as usize + glyph_index * SMALL_GLYPH_SIZE + byte_index;
+            data[address] = font.small[glyph_index][byte_index];
+            byte_index += 1;
+        }
+
+        let mut byte_index = 0;
+        while byte_index < LARGE_GLYPH_SIZE {
+            let address = layout.large_font as usize + glyph_index * LARGE_GLYPH_SIZE + byte_index;
+            data[address] = font.large[glyph_index][byte_index];
+            byte_index += 1;
+        }
+
+        glyph_index += 1;
+    }
+}
+
+impl<const SIZE: usize> Index<u16> for Memory<SIZE> {
     type Output = u8;
 
     fn index(&self, index: u16) -> &Self::Output {
         // Load the data from the st
-        self.data
+        self.data()
             .get(usize::from(index))
             .expect("Unreachable address")
     }
 }
 
-impl IndexMut<u16> for Memory {
+impl<const SIZE: usize> IndexMut<u16> for Memory<SIZE> {
     fn index_mut(&mut self, index: u16) -> &mut Self::Output {
         assert!(
-            (0x200..0x1000).contains(&index),
+            self.layout.is_writable(usize::from(index)),
             "Invalid mutable reference to read-only or non-existing memory: {index}"
         );
+        self.invalidate(usize::from(index)..usize::from(index) + 1);
         self.data
             .get_mut(usize::from(index))
             .expect("Unreachable address")
@@ -0,0 +1,397 @@
+//! This module contains input movies, which record the keypad state of every frame so a session
+//! can be played back deterministically.
+//!
+//! A movie file starts with the magic bytes `C8MV` and the format version, followed by the CRC-32
+//! checksum of the application, the quirks, the random seed, the number of instructions per frame
+//! and the keypad state of every frame. All multi-byte values are stored little-endian.
+//!
+//! Version 2 stores the configuration of the machine after the number of instructions per frame:
+//! the memory map, the CRC-32 checksum of the font, the stack limit (0 for unlimited), whether the
+//! stack is mirrored into memory and the CRC-32 checksum of the configuration of the random number
+//! generator. Playback is rejected on machines configured differently. Version 1 movies were
+//! always recorded with the [`crate::rng::Xorshift`] generator, which replaces the generator of
+//! the machine when they are played back.
+
+use crate::{
+    font::Font,
+    keypad::Keypad,
+    machine::{Machine, MachineError, Step},
+    memory::MemoryLayout,
+    quirks::Quirks,
+};
+
+/// The magic bytes every movie starts with
+pub const MAGIC: [u8; 4] = *b"C8MV";
+
+/// The version of the format written by [`Movie::to_bytes`]
+pub const VERSION: u16 = 2;
+
+/// The size of the header before the frames in version 1
+const LEGACY_HEADER_SIZE: usize = 27;
+
+/// The size of the header before the frames
+const HEADER_SIZE: usize = 56;
+
+/// The error returned if a movie can't be loaded or played back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MovieError {
+    /// The data doesn't start with the magic bytes, so it isn't a movie
+    InvalidMagic,
+
+    /// The movie was written by a newer, unknown version of the format
+    UnsupportedVersion(u16),
+
+    /// The movie ended before all frames were read, or contains trailing data
+    InvalidLength,
+
+    /// The movie contains quirks this version doesn't know about
+    InvalidQuirks(u8),
+
+    /// A field of the header contains a value the machine can't be configured with
+    InvalidField(&'static str),
+
+    /// The movie was recorded while running a different application
+    RomMismatch {
+        /// The checksum of the currently loaded application
+        expected: u32,
+
+        /// The checksum stored in the movie
+        found: u32,
+    },
+
+    /// The movie was recorded on a machine configured differently, the field names the
+    /// configuration that differs
+    ConfigurationMismatch(&'static str),
+
+    /// The machine failed while playing back the movie
+    Machine(MachineError),
+}
+
+impl std::fmt::Display for MovieError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidMagic => write!(f, "Not a movie"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "Movie version {version} is not supported, the newest supported version is \
+                 {VERSION}"
+            ),
+            Self::InvalidLength => write!(f, "Movie length doesn't match its frame count"),
+            Self::InvalidQuirks(bits) => write!(f, "Movie contains unknown quirks {bits:08b}"),
+            Self::InvalidField(field) => write!(f, "Movie contains an invalid {field}"),
+            Self::RomMismatch { expected, found } => write!(
+                f,
+                "Movie belongs to a different application (checksum {found:08X}, expected \
+                 {expected:08X})"
+            ),
+            Self::ConfigurationMismatch(field) => {
+                write!(f, "Movie was recorded with a different {field}")
+            }
+            Self::Machine(error) => write!(f, "Playback failed: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for MovieError {}
+
+impl From<MachineError> for MovieError {
+    fn from(error: MachineError) -> Self {
+        Self::Machine(error)
+    }
+}
+
+/// The configuration of the machine a movie was recorded on, which playback doesn't change
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Configuration {
+    /// The memory map
+    layout: MemoryLayout,
+
+    /// The CRC-32 checksum of the small and large glyphs of the font
+    font_checksum: u32,
+
+    /// The maximum depth of the stack, None if unlimited
+    stack_limit: Option<usize>,
+
+    /// Whether the stack is mirrored into memory
+    stack_mirror: bool,
+
+    /// The CRC-32 checksum of the kind and configuration of the random number generator
+    rng_checksum: u32,
+}
+
+impl Configuration {
+    /// Retrieves the configuration of the machine
+    fn of(machine: &Machine) -> Self {
+        Self {
+            layout: machine.memory().layout().clone(),
+            font_checksum: font_checksum(machine.font()),
+            stack_limit: machine.stack().limit(),
+            stack_mirror: machine.stack().vip_mirror(),
+            rng_checksum: crc32fast::hash(&machine.rng().configuration()),
+        }
+    }
+
+    /// Checks whether the machine has this configuration, returns the configuration that differs
+    /// otherwise
+    fn check(&self, machine: &Machine) -> Result<(), MovieError> {
+        let actual = Self::of(machine);
+        for (matches, field) in [
+            (actual.layout == self.layout, "memory map"),
+            (actual.font_checksum == self.font_checksum, "font"),
+            (
+                actual.stack_limit == self.stack_limit && actual.stack_mirror == self.stack_mirror,
+                "stack",
+            ),
+            (
+                actual.rng_checksum == self.rng_checksum,
+                "random number generator",
+            ),
+        ] {
+            if !matches {
+                return Err(MovieError::ConfigurationMismatch(field));
+            }
+        }
+        Ok(())
+    }
+
+    /// Serializes the configuration
+    fn write(&self, bytes: &mut Vec<u8>) {
+        let address = |address: usize| u32::try_from(address).expect("Memory is at most 64 KiB");
+        bytes.extend_from_slice(&self.layout.program_start.to_le_bytes());
+        bytes.extend_from_slice(&self.layout.small_font.to_le_bytes());
+        bytes.extend_from_slice(&self.layout.large_font.to_le_bytes());
+        bytes.extend_from_slice(&address(self.layout.size).to_le_bytes());
+        bytes.extend_from_slice(&address(self.layout.writable.start).to_le_bytes());
+        bytes.extend_from_slice(&address(self.layout.writable.end).to_le_bytes());
+        bytes.extend_from_slice(&self.font_checksum.to_le_bytes());
+        let limit = self.stack_limit.map_or(0, |limit| {
+            u16::try_from(limit).expect("Stack limits are at most 65535 entries")
+        });
+        bytes.extend_from_slice(&limit.to_le_bytes());
+        bytes.push(u8::from(self.stack_mirror));
+        bytes.extend_from_slice(&self.rng_checksum.to_le_bytes());
+    }
+
+    /// Deserializes the 29 bytes of a configuration serialized by [`Configuration::write`]
+    fn read(bytes: &[u8]) -> Result<Self, MovieError> {
+        let u16_at = |offset: usize| u16::from_le_bytes([bytes[offset], bytes[offset + 1]]);
+        let u32_at =
+            |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        let limit = u16_at(22);
+        Ok(Self {
+            layout: MemoryLayout {
+                program_start: u16_at(0),
+                small_font: u16_at(2),
+                large_font: u16_at(4),
+                size: u32_at(6) as usize,
+                writable: u32_at(10) as usize..u32_at(14) as usize,
+            },
+            font_checksum: u32_at(18),
+            stack_limit: (limit != 0).then_some(usize::from(limit)),
+            stack_mirror: match bytes[24] {
+                0 => false,
+                1 => true,
+                _ => return Err(MovieError::InvalidField("stack mirror")),
+            },
+            rng_checksum: u32_at(25),
+        })
+    }
+}
+
+/// Calculates the CRC-32 checksum of the small glyphs followed by the large glyphs of a font
+fn font_checksum(font: &Font) -> u32 {
+    let mut hasher = crc32fast::Hasher::new();
+    for glyph in &font.small {
+        hasher.update(glyph);
+    }
+    for glyph in &font.large {
+        hasher.update(glyph);
+    }
+    hasher.finalize()
+}
+
+/// A recorded session: the settings needed to reproduce it and the keypad state of every frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Movie {
+    /// The CRC-32 checksum of the application
+    rom_checksum: u32,
+
+    /// The quirks the session was recorded with
+    quirks: Quirks,
+
+    /// The seed of the random number generator
+    seed: u64,
+
+    /// The number of instructions executed per frame
+    cycles_per_frame: u32,
+
+    /// The configuration of the machine, None for version 1 movies, which didn't store it
+    configuration: Option<Configuration>,
+
+    /// The keypad state of every frame
+    frames: Vec<Keypad>,
+}
+
+impl Movie {
+    /// Creates an empty movie for the application and settings of the machine.
+    /// The random number generator of the machine is restarted from the seed, so the recording
+    /// starts in a reproducible state.
+    pub fn start_recording(machine: &mut Machine, seed: u64, cycles_per_frame: u32) -> Self {
+        machine.reseed(seed);
+        Self {
+            rom_checksum: machine.rom_checksum(),
+            quirks: machine.quirks(),
+            seed,
+            cycles_per_frame,
+            configuration: Some(Configuration::of(machine)),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Appends the keypad state of a frame
+    pub fn record_frame(&mut self, keypad: Keypad) {
+        self.frames.push(keypad);
+    }
+
+    /// Retrieves the CRC-32 checksum of the application
+    pub const fn rom_checksum(&self) -> u32 {
+        self.rom_checksum
+    }
+
+    /// Retrieves the quirks the session was recorded with
+    pub const fn quirks(&self) -> Quirks {
+        self.quirks
+    }
+
+    /// Retrieves the seed of the random number generator
+    pub const fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Retrieves the number of instructions executed per frame
+    pub const fn cycles_per_frame(&self) -> u32 {
+        self.cycles_per_frame
+    }
+
+    /// Retrieves the keypad state of every frame
+    pub fn frames(&self) -> &[Keypad] {
+        &self.frames
+    }
+
+    /// Prepares a machine that just loaded the application for playback, by applying the quirks
+    /// and seed of the movie. Fails if the machine is configured differently than the machine the
+    /// movie was recorded on.
+    pub fn start_playback(&self, machine: &mut Machine) -> Result<(), MovieError> {
+        if machine.rom_checksum() != self.rom_checksum {
+            return Err(MovieError::RomMismatch {
+                expected: machine.rom_checksum(),
+                found: self.rom_checksum,
+            });
+        }
+        match &self.configuration {
+            Some(configuration) => {
+                configuration.check(machine)?;
+                machine.reseed(self.seed);
+            }
+            None => machine.set_seed(self.seed),
+        }
+        machine.set_quirks(self.quirks);
+        Ok(())
+    }
+
+    /// Executes a single frame of the movie, returns None after the last frame
+    pub fn play_frame(
+        &self,
+        machine: &mut Machine,
+        frame: usize,
+    ) -> Option<Result<Step, MachineError>> {
+        let keypad = self.frames.get(frame)?;
+        *machine.keypad_mut() = *keypad;
+        Some(machine.run_frame(self.cycles_per_frame))
+    }
+
+    /// Plays back the whole movie on a machine that just loaded the application.
+    /// Stops early if the application exits.
+    pub fn play(&self, machine: &mut Machine) -> Result<Step, MovieError> {
+        self.start_playback(machine)?;
+        let mut step = Step::Continue;
+        for frame in 0..self.frames.len() {
+            if let Some(result) = self.play_frame(machine, frame) {
+                step = result?;
+            }
+            if step == Step::Exit {
+                break;
+            }
+        }
+        Ok(step)
+    }
+
+    /// Serializes the movie. Version 1 movies are written in version 1, as their configuration
+    /// is unknown.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + self.frames.len() * 2);
+        bytes.extend_from_slice(&MAGIC);
+        let version: u16 = if self.configuration.is_some() {
+            VERSION
+        } else {
+            1
+        };
+        bytes.extend_from_slice(&version.to_le_bytes());
+        bytes.extend_from_slice(&self.rom_checksum.to_le_bytes());
+        bytes.push(self.quirks.to_bits());
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&self.cycles_per_frame.to_le_bytes());
+        if let Some(configuration) = &self.configuration {
+            configuration.write(&mut bytes);
+        }
+        bytes.extend_from_slice(
+            &u32::try_from(self.frames.len())
+                .expect("Movies are at most 2^32 frames long")
+                .to_le_bytes(),
+        );
+        for keypad in &self.frames {
+            bytes.extend_from_slice(&keypad.bits().to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Deserializes a movie serialized by [`Movie::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MovieError> {
+        if !bytes.starts_with(&MAGIC) {
+            return Err(MovieError::InvalidMagic);
+        }
+        let version = bytes
+            .get(4..6)
+            .map(|version| u16::from_le_bytes([version[0], version[1]]))
+            .ok_or(MovieError::InvalidLength)?;
+        let header_size = match version {
+            1 => LEGACY_HEADER_SIZE,
+            VERSION => HEADER_SIZE,
+            _ => return Err(MovieError::UnsupportedVersion(version)),
+        };
+        let (header, frames) = bytes
+            .split_at_checked(header_size)
+            .ok_or(MovieError::InvalidLength)?;
+        let quirks = Quirks::from_bits(header[10]).ok_or(MovieError::InvalidQuirks(header[10]))?;
+        let configuration = if version == 1 {
+            None
+        } else {
+            Some(Configuration::read(&header[23..header_size - 4])?)
+        };
+        let frame_count = u32::from_le_bytes(header[header_size - 4..].try_into().unwrap());
+        if usize::try_from(frame_count).map(|count| count * 2) != Ok(frames.len()) {
+            return Err(MovieError::InvalidLength);
+        }
+        Ok(Self {
+            rom_checksum: u32::from_le_bytes(header[6..10].try_into().unwrap()),
+            quirks,
+            seed: u64::from_le_bytes(header[11..19].try_into().unwrap()),
+            cycles_per_frame: u32::from_le_bytes(header[19..23].try_into().unwrap()),
+            configuration,
+            frames: frames
+                .chunks_exact(2)
+                .map(|frame| Keypad::from_bits(u16::from_le_bytes([frame[0], frame[1]])))
+                .collect(),
+        })
+    }
+}
@@ -0,0 +1,743 @@
+//! This module contains a compiler for Octo, the assembly language the programs of Octo
+//! cartridges are written in.
+//!
+//! The instructions, control structures and directives of chip-8 and super chip-48 are supported.
+//! Macros, `:calc`, `:assert` and `:stringmode` need the expression language of Octo and XO-CHIP
+//! instructions need an XO-CHIP machine, so sources using them are rejected.
+//!
+//! Like Octo, applications start with a jump to the `main` label, unless `main` is the first
+//! label and nothing precedes it. Comparisons other than equality use VF as a scratch register.
+
+use std::collections::HashMap;
+
+/// The address applications are compiled for
+const PROGRAM_START: u16 = 0x200;
+
+/// The largest address instructions can refer to
+const MAX_ADDRESS: u16 = 0xFFF;
+
+/// The error returned if a source can't be compiled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileError {
+    /// The line the error was found in, counted from 1
+    pub line: usize,
+
+    /// The description of the error
+    pub message: String,
+}
+
+impl std::fmt::Display for CompileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+/// The way a reference to a label is filled in once the label is defined
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FixupKind {
+    /// The lowest 12 bits of an instruction
+    Address,
+
+    /// Both bytes of the instruction after the first one of `:unpack`
+    Unpack,
+
+    /// A 16-bit address stored by `:pointer`
+    Pointer,
+}
+
+/// A reference to a label that wasn't defined yet
+#[derive(Debug, Clone)]
+struct Fixup {
+    /// The address of the bytes to fill in
+    address: u16,
+
+    /// The way the bytes are filled in
+    kind: FixupKind,
+
+    /// The name of the label
+    label: String,
+
+    /// The line of the reference, for errors
+    line: usize,
+}
+
+/// An open control structure
+#[derive(Debug, Clone)]
+enum Flow {
+    /// An `if ... begin`, with the address of the jump to its `else` or `end`
+    If(u16),
+
+    /// An `else`, with the address of the jump to its `end`
+    Else(u16),
+
+    /// A `loop`, with its start and the addresses of the jumps of its `while` conditions
+    Loop(u16, Vec<u16>),
+}
+
+/// The operand of an instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operand {
+    /// A general purpose register
+    Register(u8),
+
+    /// A number
+    Number(i32),
+}
+
+/// A compiled condition of `if` or `while`
+#[derive(Debug, Clone, Default)]
+struct Condition {
+    /// The instructions computing the condition
+    prefix: Vec<u16>,
+
+    /// The instruction skipping the next one if the condition holds
+    skip_if_true: u16,
+
+    /// The instruction skipping the next one if the condition doesn't hold
+    skip_if_false: u16,
+}
+
+/// Compiles an Octo source into an application loaded at 0x200
+pub fn compile(source: &str) -> Result<Vec<u8>, CompileError> {
+    let tokens = source
+        .lines()
+        .enumerate()
+        .flat_map(|(index, line)| {
+            line.split('#')
+                .next()
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(move |token| (index + 1, token))
+        })
+        .collect();
+    let mut compiler = Compiler {
+        tokens,
+        position: 0,
+        line: 1,
+        rom: vec![0; 2],
+        here: PROGRAM_START + 2,
+        main_jump: true,
+        labels: HashMap::new(),
+        constants: HashMap::new(),
+        aliases: HashMap::new(),
+        fixups: Vec::new(),
+        flow: Vec::new(),
+    };
+    while compiler.position < compiler.tokens.len() {
+        compiler.statement()?;
+    }
+    compiler.finish()
+}
+
+/// The state of a compilation
+struct Compiler<'a> {
+    /// The tokens of the source with their line
+    tokens: Vec<(usize, &'a str)>,
+
+    /// The index of the next token
+    position: usize,
+
+    /// The line of the last token
+    line: usize,
+
+    /// The compiled bytes, starting at the program start
+    rom: Vec<u8>,
+
+    /// The address the next byte is compiled to
+    here: u16,
+
+    /// Whether the first instruction is a jump to `main`
+    main_jump: bool,
+
+    /// The addresses of the labels
+    labels: HashMap<&'a str, u16>,
+
+    /// The values of the constants
+    constants: HashMap<&'a str, i32>,
+
+    /// The registers of the aliases
+    aliases: HashMap<&'a str, u8>,
+
+    /// The references to labels that weren't defined yet
+    fixups: Vec<Fixup>,
+
+    /// The open control structures, the innermost last
+    flow: Vec<Flow>,
+}
+
+impl<'a> Compiler<'a> {
+    /// Creates an error at the line of the last token
+    fn error(&self, message: impl Into<String>) -> CompileError {
+        CompileError {
+            line: self.line,
+            message: message.into(),
+        }
+    }
+
+    /// Reads the next token
+    fn next(&mut self) -> Result<&'a str, CompileError> {
+        let &(line, token) = self
+            .tokens
+            .get(self.position)
+            .ok_or_else(|| self.error("Unexpected end of the source"))?;
+        self.position += 1;
+        self.line = line;
+        Ok(token)
+    }
+
+    /// Reads the next token, which has to be the expected one
+    fn expect(&mut self, expected: &str) -> Result<(), CompileError> {
+        let token = self.next()?;
+        if token == expected {
+            Ok(())
+        } else {
+            Err(self.error(format!("Expected {expected}, found {token}")))
+        }
+    }
+
+    /// Retrieves the next token without reading it
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.position).map(|&(_, token)| token)
+    }
+
+    /// Compiles a byte at the current address
+    fn byte(&mut self, byte: u8) -> Result<(), CompileError> {
+        let index = usize::from(
+            self.here
+                .checked_sub(PROGRAM_START)
+                .ok_or_else(|| self.error("Code before the program start"))?,
+        );
+        if self.rom.len() <= index {
+            self.rom.resize(index + 1, 0);
+        }
+        self.rom[index] = byte;
+        self.here = self
+            .here
+            .checked_add(1)
+            .ok_or_else(|| self.error("Code past the end of memory"))?;
+        Ok(())
+    }
+
+    /// Compiles an instruction at the current address
+    fn instruction(&mut self, instruction: u16) -> Result<(), CompileError> {
+        let [high, low] = instruction.to_be_bytes();
+        self.byte(high)?;
+        self.byte(low)
+    }
+
+    /// Retrieves the compiled word at the address
+    fn word(&self, address: u16) -> u16 {
+        let index = usize::from(address - PROGRAM_START);
+        u16::from_be_bytes([self.rom[index], self.rom[index + 1]])
+    }
+
+    /// Overwrites the compiled word at the address
+    fn set_word(&mut self, address: u16, word: u16) {
+        let index = usize::from(address - PROGRAM_START);
+        self.rom[index..index + 2].copy_from_slice(&word.to_be_bytes());
+    }
+
+    /// Fills in the lowest 12 bits of the instruction at the address
+    fn patch(&mut self, address: u16, target: u16) -> Result<(), CompileError> {
+        if target > MAX_ADDRESS {
+            return Err(self.error(format!("Address {target:X} is out of reach")));
+        }
+        self.set_word(address, self.word(address) & 0xF000 | target);
+        Ok(())
+    }
+
+    /// Defines a label at the address
+    fn define(&mut self, name: &'a str, address: u16) -> Result<(), CompileError> {
+        if self.labels.insert(name, address).is_some() {
+            return Err(self.error(format!("The label {name} is defined twice")));
+        }
+        Ok(())
+    }
+
+    /// Parses a number literal
+    fn number(token: &str) -> Option<i32> {
+        let (negative, digits) = match token.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, token),
+        };
+        let value = if let Some(hex) = digits.strip_prefix("0x") {
+            i32::from_str_radix(hex, 16).ok()?
+        } else if let Some(binary) = digits.strip_prefix("0b") {
+            i32::from_str_radix(binary, 2).ok()?
+        } else if digits.starts_with(|digit: char| digit.is_ascii_digit()) {
+            digits.parse().ok()?
+        } else {
+            return None;
+        };
+        Some(if negative { -value } else { value })
+    }
+
+    /// Parses the name of a register or an alias of one
+    fn register_name(&self, token: &str) -> Option<u8> {
+        if let Some(&register) = self.aliases.get(token) {
+            return Some(register);
+        }
+        match token.as_bytes() {
+            [b'v' | b'V', digit] => (*digit as char).to_digit(16).map(|digit| digit as u8),
+            _ => None,
+        }
+    }
+
+    /// Reads a register
+    fn register(&mut self) -> Result<u8, CompileError> {
+        let token = self.next()?;
+        self.register_name(token)
+            .ok_or_else(|| self.error(format!("Expected a register, found {token}")))
+    }
+
+    /// Reads a number or constant
+    fn value(&mut self) -> Result<i32, CompileError> {
+        let token = self.next()?;
+        Self::number(token)
+            .or_else(|| self.constants.get(token).copied())
+            .ok_or_else(|| self.error(format!("Expected a number, found {token}")))
+    }
+
+    /// Reads a number or constant fitting in the bits, negative numbers are stored as two's
+    /// complement
+    fn sized_value(&mut self, bits: u32) -> Result<u16, CompileError> {
+        let value = self.value()?;
+        if value < -(1 << (bits - 1)) || value >= 1 << bits {
+            return Err(self.error(format!("{value} doesn't fit in {bits} bits")));
+        }
+        Ok((value & ((1 << bits) - 1)) as u16)
+    }
+
+    /// Reads a register, number or constant
+    fn operand(&mut self) -> Result<Operand, CompileError> {
+        let token = self.peek().unwrap_or_default();
+        if let Some(register) = self.register_name(token) {
+            self.next()?;
+            Ok(Operand::Register(register))
+        } else {
+            self.value().map(Operand::Number)
+        }
+    }
+
+    /// Reads the target address of an instruction and compiles the instruction, filling in the
+    /// address once the label is defined
+    fn address_instruction(&mut self, opcode: u16) -> Result<(), CompileError> {
+        let token = self.next()?;
+        let address = self.here;
+        self.instruction(opcode)?;
+        if let Some(&target) = self.labels.get(token) {
+            return self.patch(address, target);
+        }
+        if let Some(value) = Self::number(token).or_else(|| self.constants.get(token).copied()) {
+            let target = u16::try_from(value)
+                .ok()
+                .filter(|&target| target <= MAX_ADDRESS)
+                .ok_or_else(|| self.error(format!("Address {value} is out of reach")))?;
+            return self.patch(address, target);
+        }
+        self.fixups.push(Fixup {
+            address,
+            kind: FixupKind::Address,
+            label: token.to_owned(),
+            line: self.line,
+        });
+        Ok(())
+    }
+
+    /// Compiles a jump whose target is filled in later, returns its address
+    fn forward_jump(&mut self) -> Result<u16, CompileError> {
+        let address = self.here;
+        self.instruction(0x1000)?;
+        Ok(address)
+    }
+
+    /// Compiles the next statement
+    fn statement(&mut self) -> Result<(), CompileError> {
+        let token = self.next()?;
+        match token {
+            ":" => {
+                let name = self.next()?;
+                if name == "main"
+                    && self.main_jump
+                    && self.here == PROGRAM_START + 2
+                    && self.rom.len() == 2
+                    && self.labels.is_empty()
+                {
+                    // Nothing precedes main, so there is no need to jump to it
+                    self.main_jump = false;
+                    self.rom.clear();
+                    self.here = PROGRAM_START;
+                }
+                self.define(name, self.here)?;
+            }
+            ":next" => {
+                let name = self.next()?;
+                self.define(name, self.here + 1)?;
+            }
+            ":const" => {
+                let name = self.next()?;
+                let value = self.value()?;
+                self.constants.insert(name, value);
+            }
+            ":alias" => {
+                let name = self.next()?;
+                let register = self.register()?;
+                self.aliases.insert(name, register);
+            }
+            ":unpack" => {
+                let high = self.sized_value(4)?;
+                let token = self.next()?;
+                let address = self.here;
+                self.instruction(0x6000 | high << 4)?;
+                self.instruction(0x6100)?;
+                match self.labels.get(token) {
+                    Some(&target) => self.unpack(address, target),
+                    None => self.fixups.push(Fixup {
+                        address,
+                        kind: FixupKind::Unpack,
+                        label: token.to_owned(),
+                        line: self.line,
+                    }),
+                }
+            }
+            ":org" => {
+                self.here = u16::try_from(self.value()?)
+                    .ok()
+                    .filter(|&address| address >= PROGRAM_START)
+                    .ok_or_else(|| self.error("Code before the program start"))?;
+            }
+            ":byte" => {
+                let byte = self.sized_value(8)?;
+                self.byte(byte as u8)?;
+            }
+            ":pointer" => {
+                let token = self.next()?;
+                let address = self.here;
+                self.instruction(0)?;
+                match self.labels.get(token) {
+                    Some(&target) => self.set_word(address, target),
+                    None => self.fixups.push(Fixup {
+                        address,
+                        kind: FixupKind::Pointer,
+                        label: token.to_owned(),
+                        line: self.line,
+                    }),
+                }
+            }
+            ":call" => self.address_instruction(0x2000)?,
+            ":breakpoint" => {
+                self.next()?;
+            }
+            ":monitor" => {
+                self.next()?;
+                self.next()?;
+            }
+            ":macro" | ":calc" | ":assert" | ":stringmode" | "{" => {
+                return Err(self.error(format!("{token} needs the expression language of Octo")));
+            }
+            "clear" => self.instruction(0x00E0)?,
+            "return" | ";" => self.instruction(0x00EE)?,
+            "exit" => self.instruction(0x00FD)?,
+            "lores" => self.instruction(0x00FE)?,
+            "hires" => self.instruction(0x00FF)?,
+            "scroll-right" => self.instruction(0x00FB)?,
+            "scroll-left" => self.instruction(0x00FC)?,
+            "scroll-down" => {
+                let rows = self.sized_value(4)?;
+                self.instruction(0x00C0 | rows)?;
+            }
+            "scroll-up" | "plane" | "audio" | "pitch" => {
+                return Err(self.error(format!("{token} needs XO-CHIP")));
+            }
+            "jump" => self.address_instruction(0x1000)?,
+            "jump0" => self.address_instruction(0xB000)?,
+            "native" => self.address_instruction(0x0000)?,
+            "sprite" => {
+                let x = u16::from(self.register()?);
+                let y = u16::from(self.register()?);
+                let height = self.sized_value(4)?;
+                self.instruction(0xD000 | x << 8 | y << 4 | height)?;
+            }
+            "bcd" | "save" | "load" | "saveflags" | "loadflags" => {
+                let register = u16::from(self.register()?);
+                if self.peek() == Some("-") {
+                    return Err(self.error("Register ranges need XO-CHIP"));
+                }
+                let operation = match token {
+                    "bcd" => 0x33,
+                    "save" => 0x55,
+                    "load" => 0x65,
+                    "saveflags" => 0x75,
+                    _ => 0x85,
+                };
+                self.instruction(0xF000 | register << 8 | operation)?;
+            }
+            "delay" | "buzzer" => {
+                self.expect(":=")?;
+                let register = u16::from(self.register()?);
+                let operation = if token == "delay" { 0x15 } else { 0x18 };
+                self.instruction(0xF000 | register << 8 | operation)?;
+            }
+            "i" => self.address_register()?,
+            "if" => {
+                let condition = self.condition()?;
+                for instruction in condition.prefix {
+                    self.instruction(instruction)?;
+                }
+                match self.next()? {
+                    "then" => self.instruction(condition.skip_if_false)?,
+                    "begin" => {
+                        self.instruction(condition.skip_if_true)?;
+                        let jump = self.forward_jump()?;
+                        self.flow.push(Flow::If(jump));
+                    }
+                    other => {
+                        return Err(self.error(format!("Expected then or begin, found {other}")));
+                    }
+                }
+            }
+            "else" => {
+                let Some(Flow::If(jump)) = self.flow.pop() else {
+                    return Err(self.error("else without if ... begin"));
+                };
+                let end = self.forward_jump()?;
+                self.patch(jump, self.here)?;
+                self.flow.push(Flow::Else(end));
+            }
+            "end" => match self.flow.pop() {
+                Some(Flow::If(jump) | Flow::Else(jump)) => self.patch(jump, self.here)?,
+                _ => return Err(self.error("end without if ... begin")),
+            },
+            "loop" => self.flow.push(Flow::Loop(self.here, Vec::new())),
+            "while" => {
+                let condition = self.condition()?;
+                for instruction in condition.prefix {
+                    self.instruction(instruction)?;
+                }
+                self.instruction(condition.skip_if_true)?;
+                let jump = self.forward_jump()?;
+                match self
+                    .flow
+                    .iter_mut()
+                    .rev()
+                    .find(|flow| matches!(flow, Flow::Loop(..)))
+                {
+                    Some(Flow::Loop(_, exits)) => exits.push(jump),
+                    _ => return Err(self.error("while outside of a loop")),
+                }
+            }
+            "again" => {
+                let Some(Flow::Loop(start, exits)) = self.flow.pop() else {
+                    return Err(self.error("again without loop"));
+                };
+                let jump = self.forward_jump()?;
+                self.patch(jump, start)?;
+                for exit in exits {
+                    self.patch(exit, self.here)?;
+                }
+            }
+            _ => {
+                if let Some(register) = self.register_name(token) {
+                    self.assignment(register)?;
+                } else if let Some(value) =
+                    Self::number(token).or_else(|| self.constants.get(token).copied())
+                {
+                    if !(-128..=255).contains(&value) {
+                        return Err(self.error(format!("{value} doesn't fit in a byte")));
+                    }
+                    self.byte(value as u8)?;
+                } else {
+                    // Any other name calls the subroutine of the label
+                    self.position -= 1;
+                    self.address_instruction(0x2000)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Fills in the address of `:unpack` at the address
+    fn unpack(&mut self, address: u16, target: u16) {
+        let high = self.word(address) | target >> 8 & 0xF;
+        self.set_word(address, high);
+        self.set_word(address + 2, 0x6100 | target & 0xFF);
+    }
+
+    /// Compiles an operation on I
+    fn address_register(&mut self) -> Result<(), CompileError> {
+        match self.next()? {
+            ":=" => match self.peek() {
+                Some("hex") => {
+                    self.next()?;
+                    let register = u16::from(self.register()?);
+                    self.instruction(0xF029 | register << 8)
+                }
+                Some("bighex") => {
+                    self.next()?;
+                    let register = u16::from(self.register()?);
+                    self.instruction(0xF030 | register << 8)
+                }
+                Some("long") => Err(self.error("long addresses need XO-CHIP")),
+                _ => self.address_instruction(0xA000),
+            },
+            "+=" => {
+                let register = u16::from(self.register()?);
+                self.instruction(0xF01E | register << 8)
+            }
+            other => Err(self.error(format!("Unknown operation on i: {other}"))),
+        }
+    }
+
+    /// Compiles an operation on a register
+    fn assignment(&mut self, register: u8) -> Result<(), CompileError> {
+        let x = u16::from(register) << 8;
+        let operator = self.next()?;
+        match (operator, self.peek()) {
+            (":=", Some("random")) => {
+                self.next()?;
+                let mask = self.sized_value(8)?;
+                return self.instruction(0xC000 | x | mask);
+            }
+            (":=", Some("delay")) => {
+                self.next()?;
+                return self.instruction(0xF007 | x);
+            }
+            (":=", Some("key")) => {
+                self.next()?;
+                return self.instruction(0xF00A | x);
+            }
+            _ => {}
+        }
+        let operation = match operator {
+            ":=" => 0x0,
+            "|=" => 0x1,
+            "&=" => 0x2,
+            "^=" => 0x3,
+            "+=" => 0x4,
+            "-=" => 0x5,
+            ">>=" => 0x6,
+            "=-" => 0x7,
+            "<<=" => 0xE,
+            _ => return Err(self.error(format!("Unknown operator {operator}"))),
+        };
+        match self.operand()? {
+            Operand::Register(y) => self.instruction(0x8000 | x | u16::from(y) << 4 | operation),
+            Operand::Number(value) => {
+                let value = match operator {
+                    ":=" | "+=" => value,
+                    "-=" => -value,
+                    _ => return Err(self.error(format!("{operator} needs a register"))),
+                };
+                if !(-128..=255).contains(&value) {
+                    return Err(self.error(format!("{value} doesn't fit in a byte")));
+                }
+                let opcode = if operator == ":=" { 0x6000 } else { 0x7000 };
+                self.instruction(opcode | x | u16::from(value as u8))
+            }
+        }
+    }
+
+    /// Compiles the condition of `if` or `while`
+    fn condition(&mut self) -> Result<Condition, CompileError> {
+        let x = self.register()?;
+        let operator = self.next()?;
+        let register = u16::from(x) << 8;
+        let skip = |if_true: u16, if_false: u16| Condition {
+            prefix: Vec::new(),
+            skip_if_true: if_true,
+            skip_if_false: if_false,
+        };
+        match operator {
+            "key" => return Ok(skip(0xE09E | register, 0xE0A1 | register)),
+            "-key" => return Ok(skip(0xE0A1 | register, 0xE09E | register)),
+            _ => {}
+        }
+        let operand = self.operand()?;
+        let (equal, not_equal) = match operand {
+            Operand::Register(y) => {
+                let y = u16::from(y) << 4;
+                (0x5000 | register | y, 0x9000 | register | y)
+            }
+            Operand::Number(value) if (-128..=255).contains(&value) => {
+                let byte = u16::from(value as u8);
+                (0x3000 | register | byte, 0x4000 | register | byte)
+            }
+            Operand::Number(value) => {
+                return Err(self.error(format!("{value} doesn't fit in a byte")));
+            }
+        };
+        // VF holds whether the left side minus the right side doesn't borrow
+        let (left, right, borrow_if_true) = match operator {
+            "==" => return Ok(skip(equal, not_equal)),
+            "!=" => return Ok(skip(not_equal, equal)),
+            "<" => (Operand::Register(x), operand, true),
+            ">=" => (Operand::Register(x), operand, false),
+            ">" => (operand, Operand::Register(x), true),
+            "<=" => (operand, Operand::Register(x), false),
+            _ => return Err(self.error(format!("Unknown comparison {operator}"))),
+        };
+        let prefix = match (left, right) {
+            (Operand::Register(left), Operand::Register(right)) => vec![
+                0x8F00 | u16::from(left) << 4,
+                0x8F05 | u16::from(right) << 4,
+            ],
+            (Operand::Register(left), Operand::Number(right)) => {
+                vec![
+                    0x6F00 | u16::from(right as u8),
+                    0x8F07 | u16::from(left) << 4,
+                ]
+            }
+            (Operand::Number(left), Operand::Register(right)) => {
+                vec![
+                    0x6F00 | u16::from(left as u8),
+                    0x8F05 | u16::from(right) << 4,
+                ]
+            }
+            (Operand::Number(_), Operand::Number(_)) => unreachable!("One side is a register"),
+        };
+        let (borrow, no_borrow) = (0x3F00, 0x3F01);
+        Ok(if borrow_if_true {
+            Condition {
+                prefix,
+                skip_if_true: borrow,
+                skip_if_false: no_borrow,
+            }
+        } else {
+            Condition {
+                prefix,
+                skip_if_true: no_borrow,
+                skip_if_false: borrow,
+            }
+        })
+    }
+
+    /// Fills in the references to labels and the jump to `main`
+    fn finish(mut self) -> Result<Vec<u8>, CompileError> {
+        if !self.flow.is_empty() {
+            return Err(self.error("Unterminated if ... begin or loop"));
+        }
+        for fixup in std::mem::take(&mut self.fixups) {
+            self.line = fixup.line;
+            let target = *self
+                .labels
+                .get(fixup.label.as_str())
+                .ok_or_else(|| self.error(format!("Undefined name {}", fixup.label)))?;
+            match fixup.kind {
+                FixupKind::Address => self.patch(fixup.address, target)?,
+                FixupKind::Unpack => self.unpack(fixup.address, target),
+                FixupKind::Pointer => self.set_word(fixup.address, target),
+            }
+        }
+        if self.main_jump {
+            let main = *self
+                .labels
+                .get("main")
+                .ok_or_else(|| self.error("The source doesn't define main"))?;
+            self.set_word(PROGRAM_START, 0x1000);
+            self.patch(PROGRAM_START, main)?;
+        }
+        Ok(self.rom)
+    }
+}
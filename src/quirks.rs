@@ -0,0 +1,107 @@
+//! This module contains the behavioural differences between chip-8 interpreters
+
+/// The configurable behaviour that differs between the original and later interpreters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quirks {
+    /// The logical instructions (or, and, xor) reset VF to 0
+    pub vf_reset: bool,
+
+    /// Loading and storing registers from and to memory increments I
+    pub load_store_increments_i: bool,
+
+    /// The shift instructions shift vy and store the result in vx, instead of shifting vx
+    pub shift_uses_vy: bool,
+
+    /// The jump with offset instruction adds vx instead of v0, x being the highest nibble of the
+    /// address
+    pub jump_uses_vx: bool,
+
+    /// Sprites are clipped at the edges of the display instead of wrapping around
+    pub clip_sprites: bool,
+
+    /// Loading and storing registers increments I by x instead of x + 1, if it increments I
+    pub increment_i_by_x: bool,
+
+    /// Drawing a sprite waits for the display to refresh, so at most one sprite is drawn per
+    /// frame
+    pub display_wait: bool,
+}
+
+impl Default for Quirks {
+    fn default() -> Self {
+        Self::VIP
+    }
+}
+
+impl Quirks {
+    /// The behaviour of the original COSMAC VIP interpreter
+    pub const VIP: Self = Self {
+        vf_reset: true,
+        load_store_increments_i: true,
+        shift_uses_vy: true,
+        jump_uses_vx: false,
+        clip_sprites: true,
+        increment_i_by_x: false,
+        display_wait: true,
+    };
+
+    /// The behaviour of the super chip-48 interpreter
+    pub const SCHIP: Self = Self {
+        vf_reset: false,
+        load_store_increments_i: false,
+        shift_uses_vy: false,
+        jump_uses_vx: true,
+        clip_sprites: true,
+        increment_i_by_x: false,
+        display_wait: false,
+    };
+
+    /// The behaviour of XO-CHIP and most modern interpreters
+    pub const XO_CHIP: Self = Self {
+        vf_reset: false,
+        load_store_increments_i: true,
+        shift_uses_vy: true,
+        jump_uses_vx: false,
+        clip_sprites: false,
+        increment_i_by_x: false,
+        display_wait: false,
+    };
+
+    /// Retrieves the preset with the given name (vip, schip or xo-chip), case insensitive
+    #[cfg(feature = "std")]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "vip" => Some(Self::VIP),
+            "schip" => Some(Self::SCHIP),
+            "xo-chip" | "xochip" => Some(Self::XO_CHIP),
+            _ => None,
+        }
+    }
+
+    /// Packs the quirks into a byte, one bit per quirk in the order of the fields
+    pub const fn to_bits(self) -> u8 {
+        self.vf_reset as u8
+            | (self.load_store_increments_i as u8) << 1
+            | (self.shift_uses_vy as u8) << 2
+            | (self.jump_uses_vx as u8) << 3
+            | (self.clip_sprites as u8) << 4
+            | (self.increment_i_by_x as u8) << 5
+            | (self.display_wait as u8) << 6
+    }
+
+    /// Unpacks quirks packed by [`Quirks::to_bits`], returns None if unknown bits are set
+    pub const fn from_bits(bits: u8) -> Option<Self> {
+        if bits >> 7 != 0 {
+            return None;
+        }
+        Some(Self {
+            vf_reset: bits & 1 != 0,
+            load_store_increments_i: bits >> 1 & 1 != 0,
+            shift_uses_vy: bits >> 2 & 1 != 0,
+            jump_uses_vx: bits >> 3 & 1 != 0,
+            clip_sprites: bits >> 4 & 1 != 0,
+            increment_i_by_x: bits >> 5 & 1 != 0,
+            display_wait: bits >> 6 & 1 != 0,
+        })
+    }
+}
@@ -0,0 +1,654 @@
+//! This module contains the static recompiler, which translates an application into a Rust module
+//! linking against this crate.
+//!
+//! The code reachable from the program start is split into basic blocks, and the blocks are
+//! grouped into routines: the program start and every called address. Every routine becomes a
+//! function executing its blocks natively. Only register arithmetic and control flow are
+//! translated into Rust, all other instructions are executed by
+//! [`Machine::execute_at`](crate::machine::Machine::execute_at).
+//!
+//! Blocks are only executed natively if their bytes are still those of the application and the
+//! remaining cycles of the frame fit the whole block. Everything else, like code reached by
+//! computed jumps or overwritten by the application, is executed by the interpreter, so the
+//! recompiled application behaves exactly like the interpreted one.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::{self, Write},
+};
+
+use crate::{instruction::Instruction, memory::MemoryLayout, quirks::Quirks, rom::Rom};
+
+/// The maximum number of instructions in a block, frames must execute at least this many
+/// instructions for long blocks to be executed natively
+const MAX_BLOCK_LENGTH: usize = 8;
+
+/// A straight-line sequence of instructions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    /// The address of the first instruction
+    pub start: u16,
+
+    /// The instructions
+    pub instructions: Vec<Instruction>,
+}
+
+impl Block {
+    /// Retrieves the address after the last instruction
+    pub fn end(&self) -> u16 {
+        self.start.wrapping_add(2 * self.instructions.len() as u16)
+    }
+
+    /// Retrieves the addresses the block can continue at within its routine, not including calls
+    /// and computed jumps
+    pub fn successors(&self) -> Vec<u16> {
+        let next = self.end();
+        match self.instructions.last() {
+            Some(Instruction::JumpAddress(target)) => vec![*target],
+            Some(
+                Instruction::SkipEqualRegByte(..)
+                | Instruction::SkipNotEqualRegByte(..)
+                | Instruction::SkipEqualRegisters(_)
+                | Instruction::SkipNotEqualReg(_)
+                | Instruction::SkipPressed(_)
+                | Instruction::SkipNotPressed(_),
+            ) => vec![next, next.wrapping_add(2)],
+            Some(Instruction::Return | Instruction::JumpAddressOffset(_) | Instruction::Exit) => {
+                Vec::new()
+            }
+            _ => vec![next],
+        }
+    }
+}
+
+/// A function: the blocks reachable from its entry without calling another routine
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Routine {
+    /// The address of the first instruction
+    pub entry: u16,
+
+    /// The blocks, sorted by address. Blocks reachable from several routines belong to the first.
+    pub blocks: Vec<Block>,
+}
+
+/// Retrieves whether the instruction may continue with anything but the next instruction
+const fn transfers_control(instruction: Instruction) -> bool {
+    matches!(
+        instruction,
+        Instruction::Return
+            | Instruction::JumpAddress(_)
+            | Instruction::CallAddress(_)
+            | Instruction::SkipEqualRegByte(..)
+            | Instruction::SkipNotEqualRegByte(..)
+            | Instruction::SkipEqualRegisters(_)
+            | Instruction::SkipNotEqualReg(_)
+            | Instruction::JumpAddressOffset(_)
+            | Instruction::SkipPressed(_)
+            | Instruction::SkipNotPressed(_)
+            | Instruction::LoadKeyPress(_)
+            | Instruction::Exit
+    )
+}
+
+/// Retrieves whether the instruction ends a block: it transfers control or writes memory, which
+/// may modify the code after it
+const fn ends_block(instruction: Instruction) -> bool {
+    transfers_control(instruction)
+        || matches!(
+            instruction,
+            Instruction::LoadRegisterSprites(_) | Instruction::LoadMemoryRegisters(_)
+        )
+}
+
+/// Formats a register as Rust code indexing the registers
+fn reg(register: u8) -> String {
+    format!("v[0x{:X}]", register & 0xF)
+}
+
+/// Formats the instruction as Rust code constructing it, with hexadecimal operands
+fn instruction_code(instruction: Instruction) -> String {
+    let debug = format!("{instruction:?}");
+    let Some((name, operands)) = debug.split_once('(') else {
+        return format!("Instruction::{debug}");
+    };
+    let operands = operands
+        .trim_end_matches(')')
+        .split(", ")
+        .map(|operand| match operand.parse::<u16>() {
+            Ok(value) => format!("0x{value:X}"),
+            Err(_) => operand.to_owned(),
+        })
+        .collect::<Vec<_>>();
+    format!("Instruction::{name}({})", operands.join(", "))
+}
+
+/// Translates the instruction at the address into Rust statements, None if it is executed by the
+/// machine instead
+fn translate(address: u16, instruction: Instruction, quirks: Quirks) -> Option<Vec<String>> {
+    let next = address.wrapping_add(2);
+    let skip = |condition: String| {
+        vec![
+            format!("let skip = {condition};"),
+            format!(
+                "machine.set_program_counter(if skip {{ 0x{:03X} }} else {{ 0x{next:03X} }});",
+                next.wrapping_add(2)
+            ),
+        ]
+    };
+    let logical = |regs: u8, operator: &str| {
+        let (x, y) = (reg(regs >> 4), reg(regs));
+        let mut lines = match (x == y, operator) {
+            (false, _) => vec![format!("{x} {operator}= {y};")],
+            (true, "^") => vec![format!("{x} = 0;")],
+            (true, _) => Vec::new(),
+        };
+        if quirks.vf_reset {
+            lines.push("v[0xF] = 0;".to_owned());
+        }
+        lines
+    };
+    let arithmetic = |target: u8, left: String, right: String, operation: &str, flag: &str| {
+        vec![
+            format!("let (result, flag) = {left}.{operation}({right});"),
+            format!("{} = result;", reg(target)),
+            format!("v[0xF] = u8::from({flag});"),
+        ]
+    };
+    let shift = |regs: u8, operation: &str, flag: &str| {
+        let source = if quirks.shift_uses_vy {
+            regs
+        } else {
+            regs >> 4
+        };
+        vec![
+            format!("let source = {};", reg(source)),
+            format!("{} = source {operation};", reg(regs >> 4)),
+            format!("v[0xF] = source {flag};"),
+        ]
+    };
+    Some(match instruction {
+        Instruction::SystemAddress(_) => Vec::new(),
+        Instruction::JumpAddress(target) => {
+            vec![format!("machine.set_program_counter(0x{target:03X});")]
+        }
+        Instruction::SkipEqualRegByte(x, byte) => skip(format!("{} == 0x{byte:02X}", reg(x))),
+        Instruction::SkipNotEqualRegByte(x, byte) => skip(format!("{} != 0x{byte:02X}", reg(x))),
+        Instruction::SkipEqualRegisters(regs) => {
+            skip(format!("{} == {}", reg(regs >> 4), reg(regs)))
+        }
+        Instruction::SkipNotEqualReg(regs) => skip(format!("{} != {}", reg(regs >> 4), reg(regs))),
+        Instruction::LoadByte(x, byte) => vec![format!("{} = 0x{byte:02X};", reg(x))],
+        Instruction::AddByte(x, byte) => {
+            vec![format!("{0} = {0}.wrapping_add(0x{byte:02X});", reg(x))]
+        }
+        Instruction::LoadRegister(regs) if regs >> 4 == regs & 0xF => Vec::new(),
+        Instruction::LoadRegister(regs) => {
+            vec![format!("{} = {};", reg(regs >> 4), reg(regs))]
+        }
+        Instruction::Or(regs) => logical(regs, "|"),
+        Instruction::And(regs) => logical(regs, "&"),
+        Instruction::Xor(regs) => logical(regs, "^"),
+        Instruction::Add(regs) => arithmetic(
+            regs >> 4,
+            reg(regs >> 4),
+            reg(regs),
+            "overflowing_add",
+            "flag",
+        ),
+        Instruction::Sub(regs) => arithmetic(
+            regs >> 4,
+            reg(regs >> 4),
+            reg(regs),
+            "overflowing_sub",
+            "!flag",
+        ),
+        Instruction::SubInverted(regs) => arithmetic(
+            regs >> 4,
+            reg(regs),
+            reg(regs >> 4),
+            "overflowing_sub",
+            "!flag",
+        ),
+        Instruction::ShiftRight(regs) => shift(regs, ">> 1", "& 1"),
+        Instruction::ShiftLeft(regs) => shift(regs, "<< 1", ">> 7"),
+        Instruction::LoadI(address) => {
+            vec![format!(
+                "*machine.registers_mut().address_mut() = 0x{address:03X};"
+            )]
+        }
+        _ => return None,
+    })
+}
+
+/// The recovered control flow of an application
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    /// The bytes of the application
+    rom: Vec<u8>,
+
+    /// The memory map the application runs in
+    layout: MemoryLayout,
+
+    /// The routines, the one at the program start first and the others sorted by address
+    routines: Vec<Routine>,
+}
+
+impl Program {
+    /// Recovers the control flow of the application, starting at the program start of the memory
+    /// map and following jumps, calls and skips
+    pub fn analyze(rom: &Rom, layout: &MemoryLayout) -> Self {
+        let mut program = Self {
+            rom: rom.data().to_vec(),
+            layout: layout.clone(),
+            routines: Vec::new(),
+        };
+        let start = layout.program_start;
+
+        // Find every reachable instruction, and the addresses blocks have to start at
+        let mut reachable = BTreeMap::new();
+        let mut leaders = BTreeSet::from([start]);
+        let mut entries = BTreeSet::from([start]);
+        let mut pending = vec![start];
+        while let Some(address) = pending.pop() {
+            if reachable.contains_key(&address) {
+                continue;
+            }
+            let Some(instruction) = program.decode(address) else {
+                continue;
+            };
+            reachable.insert(address, instruction);
+            let next = address.wrapping_add(2);
+            match instruction {
+                Instruction::JumpAddress(target) => {
+                    leaders.insert(target);
+                    pending.push(target);
+                }
+                Instruction::CallAddress(target) => {
+                    entries.insert(target);
+                    leaders.extend([target, next]);
+                    pending.extend([target, next]);
+                }
+                Instruction::SkipEqualRegByte(..)
+                | Instruction::SkipNotEqualRegByte(..)
+                | Instruction::SkipEqualRegisters(_)
+                | Instruction::SkipNotEqualReg(_)
+                | Instruction::SkipPressed(_)
+                | Instruction::SkipNotPressed(_) => {
+                    let skipped = next.wrapping_add(2);
+                    leaders.extend([next, skipped]);
+                    pending.extend([next, skipped]);
+                }
+                Instruction::Return | Instruction::JumpAddressOffset(_) | Instruction::Exit => {}
+                instruction => {
+                    if ends_block(instruction) {
+                        leaders.insert(next);
+                    }
+                    pending.push(next);
+                }
+            }
+        }
+
+        // Split the reachable instructions into blocks
+        let mut blocks = BTreeMap::new();
+        let mut starts = leaders
+            .iter()
+            .copied()
+            .filter(|leader| reachable.contains_key(leader))
+            .collect::<Vec<_>>();
+        while let Some(start) = starts.pop() {
+            if blocks.contains_key(&start) {
+                continue;
+            }
+            let (mut instructions, mut address) = (Vec::new(), start);
+            while let Some(&instruction) = reachable.get(&address) {
+                instructions.push(instruction);
+                address = address.wrapping_add(2);
+                if ends_block(instruction) || leaders.contains(&address) {
+                    break;
+                }
+                if instructions.len() == MAX_BLOCK_LENGTH {
+                    starts.push(address);
+                    break;
+                }
+            }
+            blocks.insert(
+                start,
+                Block {
+                    start,
+                    instructions,
+                },
+            );
+        }
+
+        // Group the blocks into routines, the program start first
+        let entries =
+            std::iter::once(start).chain(entries.into_iter().filter(|&entry| entry != start));
+        for entry in entries {
+            let mut routine = Routine {
+                entry,
+                blocks: Vec::new(),
+            };
+            let mut pending = vec![entry];
+            while let Some(address) = pending.pop() {
+                if let Some(block) = blocks.remove(&address) {
+                    pending.extend(block.successors());
+                    routine.blocks.push(block);
+                }
+            }
+            if !routine.blocks.is_empty() {
+                routine.blocks.sort_by_key(|block| block.start);
+                program.routines.push(routine);
+            }
+        }
+        program
+    }
+
+    /// Decodes the instruction at the address if it is part of the application
+    fn decode(&self, address: u16) -> Option<Instruction> {
+        let offset = usize::from(address.checked_sub(self.layout.program_start)?);
+        let bytes = self.rom.get(offset..offset + 2)?;
+        Instruction::try_from(u16::from_be_bytes([bytes[0], bytes[1]])).ok()
+    }
+
+    /// Retrieves the routines, the one at the program start first
+    pub fn routines(&self) -> &[Routine] {
+        &self.routines
+    }
+
+    /// Retrieves the number of blocks of all routines
+    pub fn block_count(&self) -> usize {
+        self.routines
+            .iter()
+            .map(|routine| routine.blocks.len())
+            .sum()
+    }
+
+    /// Retrieves the number of instructions of all routines
+    pub fn instruction_count(&self) -> usize {
+        self.routines
+            .iter()
+            .flat_map(|routine| &routine.blocks)
+            .map(|block| block.instructions.len())
+            .sum()
+    }
+
+    /// Writes the application as a Rust module, executing the instructions that depend on the
+    /// quirks as they behave with the given quirks. The name of the application is mentioned in
+    /// the documentation of the module.
+    ///
+    /// The module contains the application, its memory map and quirks, a `machine` function
+    /// creating a machine running it and a `run_frame` function to use in place of
+    /// [`Machine::run_frame`](crate::machine::Machine::run_frame).
+    pub fn write_rust(&self, name: &str, quirks: Quirks, mut writer: impl Write) -> io::Result<()> {
+        let delegates = self
+            .routines
+            .iter()
+            .flat_map(|routine| &routine.blocks)
+            .flat_map(|block| &block.instructions)
+            .any(|&instruction| translate(0, instruction, quirks).is_none());
+        writeln!(
+            writer,
+            "//! Recompiled from {name} by chip8-recompile: {} routines, {} blocks and {} \
+             instructions.",
+            self.routines.len(),
+            self.block_count(),
+            self.instruction_count()
+        )?;
+        writeln!(writer, "//!")?;
+        writeln!(
+            writer,
+            "//! Create a machine running the application with [`machine`], then execute frames \
+             with"
+        )?;
+        writeln!(writer, "//! [`run_frame`] instead of `Machine::run_frame`.")?;
+        writeln!(writer)?;
+        writeln!(writer, "use chip_8::{{")?;
+        if delegates {
+            writeln!(writer, "    instruction::Instruction,")?;
+        }
+        writeln!(writer, "    machine::{{Machine, MachineError, Step}},")?;
+        writeln!(writer, "    memory::MemoryLayout,")?;
+        writeln!(writer, "    quirks::Quirks,")?;
+        writeln!(writer, "    rom::Rom,")?;
+        writeln!(writer, "}};")?;
+        writeln!(writer)?;
+
+        writeln!(writer, "/// The application")?;
+        writeln!(writer, "pub const ROM: &[u8] = &[")?;
+        for row in self.rom.chunks(16) {
+            let bytes = row
+                .iter()
+                .map(|byte| format!("0x{byte:02X},"))
+                .collect::<Vec<_>>();
+            writeln!(writer, "    {}", bytes.join(" "))?;
+        }
+        writeln!(writer, "];")?;
+        writeln!(writer)?;
+        let layout = &self.layout;
+        writeln!(
+            writer,
+            "/// The memory map the application was recompiled for"
+        )?;
+        writeln!(writer, "pub const LAYOUT: MemoryLayout = MemoryLayout {{")?;
+        writeln!(writer, "    program_start: 0x{:03X},", layout.program_start)?;
+        writeln!(writer, "    small_font: 0x{:03X},", layout.small_font)?;
+        writeln!(writer, "    large_font: 0x{:03X},", layout.large_font)?;
+        writeln!(writer, "    size: 0x{:X},", layout.size)?;
+        writeln!(
+            writer,
+            "    writable: 0x{:03X}..0x{:X},",
+            layout.writable.start, layout.writable.end
+        )?;
+        writeln!(writer, "}};")?;
+        writeln!(writer)?;
+        writeln!(writer, "/// The quirks the application was recompiled for")?;
+        writeln!(writer, "pub const QUIRKS: Quirks = Quirks {{")?;
+        writeln!(writer, "    vf_reset: {},", quirks.vf_reset)?;
+        writeln!(
+            writer,
+            "    load_store_increments_i: {},",
+            quirks.load_store_increments_i
+        )?;
+        writeln!(writer, "    shift_uses_vy: {},", quirks.shift_uses_vy)?;
+        writeln!(writer, "    jump_uses_vx: {},", quirks.jump_uses_vx)?;
+        writeln!(writer, "    clip_sprites: {},", quirks.clip_sprites)?;
+        writeln!(writer, "    increment_i_by_x: {},", quirks.increment_i_by_x)?;
+        writeln!(writer, "    display_wait: {},", quirks.display_wait)?;
+        writeln!(writer, "}};")?;
+        writeln!(writer)?;
+
+        writeln!(
+            writer,
+            "/// Creates a machine with the memory map and quirks, running the application"
+        )?;
+        writeln!(writer, "pub fn machine() -> Machine {{")?;
+        writeln!(
+            writer,
+            "    let mut machine = Machine::with_layout(QUIRKS, LAYOUT).expect(\"The memory map is valid\");"
+        )?;
+        writeln!(writer, "    machine")?;
+        writeln!(
+            writer,
+            "        .load_rom(&Rom::from_bytes(ROM).expect(\"The application isn't empty\"))"
+        )?;
+        writeln!(
+            writer,
+            "        .expect(\"The application fits in the memory map\");"
+        )?;
+        writeln!(writer, "    machine.set_quirks(QUIRKS);")?;
+        writeln!(writer, "    machine")?;
+        writeln!(writer, "}}")?;
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "/// Executes up to the given number of instructions, then updates the timers, exactly \
+             like"
+        )?;
+        writeln!(
+            writer,
+            "/// `Machine::run_frame`. Falls back to the interpreter if the quirks were changed."
+        )?;
+        writeln!(
+            writer,
+            "pub fn run_frame(machine: &mut Machine, cycles: u32) -> Result<Step, MachineError> {{"
+        )?;
+        writeln!(writer, "    if machine.quirks() != QUIRKS {{")?;
+        writeln!(writer, "        return machine.run_frame(cycles);")?;
+        writeln!(writer, "    }}")?;
+        writeln!(writer, "    let step = run(machine, cycles)?;")?;
+        writeln!(writer, "    machine.registers_mut().cycle();")?;
+        writeln!(writer, "    Ok(step)")?;
+        writeln!(writer, "}}")?;
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "/// Executes up to the given number of instructions, natively where possible"
+        )?;
+        writeln!(
+            writer,
+            "fn run(machine: &mut Machine, cycles: u32) -> Result<Step, MachineError> {{"
+        )?;
+        writeln!(writer, "    let mut budget = cycles;")?;
+        writeln!(writer, "    while budget > 0 {{")?;
+        writeln!(writer, "        let remaining = budget;")?;
+        writeln!(
+            writer,
+            "        let mut step = match machine.program_counter() {{"
+        )?;
+        for routine in &self.routines {
+            for block in &routine.blocks {
+                writeln!(
+                    writer,
+                    "            0x{:03X} => routine_{:03x}(machine, &mut budget)?,",
+                    block.start, routine.entry
+                )?;
+            }
+        }
+        writeln!(writer, "            _ => Step::Continue,")?;
+        writeln!(writer, "        }};")?;
+        writeln!(writer, "        if budget == remaining {{")?;
+        writeln!(writer, "            budget -= 1;")?;
+        writeln!(writer, "            step = machine.step()?;")?;
+        writeln!(writer, "        }}")?;
+        writeln!(writer, "        if step != Step::Continue {{")?;
+        writeln!(writer, "            return Ok(step);")?;
+        writeln!(writer, "        }}")?;
+        writeln!(writer, "    }}")?;
+        writeln!(writer, "    Ok(Step::Continue)")?;
+        writeln!(writer, "}}")?;
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "/// Retrieves whether the block with the number of instructions at the address is \
+             unchanged"
+        )?;
+        writeln!(writer, "/// and fits in the remaining cycles")?;
+        writeln!(
+            writer,
+            "fn ready(machine: &Machine, budget: u32, address: usize, count: u32) -> bool {{"
+        )?;
+        writeln!(
+            writer,
+            "    let offset = address - usize::from(LAYOUT.program_start);"
+        )?;
+        writeln!(writer, "    let length = 2 * count as usize;")?;
+        writeln!(writer, "    budget >= count")?;
+        writeln!(
+            writer,
+            "        && machine.memory().data().get(address..address + length)"
+        )?;
+        writeln!(writer, "            == ROM.get(offset..offset + length)")?;
+        writeln!(writer, "}}")?;
+
+        for routine in &self.routines {
+            writeln!(writer)?;
+            write_routine(routine, quirks, &mut writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the function executing the blocks of the routine
+fn write_routine(routine: &Routine, quirks: Quirks, mut writer: impl Write) -> io::Result<()> {
+    writeln!(writer, "/// The routine at 0x{:03X}", routine.entry)?;
+    writeln!(
+        writer,
+        "fn routine_{:03x}(machine: &mut Machine, budget: &mut u32) -> Result<Step, \
+         MachineError> {{",
+        routine.entry
+    )?;
+    writeln!(writer, "    loop {{")?;
+    writeln!(writer, "        match machine.program_counter() {{")?;
+    for block in &routine.blocks {
+        let count = block.instructions.len();
+        writeln!(
+            writer,
+            "            0x{0:03X} if ready(machine, *budget, 0x{0:03X}, {count}) => {{",
+            block.start
+        )?;
+        writeln!(writer, "                *budget -= {count};")?;
+        let mut registers = false;
+        let mut address = block.start;
+        for &instruction in &block.instructions {
+            for line in statements(address, instruction, quirks, &mut registers) {
+                writeln!(writer, "                {line}")?;
+            }
+            address = address.wrapping_add(2);
+        }
+        let last = *block.instructions.last().expect("Blocks aren't empty");
+        if !transfers_control(last) && translate(0, last, quirks).is_some() {
+            writeln!(
+                writer,
+                "                machine.set_program_counter(0x{:03X});",
+                block.end()
+            )?;
+        }
+        writeln!(writer, "            }}")?;
+    }
+    writeln!(writer, "            _ => return Ok(Step::Continue),")?;
+    writeln!(writer, "        }}")?;
+    writeln!(writer, "    }}")?;
+    writeln!(writer, "}}")
+}
+
+/// Retrieves the statements executing the instruction at the address. Registers tracks
+/// whether the registers are borrowed as `v`.
+fn statements(
+    address: u16,
+    instruction: Instruction,
+    quirks: Quirks,
+    registers: &mut bool,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    match translate(address, instruction, quirks) {
+        Some(statements) => {
+            let uses_registers = statements.iter().any(|line| line.contains("v["));
+            if uses_registers && !*registers {
+                lines.push("let v = machine.registers_mut().data_mut();".to_owned());
+            }
+            *registers = (*registers || uses_registers)
+                && !statements.iter().any(|line| line.starts_with("*machine"));
+            lines.extend(statements);
+        }
+        None => {
+            *registers = false;
+            let call = format!(
+                "machine.execute_at(0x{address:03X}, {})?",
+                instruction_code(instruction)
+            );
+            if matches!(
+                instruction,
+                Instruction::LoadKeyPress(_) | Instruction::Exit
+            ) || quirks.display_wait && matches!(instruction, Instruction::Draw(..))
+            {
+                lines.push(format!("let step = {call};"));
+                lines.push("if step != Step::Continue {".to_owned());
+                lines.push("    return Ok(step);".to_owned());
+                lines.push("}".to_owned());
+            } else {
+                lines.push(format!("{call};"));
+            }
+        }
+    }
+    lines
+}
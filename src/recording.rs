@@ -0,0 +1,252 @@
+//! This module contains the recording of gameplay to animated GIF and raw Y4M video.
+//!
+//! Frames are captured once per emulated frame, so recordings always play at 60 frames per
+//! second, regardless of how fast the host ran the emulation.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    display::{Display, HEIGHT, WIDTH},
+    screenshot::{Palette, scaled_rows},
+};
+
+/// The number of emulated frames per second
+const FRAMES_PER_SECOND: u64 = 60;
+
+/// The shortest delay of GIF frames, in hundredths of a second. Most viewers play shorter delays
+/// as 10, so shorter frames are lengthened to this and the following frames are shortened.
+const MIN_GIF_DELAY: u64 = 2;
+
+/// The supported video formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoFormat {
+    /// Animated GIF, identical consecutive frames are merged into a longer frame and frames
+    /// shorter than two hundredths of a second borrow time from the frames following them
+    Gif,
+
+    /// Raw YUV4MPEG2 stream with 4:4:4 chroma, every frame is stored
+    Y4m,
+}
+
+impl VideoFormat {
+    /// Determines the format from the extension of the path
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "gif" => Some(Self::Gif),
+            "y4m" => Some(Self::Y4m),
+            _ => None,
+        }
+    }
+}
+
+/// The encoder of a recording
+enum Encoder {
+    /// Writes an animated GIF
+    Gif {
+        /// The GIF encoder
+        encoder: gif::Encoder<Box<dyn Write>>,
+
+        /// The width and height of the frames
+        size: (u16, u16),
+
+        /// The palette indices of the frame that hasn't been written yet
+        pending: Option<Vec<u8>>,
+
+        /// The number of emulated frames the pending frame lasts
+        pending_frames: u64,
+
+        /// The number of emulated frames written so far
+        written_frames: u64,
+
+        /// The hundredths of a second written so far, ahead of the emulated frames if short
+        /// frames were lengthened
+        written_delay: u64,
+    },
+
+    /// Writes a Y4M stream
+    Y4m {
+        /// The destination of the stream
+        writer: Box<dyn Write>,
+
+        /// The luma and chroma of unlit and lit pixels
+        colors: [[u8; 3]; 2],
+    },
+}
+
+/// Records the display every emulated frame
+pub struct Recorder {
+    /// The encoder of the video format
+    encoder: Encoder,
+
+    /// The factor every pixel is scaled by
+    scale: usize,
+}
+
+/// Converts an RGB color to BT.601 limited range luma and chroma
+fn to_ycbcr([red, green, blue]: [u8; 3]) -> [u8; 3] {
+    let (red, green, blue) = (f32::from(red), f32::from(green), f32::from(blue));
+    [
+        16.0 + 0.257 * red + 0.504 * green + 0.098 * blue,
+        128.0 - 0.148 * red - 0.291 * green + 0.439 * blue,
+        128.0 + 0.439 * red - 0.368 * green - 0.071 * blue,
+    ]
+    .map(|value| value.round() as u8)
+}
+
+impl Recorder {
+    /// Starts a recording to the writer
+    pub fn new(
+        writer: impl Write + 'static,
+        format: VideoFormat,
+        scale: usize,
+        palette: Palette,
+    ) -> io::Result<Self> {
+        let mut writer: Box<dyn Write> = Box::new(writer);
+        let (width, height) = (WIDTH * scale, HEIGHT * scale);
+        let encoder = match format {
+            VideoFormat::Gif => {
+                let size = (
+                    u16::try_from(width).map_err(io::Error::other)?,
+                    u16::try_from(height).map_err(io::Error::other)?,
+                );
+                let mut encoder = gif::Encoder::new(
+                    writer,
+                    size.0,
+                    size.1,
+                    &[palette.background, palette.foreground].concat(),
+                )
+                .map_err(io::Error::other)?;
+                encoder
+                    .set_repeat(gif::Repeat::Infinite)
+                    .map_err(io::Error::other)?;
+                Encoder::Gif {
+                    encoder,
+                    size,
+                    pending: None,
+                    pending_frames: 0,
+                    written_frames: 0,
+                    written_delay: 0,
+                }
+            }
+            VideoFormat::Y4m => {
+                writeln!(
+                    writer,
+                    "YUV4MPEG2 W{width} H{height} F{FRAMES_PER_SECOND}:1 Ip A1:1 C444"
+                )?;
+                Encoder::Y4m {
+                    writer,
+                    colors: [to_ycbcr(palette.background), to_ycbcr(palette.foreground)],
+                }
+            }
+        };
+        Ok(Self { encoder, scale })
+    }
+
+    /// Starts a recording to a file, the format is determined by the extension of the path
+    pub fn create(path: &Path, scale: usize, palette: Palette) -> io::Result<Self> {
+        let format = VideoFormat::from_path(path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Unsupported video format: {}", path.display()),
+            )
+        })?;
+        Self::new(BufWriter::new(File::create(path)?), format, scale, palette)
+    }
+
+    /// Records the display as the next emulated frame
+    pub fn record_frame(&mut self, display: &Display) -> io::Result<()> {
+        let pixels = scaled_rows(display, self.scale)
+            .flatten()
+            .map(u8::from)
+            .collect::<Vec<_>>();
+        match &mut self.encoder {
+            Encoder::Gif {
+                encoder,
+                size,
+                pending,
+                pending_frames,
+                written_frames,
+                written_delay,
+            } => {
+                if pending.as_ref() == Some(&pixels) {
+                    *pending_frames += 1;
+                    return Ok(());
+                }
+                if let Some(pending) = pending.replace(pixels) {
+                    write_gif_frame(
+                        encoder,
+                        *size,
+                        pending,
+                        *pending_frames,
+                        (written_frames, written_delay),
+                    )?;
+                }
+                *pending_frames = 1;
+            }
+            Encoder::Y4m { writer, colors } => {
+                writer.write_all(b"FRAME\n")?;
+                let [unlit, lit] = *colors;
+                for (unlit, lit) in unlit.into_iter().zip(lit) {
+                    let bytes = pixels
+                        .iter()
+                        .map(|&pixel| if pixel == 1 { lit } else { unlit })
+                        .collect::<Vec<_>>();
+                    writer.write_all(&bytes)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the remaining frames and flushes the recording
+    pub fn finish(self) -> io::Result<()> {
+        match self.encoder {
+            Encoder::Gif {
+                mut encoder,
+                size,
+                pending,
+                pending_frames,
+                mut written_frames,
+                mut written_delay,
+            } => {
+                if let Some(pending) = pending {
+                    write_gif_frame(
+                        &mut encoder,
+                        size,
+                        pending,
+                        pending_frames,
+                        (&mut written_frames, &mut written_delay),
+                    )?;
+                }
+                encoder.into_inner().map_err(io::Error::other)?.flush()
+            }
+            Encoder::Y4m { mut writer, .. } => writer.flush(),
+        }
+    }
+}
+
+/// Writes a frame lasting the number of emulated frames to a GIF, after the given number of
+/// emulated frames and hundredths of a second were written. GIF delays are in hundredths of a
+/// second, so the delay is derived from the total time written, to prevent rounding errors from
+/// accumulating. Frames shorter than the shortest delay are lengthened to it, and the time they
+/// took too long is taken from the following frames.
+fn write_gif_frame(
+    encoder: &mut gif::Encoder<Box<dyn Write>>,
+    (width, height): (u16, u16),
+    pixels: Vec<u8>,
+    frames: u64,
+    (written_frames, written_delay): (&mut u64, &mut u64),
+) -> io::Result<()> {
+    *written_frames += frames;
+    let end = *written_frames * 100 / FRAMES_PER_SECOND;
+    let delay = end.saturating_sub(*written_delay).max(MIN_GIF_DELAY);
+    *written_delay += delay;
+    let mut frame = gif::Frame::from_indexed_pixels(width, height, pixels, None);
+    frame.delay = u16::try_from(delay).unwrap_or(u16::MAX);
+    encoder.write_frame(&frame).map_err(io::Error::other)
+}
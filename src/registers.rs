@@ -1,8 +1,9 @@
 //! This module contains the implementation for most registers.
 
-use std::ops::Range;
+use core::ops::Range;
 
 /// The set of registers for the chip-8 architecture
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Registers {
     /// The basic data registers (specified in the instructions)
     data: [u8; 16],
@@ -52,6 +53,16 @@ impl Registers {
         }
     }
 
+    /// Retrieves the values of all general purpose registers
+    pub const fn data(&self) -> &[u8; 16] {
+        &self.data
+    }
+
+    /// Gets a mutable reference to all general purpose registers
+    pub const fn data_mut(&mut self) -> &mut [u8; 16] {
+        &mut self.data
+    }
+
     /// Retrieves the value of the address register
     pub const fn address(&self) -> u16 {
         self.address
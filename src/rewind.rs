@@ -0,0 +1,179 @@
+//! This module contains the rewind buffer, which stores a snapshot of the machine every frame.
+//!
+//! Only the newest snapshot is stored in full. Every older snapshot is stored as the run-length
+//! encoded xor of itself and the snapshot after it, as most of the state doesn't change between
+//! frames. Snapshots grow and shrink with the call stack, so the shorter of two snapshots is
+//! padded with zeros and every delta stores the length of the older snapshot.
+
+use std::collections::VecDeque;
+
+use crate::{machine::Machine, state::StateError};
+
+/// The default memory budget of the rewind buffer, several minutes of most applications
+pub const DEFAULT_BUDGET: usize = 16 * 1024 * 1024;
+
+/// A bounded history of machine snapshots
+#[derive(Debug, Clone)]
+pub struct Rewind {
+    /// The maximum number of bytes used by the snapshots
+    budget: usize,
+
+    /// The number of bytes currently used by the snapshots
+    used: usize,
+
+    /// The newest snapshot, stored in full
+    newest: Option<Vec<u8>>,
+
+    /// The compressed differences between consecutive snapshots, oldest first
+    deltas: VecDeque<Vec<u8>>,
+}
+
+impl Default for Rewind {
+    fn default() -> Self {
+        Self::new(DEFAULT_BUDGET)
+    }
+}
+
+impl Rewind {
+    /// Creates an empty rewind buffer using at most the given number of bytes
+    pub const fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            used: 0,
+            newest: None,
+            deltas: VecDeque::new(),
+        }
+    }
+
+    /// Retrieves the maximum number of bytes used by the snapshots
+    pub const fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// Retrieves the number of bytes currently used by the snapshots
+    pub const fn memory_usage(&self) -> usize {
+        self.used
+    }
+
+    /// Retrieves the number of frames that can be rewound
+    pub fn len(&self) -> usize {
+        self.deltas.len()
+    }
+
+    /// Retrieves whether no frames can be rewound
+    pub fn is_empty(&self) -> bool {
+        self.deltas.is_empty()
+    }
+
+    /// Removes all snapshots
+    pub fn clear(&mut self) {
+        self.newest = None;
+        self.deltas.clear();
+        self.used = 0;
+    }
+
+    /// Stores a snapshot of the machine, dropping the oldest snapshots if over budget
+    pub fn record(&mut self, machine: &Machine) {
+        let state = machine.save_state();
+        if let Some(previous) = self.newest.take() {
+            let delta = compress(&previous, &state);
+            self.used = self.used + delta.len() - previous.len();
+            self.deltas.push_back(delta);
+        }
+        self.used += state.len();
+        self.newest = Some(state);
+
+        // Drop the oldest snapshots until the buffer fits in its budget again
+        while self.used > self.budget {
+            let Some(oldest) = self.deltas.pop_front() else {
+                break;
+            };
+            self.used -= oldest.len();
+        }
+    }
+
+    /// Restores the machine to the snapshot before the newest one.
+    /// Returns false if there is no older snapshot. If the snapshot can't be loaded, for example
+    /// because the machine loaded another application since it was recorded, the buffer is
+    /// cleared and the machine is left unchanged.
+    pub fn rewind(&mut self, machine: &mut Machine) -> Result<bool, StateError> {
+        let (Some(delta), Some(newest)) = (self.deltas.pop_back(), self.newest.as_mut()) else {
+            return Ok(false);
+        };
+        self.used -= delta.len();
+        decompress(&delta, newest);
+        if let Err(error) = machine.load_state(newest) {
+            self.clear();
+            return Err(error);
+        }
+        Ok(true)
+    }
+}
+
+/// Run-length encodes the xor of two snapshots, padding the shorter one with zeros.
+/// The result starts with the length of the previous snapshot, followed by pairs of a run of
+/// equal bytes and a run of differing bytes. Every length is stored as a variable length integer
+/// and only the xored differing bytes are stored.
+fn compress(previous: &[u8], current: &[u8]) -> Vec<u8> {
+    let mut delta = Vec::new();
+    write_length(&mut delta, previous.len());
+    let length = previous.len().max(current.len());
+    let xor = |index: usize| {
+        previous.get(index).copied().unwrap_or(0) ^ current.get(index).copied().unwrap_or(0)
+    };
+    let mut index = 0;
+    while index < length {
+        let equal = (index..length).take_while(|&index| xor(index) == 0).count();
+        index += equal;
+        let differing = (index..length).take_while(|&index| xor(index) != 0).count();
+        write_length(&mut delta, equal);
+        write_length(&mut delta, differing);
+        delta.extend((index..index + differing).map(xor));
+        index += differing;
+    }
+    delta
+}
+
+/// Applies a delta created by [`compress`] to a snapshot, turning it into the other snapshot
+fn decompress(delta: &[u8], snapshot: &mut Vec<u8>) {
+    let mut delta = delta;
+    let length = read_length(&mut delta);
+    snapshot.resize(snapshot.len().max(length), 0);
+    let mut index = 0;
+    while !delta.is_empty() {
+        index += read_length(&mut delta);
+        let differing = read_length(&mut delta);
+        let (xored, rest) = delta.split_at(differing);
+        for (byte, xor) in snapshot[index..index + differing].iter_mut().zip(xored) {
+            *byte ^= xor;
+        }
+        delta = rest;
+        index += differing;
+    }
+    snapshot.truncate(length);
+}
+
+/// Writes a length as a variable length integer, 7 bits per byte with the most significant bit
+/// indicating another byte follows
+fn write_length(delta: &mut Vec<u8>, mut length: usize) {
+    while length >= 0x80 {
+        delta.push(length as u8 | 0x80);
+        length >>= 7;
+    }
+    delta.push(length as u8);
+}
+
+/// Reads a length written by [`write_length`]
+fn read_length(delta: &mut &[u8]) -> usize {
+    let mut length = 0;
+    let mut shift = 0;
+    while let Some((&byte, rest)) = delta.split_first() {
+        *delta = rest;
+        length |= usize::from(byte & 0x7F) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    length
+}
@@ -0,0 +1,181 @@
+//! This module contains the random number generators used by the random instruction
+
+use core::fmt::Debug;
+
+/// A source of random bytes for the random instruction.
+/// The state must be serializable, so save states and replays stay deterministic.
+pub trait Rng: Debug {
+    /// Generates the next random byte
+    fn next_byte(&mut self) -> u8;
+
+    /// Serializes the internal state
+    #[cfg(feature = "std")]
+    fn save(&self) -> Vec<u8>;
+
+    /// Restores a state serialized by [`Rng::save`], returns false if the state is invalid
+    fn restore(&mut self, state: &[u8]) -> bool;
+
+    /// Restarts the sequence from a seed, keeping the configuration of the generator
+    fn reseed(&mut self, seed: u64);
+
+    /// Serializes the kind and configuration of the generator, which together with a seed
+    /// determine the generated sequence
+    #[cfg(feature = "std")]
+    fn configuration(&self) -> Vec<u8>;
+
+    /// Creates a boxed copy of the generator
+    #[cfg(feature = "std")]
+    fn boxed_clone(&self) -> Box<dyn Rng>;
+}
+
+#[cfg(feature = "std")]
+impl Clone for Box<dyn Rng> {
+    fn clone(&self) -> Self {
+        self.boxed_clone()
+    }
+}
+
+/// A fast xorshift64* generator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Xorshift {
+    /// The current state, never 0
+    state: u64,
+}
+
+/// Seeds the generator randomly with the `rand` feature, with 0 otherwise
+impl Default for Xorshift {
+    fn default() -> Self {
+        #[cfg(feature = "rng")]
+        let seed = rand::random();
+        #[cfg(not(feature = "rng"))]
+        let seed = 0;
+        Self::new(seed)
+    }
+}
+
+impl Xorshift {
+    /// Creates a generator from a seed, equal seeds generate equal sequences
+    pub const fn new(seed: u64) -> Self {
+        // Xorshift can't continue from 0, so mix the seed into a nonzero state
+        Self {
+            state: (seed ^ 0x9E37_79B9_7F4A_7C15) | 1,
+        }
+    }
+}
+
+impl Rng for Xorshift {
+    fn next_byte(&mut self) -> u8 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        (self.state.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 56) as u8
+    }
+
+    #[cfg(feature = "std")]
+    fn save(&self) -> Vec<u8> {
+        self.state.to_le_bytes().to_vec()
+    }
+
+    fn restore(&mut self, state: &[u8]) -> bool {
+        match state.try_into().map(u64::from_le_bytes) {
+            Ok(state) if state != 0 => {
+                self.state = state;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn reseed(&mut self, seed: u64) {
+        *self = Self::new(seed);
+    }
+
+    #[cfg(feature = "std")]
+    fn configuration(&self) -> Vec<u8> {
+        b"xorshift".to_vec()
+    }
+
+    #[cfg(feature = "std")]
+    fn boxed_clone(&self) -> Box<dyn Rng> {
+        Box::new(*self)
+    }
+}
+
+/// The size of the original COSMAC VIP interpreter, which is stored at 0x000 - 0x1FF
+pub const VIP_INTERPRETER_SIZE: usize = 0x200;
+
+/// An emulation of the random routine of the original COSMAC VIP interpreter.
+///
+/// The VIP increments the low byte of its random register, uses it to index a byte of the
+/// interpreter code at 0x100 - 0x1FF and adds that byte to the high byte, which is the result.
+/// The interpreter isn't part of chip-8 memory here, so its page has to be supplied, for example
+/// from a dump of the interpreter using [`CosmacVip::from_interpreter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CosmacVip {
+    /// The low byte of the random register, indexing the interpreter page
+    index: u8,
+
+    /// The high byte of the random register, the last generated byte
+    value: u8,
+
+    /// The page of the interpreter code the bytes are taken from
+    page: [u8; 256],
+}
+
+impl CosmacVip {
+    /// Creates a generator from a seed and the interpreter page at 0x100 - 0x1FF
+    pub const fn new(seed: u16, page: [u8; 256]) -> Self {
+        Self {
+            index: seed as u8,
+            value: (seed >> 8) as u8,
+            page,
+        }
+    }
+
+    /// Creates a generator from a seed and a dump of the interpreter: either the whole
+    /// interpreter or only the page at 0x100 - 0x1FF.
+    /// Returns None if the dump has another size.
+    pub fn from_interpreter(seed: u16, interpreter: &[u8]) -> Option<Self> {
+        let page = match interpreter.len() {
+            VIP_INTERPRETER_SIZE => &interpreter[0x100..],
+            _ => interpreter,
+        };
+        page.try_into().ok().map(|page| Self::new(seed, page))
+    }
+}
+
+impl Rng for CosmacVip {
+    fn next_byte(&mut self) -> u8 {
+        self.index = self.index.wrapping_add(1);
+        self.value = self.value.wrapping_add(self.page[usize::from(self.index)]);
+        self.value
+    }
+
+    #[cfg(feature = "std")]
+    fn save(&self) -> Vec<u8> {
+        vec![self.index, self.value]
+    }
+
+    fn restore(&mut self, state: &[u8]) -> bool {
+        let &[index, value] = state else {
+            return false;
+        };
+        (self.index, self.value) = (index, value);
+        true
+    }
+
+    /// Only the lowest 16 bits of the seed are used, like [`CosmacVip::new`]
+    fn reseed(&mut self, seed: u64) {
+        *self = Self::new(seed as u16, self.page);
+    }
+
+    #[cfg(feature = "std")]
+    fn configuration(&self) -> Vec<u8> {
+        [b"cosmac-vip".as_slice(), &self.page].concat()
+    }
+
+    #[cfg(feature = "std")]
+    fn boxed_clone(&self) -> Box<dyn Rng> {
+        Box::new(*self)
+    }
+}
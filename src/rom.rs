@@ -0,0 +1,134 @@
+//! This module contains the loading and identification of applications
+
+#[cfg(feature = "std")]
+use std::{fmt::Write as _, fs::File, io::Read, path::Path};
+
+#[cfg(feature = "std")]
+use sha1::{Digest, Sha1};
+
+/// The error returned if an application can't be read or loaded
+#[derive(Debug)]
+pub enum RomError {
+    /// The application couldn't be read
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+
+    /// The application doesn't contain any bytes
+    Empty,
+
+    /// The application doesn't fit in the writable memory after the program start
+    TooLarge {
+        /// The size of the application in bytes
+        size: usize,
+
+        /// The number of bytes available for the application
+        capacity: usize,
+    },
+}
+
+impl core::fmt::Display for RomError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            Self::Io(error) => write!(f, "Failed to read application: {error}"),
+            Self::Empty => write!(f, "Application is empty"),
+            Self::TooLarge { size, capacity } => write!(
+                f,
+                "Application is {size} bytes, but only {capacity} bytes are available"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for RomError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for RomError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// An application and the checksums identifying it
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rom {
+    /// The bytes of the application
+    data: Vec<u8>,
+
+    /// The SHA-1 hash of the application
+    sha1: [u8; 20],
+
+    /// The CRC-32 checksum of the application
+    crc32: u32,
+}
+
+#[cfg(feature = "std")]
+impl Rom {
+    /// Creates an application from its bytes.
+    /// Returns an error if the application is empty.
+    pub fn from_bytes(data: impl Into<Vec<u8>>) -> Result<Self, RomError> {
+        let data = data.into();
+        if data.is_empty() {
+            return Err(RomError::Empty);
+        }
+        Ok(Self {
+            sha1: Sha1::digest(&data).into(),
+            crc32: crc32fast::hash(&data),
+            data,
+        })
+    }
+
+    /// Reads an application until the end of the reader
+    pub fn from_reader(mut reader: impl Read) -> Result<Self, RomError> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Self::from_bytes(data)
+    }
+
+    /// Reads an application from a file
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, RomError> {
+        Self::from_reader(File::open(path)?)
+    }
+
+    /// Retrieves the bytes of the application
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Retrieves the size of the application in bytes
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Retrieves whether the application is empty, which is never the case
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Retrieves the SHA-1 hash of the application
+    pub const fn sha1(&self) -> [u8; 20] {
+        self.sha1
+    }
+
+    /// Retrieves the SHA-1 hash of the application as lowercase hexadecimal digits
+    pub fn sha1_hex(&self) -> String {
+        self.sha1.iter().fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+    }
+
+    /// Retrieves the CRC-32 checksum of the application
+    pub const fn crc32(&self) -> u32 {
+        self.crc32
+    }
+}
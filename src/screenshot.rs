@@ -0,0 +1,188 @@
+//! This module contains the export of the framebuffer to image files
+
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::display::{Display, HEIGHT, WIDTH};
+
+/// The colors the pixels are mapped to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    /// The color of pixels that are off, as red, green and blue
+    pub background: [u8; 3],
+
+    /// The color of pixels that are lit, as red, green and blue
+    pub foreground: [u8; 3],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::MONOCHROME
+    }
+}
+
+impl Palette {
+    /// White pixels on a black background
+    pub const MONOCHROME: Self = Self {
+        background: [0; 3],
+        foreground: [0xFF; 3],
+    };
+
+    /// Retrieves the color of a pixel
+    pub const fn color(&self, lit: bool) -> [u8; 3] {
+        if lit {
+            self.foreground
+        } else {
+            self.background
+        }
+    }
+
+    /// Retrieves the color of a pixel as 0RGB, as used by most framebuffers
+    pub const fn color_u32(&self, lit: bool) -> u32 {
+        let [red, green, blue] = self.color(lit);
+        u32::from_be_bytes([0, red, green, blue])
+    }
+}
+
+/// Parses a color in the `#rrggbb` format
+#[cfg(feature = "serde")]
+pub(crate) fn parse_color(color: &str) -> Option<[u8; 3]> {
+    let digits = color.strip_prefix('#')?;
+    let value = u32::from_str_radix(digits, 16).ok()?;
+    if digits.len() != 6 {
+        return None;
+    }
+    let [_, red, green, blue] = value.to_be_bytes();
+    Some([red, green, blue])
+}
+
+/// The supported image formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Portable network graphics, compressed and palette-mapped
+    Png,
+
+    /// Binary portable bitmap, lit pixels are black regardless of the palette
+    Pbm,
+
+    /// Binary portable pixmap, palette-mapped
+    Ppm,
+}
+
+impl ImageFormat {
+    /// Determines the format from the extension of the path
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "png" => Some(Self::Png),
+            "pbm" => Some(Self::Pbm),
+            "ppm" => Some(Self::Ppm),
+            _ => None,
+        }
+    }
+}
+
+/// Iterates over the rows of the display scaled by the factor, each row as the lit state of its
+/// scaled pixels
+pub(crate) fn scaled_rows(display: &Display, scale: usize) -> impl Iterator<Item = Vec<bool>> + '_ {
+    display.rows().iter().flat_map(move |&row| {
+        let row = (0..WIDTH)
+            .map(|x| row >> (WIDTH - 1 - x) & 1 == 1)
+            .flat_map(|lit| std::iter::repeat_n(lit, scale))
+            .collect::<Vec<_>>();
+        std::iter::repeat_n(row, scale)
+    })
+}
+
+/// Packs a row of pixels into bytes, 8 pixels per byte with the leftmost pixel in the most
+/// significant bit. The last byte is padded with unlit pixels.
+fn pack_row(row: &[bool]) -> impl Iterator<Item = u8> + '_ {
+    row.chunks(8).map(|pixels| {
+        pixels
+            .iter()
+            .enumerate()
+            .fold(0, |byte, (bit, &lit)| byte | u8::from(lit) << (7 - bit))
+    })
+}
+
+/// Writes the display as a binary portable bitmap, every pixel scaled to a square of pixels
+pub fn write_pbm(display: &Display, scale: usize, mut writer: impl Write) -> io::Result<()> {
+    write!(writer, "P4\n{} {}\n", WIDTH * scale, HEIGHT * scale)?;
+    for row in scaled_rows(display, scale) {
+        writer.write_all(&pack_row(&row).collect::<Vec<_>>())?;
+    }
+    Ok(())
+}
+
+/// Writes the display as a binary portable pixmap, every pixel scaled to a square of pixels
+pub fn write_ppm(
+    display: &Display,
+    scale: usize,
+    palette: Palette,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    write!(writer, "P6\n{} {}\n255\n", WIDTH * scale, HEIGHT * scale)?;
+    for row in scaled_rows(display, scale) {
+        let bytes = row
+            .into_iter()
+            .flat_map(|lit| palette.color(lit))
+            .collect::<Vec<_>>();
+        writer.write_all(&bytes)?;
+    }
+    Ok(())
+}
+
+/// Writes the display as a 1-bit indexed PNG, every pixel scaled to a square of pixels
+pub fn write_png(
+    display: &Display,
+    scale: usize,
+    palette: Palette,
+    writer: impl Write,
+) -> io::Result<()> {
+    let (width, height) = (WIDTH * scale, HEIGHT * scale);
+    let mut encoder = png::Encoder::new(
+        writer,
+        u32::try_from(width).map_err(io::Error::other)?,
+        u32::try_from(height).map_err(io::Error::other)?,
+    );
+    encoder.set_color(png::ColorType::Indexed);
+    encoder.set_depth(png::BitDepth::One);
+    encoder.set_palette([palette.background, palette.foreground].concat());
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    let mut data = Vec::with_capacity(width.div_ceil(8) * height);
+    for row in scaled_rows(display, scale) {
+        data.extend(pack_row(&row));
+    }
+    writer.write_image_data(&data).map_err(io::Error::other)?;
+    writer.finish().map_err(io::Error::other)
+}
+
+/// Writes the display in the given format, every pixel scaled to a square of pixels
+pub fn write_image(
+    display: &Display,
+    format: ImageFormat,
+    scale: usize,
+    palette: Palette,
+    writer: impl Write,
+) -> io::Result<()> {
+    match format {
+        ImageFormat::Png => write_png(display, scale, palette, writer),
+        ImageFormat::Pbm => write_pbm(display, scale, writer),
+        ImageFormat::Ppm => write_ppm(display, scale, palette, writer),
+    }
+}
+
+/// Saves the display to a file, the format is determined by the extension of the path
+pub fn save(display: &Display, path: &Path, scale: usize, palette: Palette) -> io::Result<()> {
+    let format = ImageFormat::from_path(path).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Unsupported image format: {}", path.display()),
+        )
+    })?;
+    let mut writer = io::BufWriter::new(std::fs::File::create(path)?);
+    write_image(display, format, scale, palette, &mut writer)?;
+    writer.flush()
+}
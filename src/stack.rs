@@ -0,0 +1,163 @@
+//! This module contains the call stack, which stores the return addresses of subroutines
+
+/// The number of return addresses the COSMAC VIP interpreter can store
+pub const VIP_DEPTH: usize = 12;
+
+/// The number of return addresses the super chip-48 interpreter can store
+pub const SCHIP_DEPTH: usize = 16;
+
+/// The address the COSMAC VIP interpreter stores the first return address at, the stack grows
+/// downwards from there
+pub const VIP_MIRROR_ADDRESS: u16 = 0xECE;
+
+/// The number of return addresses the stack can store without the `std` feature, even if it is
+/// unlimited
+#[cfg(not(feature = "std"))]
+pub const MAX_DEPTH: usize = 64;
+
+/// The error returned if the stack can't be pushed or popped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackError {
+    /// The stack is full
+    Overflow,
+
+    /// The stack is empty
+    Underflow,
+}
+
+impl core::fmt::Display for StackError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Overflow => write!(f, "Stack overflow"),
+            Self::Underflow => write!(f, "Stack underflow"),
+        }
+    }
+}
+
+impl core::error::Error for StackError {}
+
+/// The call stack, separate from memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stack {
+    /// The return addresses, the last one is the top of the stack
+    #[cfg(feature = "std")]
+    entries: Vec<u16>,
+
+    /// The return addresses, the one before the depth is the top of the stack. Popped entries
+    /// are cleared.
+    #[cfg(not(feature = "std"))]
+    entries: [u16; MAX_DEPTH],
+
+    /// The number of return addresses
+    #[cfg(not(feature = "std"))]
+    depth: usize,
+
+    /// The maximum number of return addresses, None for unlimited
+    limit: Option<usize>,
+
+    /// Whether the return addresses are also stored in memory, where the VIP stored them
+    vip_mirror: bool,
+}
+
+impl Default for Stack {
+    fn default() -> Self {
+        Self::new(Some(SCHIP_DEPTH))
+    }
+}
+
+impl Stack {
+    /// Creates an empty stack storing at most the given number of return addresses, None for
+    /// unlimited
+    pub const fn new(limit: Option<usize>) -> Self {
+        Self {
+            #[cfg(feature = "std")]
+            entries: Vec::new(),
+            #[cfg(not(feature = "std"))]
+            entries: [0; MAX_DEPTH],
+            #[cfg(not(feature = "std"))]
+            depth: 0,
+            limit,
+            vip_mirror: false,
+        }
+    }
+
+    /// Retrieves the maximum number of return addresses, None for unlimited
+    pub const fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// Sets the maximum number of return addresses, None for unlimited.
+    /// Returns false if the stack already contains more return addresses.
+    pub fn set_limit(&mut self, limit: Option<usize>) -> bool {
+        if limit.is_some_and(|limit| self.depth() > limit) {
+            return false;
+        }
+        self.limit = limit;
+        true
+    }
+
+    /// Retrieves whether the return addresses are mirrored into memory
+    pub const fn vip_mirror(&self) -> bool {
+        self.vip_mirror
+    }
+
+    /// Sets whether the return addresses are mirrored into memory, below
+    /// [`VIP_MIRROR_ADDRESS`], for applications that read them
+    pub const fn set_vip_mirror(&mut self, vip_mirror: bool) {
+        self.vip_mirror = vip_mirror;
+    }
+
+    /// Retrieves the return addresses, the last one is the top of the stack
+    pub fn entries(&self) -> &[u16] {
+        #[cfg(feature = "std")]
+        {
+            &self.entries
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            &self.entries[..self.depth]
+        }
+    }
+
+    /// Retrieves the number of return addresses on the stack
+    pub fn depth(&self) -> usize {
+        self.entries().len()
+    }
+
+    /// Pushes a return address on the stack
+    pub fn push(&mut self, address: u16) -> Result<(), StackError> {
+        if self.limit.is_some_and(|limit| self.depth() >= limit) {
+            return Err(StackError::Overflow);
+        }
+        #[cfg(feature = "std")]
+        self.entries.push(address);
+        #[cfg(not(feature = "std"))]
+        {
+            let entry = self
+                .entries
+                .get_mut(self.depth)
+                .ok_or(StackError::Overflow)?;
+            *entry = address;
+            self.depth += 1;
+        }
+        Ok(())
+    }
+
+    /// Pops the return address from the top of the stack
+    pub fn pop(&mut self) -> Result<u16, StackError> {
+        #[cfg(feature = "std")]
+        {
+            self.entries.pop().ok_or(StackError::Underflow)
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            self.depth = self.depth.checked_sub(1).ok_or(StackError::Underflow)?;
+            Ok(core::mem::take(&mut self.entries[self.depth]))
+        }
+    }
+
+    /// Retrieves the address the return address at the given depth is mirrored to
+    pub const fn mirror_address(depth: usize) -> u16 {
+        VIP_MIRROR_ADDRESS.wrapping_sub(2 * depth as u16)
+    }
+}
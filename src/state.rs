@@ -0,0 +1,322 @@
+//! This module contains the versioned binary save state format.
+//!
+//! A save state starts with the magic bytes `C8SS`, followed by the format version and the CRC-32
+//! checksum of the application. All multi-byte values are stored little-endian.
+//!
+//! Version 1 stored the random state as a fixed 64-bit value, which is restored into the current
+//! random number generator. Version 2 stores the serialized state of the generator, prefixed by
+//! its length.
+//! Versions 1 and 2 stored the call stack in memory after the font, as native-endian addresses
+//! with a stack pointer. Version 3 stores the separate stack: its limit (0 for unlimited), whether
+//! it's mirrored into memory, its depth and the return addresses.
+//! Versions 1 to 3 stored 4 KiB of memory without the large font, which is restored when they are
+//! loaded. Version 4 prefixes memory by its size, which has to match the memory map of the machine
+//! loading the state.
+
+use crate::{
+    display::{self, Display},
+    keypad::Keypad,
+    machine::{KeyWait, Machine},
+    memory::Memory,
+    quirks::Quirks,
+    registers::Registers,
+    stack::Stack,
+};
+
+/// The address the stack started at in memory before version 3
+const LEGACY_STACK_START: usize = 80;
+
+/// The magic bytes every save state starts with
+pub const MAGIC: [u8; 4] = *b"C8SS";
+
+/// The version of the format written by [`Machine::save_state`]
+pub const VERSION: u16 = 4;
+
+/// The error returned if a save state can't be loaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateError {
+    /// The data doesn't start with the magic bytes, so it isn't a save state
+    InvalidMagic,
+
+    /// The save state was written by a newer, unknown version of the format
+    UnsupportedVersion(u16),
+
+    /// The save state ended before all fields were read
+    Truncated,
+
+    /// The save state contains data after the last field
+    TrailingData,
+
+    /// The save state was made while running a different application
+    RomMismatch {
+        /// The checksum of the currently loaded application
+        expected: u32,
+
+        /// The checksum stored in the save state
+        found: u32,
+    },
+
+    /// A field contains a value the machine can't be in
+    InvalidField(&'static str),
+}
+
+impl std::fmt::Display for StateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidMagic => write!(f, "Not a save state"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "Save state version {version} is not supported, the newest supported version is \
+                 {VERSION}"
+            ),
+            Self::Truncated => write!(f, "Save state is truncated"),
+            Self::TrailingData => write!(f, "Save state contains trailing data"),
+            Self::RomMismatch { expected, found } => write!(
+                f,
+                "Save state belongs to a different application (checksum {found:08X}, expected \
+                 {expected:08X})"
+            ),
+            Self::InvalidField(field) => write!(f, "Save state contains an invalid {field}"),
+        }
+    }
+}
+
+impl std::error::Error for StateError {}
+
+/// Reads fields from a save state
+struct Reader<'a> {
+    /// The bytes that haven't been read yet
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    /// Reads the next bytes
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N], StateError> {
+        let (bytes, rest) = self.data.split_first_chunk().ok_or(StateError::Truncated)?;
+        self.data = rest;
+        Ok(*bytes)
+    }
+
+    /// Reads the given number of bytes
+    fn take(&mut self, length: usize) -> Result<&'a [u8], StateError> {
+        let (bytes, rest) = self
+            .data
+            .split_at_checked(length)
+            .ok_or(StateError::Truncated)?;
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    /// Reads a byte
+    fn u8(&mut self) -> Result<u8, StateError> {
+        Ok(self.bytes::<1>()?[0])
+    }
+
+    /// Reads a 16-bit value
+    fn u16(&mut self) -> Result<u16, StateError> {
+        self.bytes().map(u16::from_le_bytes)
+    }
+
+    /// Reads a 32-bit value
+    fn u32(&mut self) -> Result<u32, StateError> {
+        self.bytes().map(u32::from_le_bytes)
+    }
+
+    /// Reads a 64-bit value
+    fn u64(&mut self) -> Result<u64, StateError> {
+        self.bytes().map(u64::from_le_bytes)
+    }
+
+    /// Reads an optional 4-bit value, 0xFF represents None
+    fn optional_nibble(&mut self, field: &'static str) -> Result<Option<u8>, StateError> {
+        match self.u8()? {
+            0xFF => Ok(None),
+            value @ ..=0xF => Ok(Some(value)),
+            _ => Err(StateError::InvalidField(field)),
+        }
+    }
+}
+
+impl Machine {
+    /// Serializes the complete state of the machine
+    pub fn save_state(&self) -> Vec<u8> {
+        let mut state = Vec::with_capacity(0x1200);
+        state.extend_from_slice(&MAGIC);
+        state.extend_from_slice(&VERSION.to_le_bytes());
+        state.extend_from_slice(&self.rom_checksum.to_le_bytes());
+
+        let size = u32::try_from(self.memory.data().len()).expect("Memory is at most 64 KiB");
+        state.extend_from_slice(&size.to_le_bytes());
+        state.extend_from_slice(self.memory.data());
+        let limit = self.stack.limit().map_or(0, |limit| {
+            u16::try_from(limit).expect("Stack limits are at most 65535 entries")
+        });
+        state.extend_from_slice(&limit.to_le_bytes());
+        state.push(u8::from(self.stack.vip_mirror()));
+        let depth = u16::try_from(self.stack.depth()).expect("Stacks are at most 65535 entries");
+        state.extend_from_slice(&depth.to_le_bytes());
+        for address in self.stack.entries() {
+            state.extend_from_slice(&address.to_le_bytes());
+        }
+
+        state.extend_from_slice(self.registers.data());
+        state.extend_from_slice(&self.registers.address().to_le_bytes());
+        state.push(self.registers.delay());
+        state.push(self.registers.sound_timer());
+        state.extend_from_slice(&self.program_counter.to_le_bytes());
+
+        state.extend_from_slice(&self.display.to_bytes());
+        state.extend_from_slice(&self.keypad.bits().to_le_bytes());
+        match self.key_wait {
+            Some(KeyWait { register, pressed }) => {
+                state.push(register);
+                state.push(pressed.unwrap_or(0xFF));
+            }
+            None => state.extend_from_slice(&[0xFF; 2]),
+        }
+
+        state.push(self.quirks.to_bits());
+        let random_state = self.rng.save();
+        state.push(
+            u8::try_from(random_state.len()).expect("Random states are at most 255 bytes long"),
+        );
+        state.extend_from_slice(&random_state);
+        state
+    }
+
+    /// Restores a state serialized by [`Machine::save_state`].
+    /// The machine is left unchanged if the state can't be loaded.
+    pub fn load_state(&mut self, state: &[u8]) -> Result<(), StateError> {
+        let mut reader = Reader { data: state };
+        if reader.bytes()? != MAGIC {
+            return Err(StateError::InvalidMagic);
+        }
+        let version = reader.u16()?;
+        if !(1..=VERSION).contains(&version) {
+            return Err(StateError::UnsupportedVersion(version));
+        }
+        let checksum = reader.u32()?;
+        if checksum != self.rom_checksum {
+            return Err(StateError::RomMismatch {
+                expected: self.rom_checksum,
+                found: checksum,
+            });
+        }
+
+        let size = if version < 4 {
+            0x1000
+        } else {
+            reader.u32()? as usize
+        };
+        if size != self.memory.layout().size {
+            return Err(StateError::InvalidField("memory size"));
+        }
+        let mut data = reader.take(size)?.to_vec();
+        let stack = if version < 3 {
+            // Move the stack out of memory, keeping the configuration of the current stack
+            let stack_pointer = usize::from(reader.u16()?);
+            if !(LEGACY_STACK_START..0x200).contains(&stack_pointer)
+                || !stack_pointer.is_multiple_of(2)
+            {
+                return Err(StateError::InvalidField("stack pointer"));
+            }
+            let mut stack = Stack::new(None);
+            stack.set_vip_mirror(self.stack.vip_mirror());
+            for address in data[LEGACY_STACK_START..stack_pointer].chunks_exact(2) {
+                let _ = stack.push(u16::from_ne_bytes([address[0], address[1]]));
+            }
+            data[LEGACY_STACK_START..0x200].fill(0);
+            if !stack.set_limit(self.stack.limit()) {
+                return Err(StateError::InvalidField("stack depth"));
+            }
+            stack
+        } else {
+            let limit = match reader.u16()? {
+                0 => None,
+                limit => Some(usize::from(limit)),
+            };
+            let mut stack = Stack::new(None);
+            stack.set_vip_mirror(match reader.u8()? {
+                0 => false,
+                1 => true,
+                _ => return Err(StateError::InvalidField("stack mirror")),
+            });
+            for _ in 0..reader.u16()? {
+                let _ = stack.push(reader.u16()?);
+            }
+            if !stack.set_limit(limit) {
+                return Err(StateError::InvalidField("stack depth"));
+            }
+            stack
+        };
+        let mut memory = Memory::from_raw_parts(self.memory.layout().clone(), &data)
+            .ok_or(StateError::InvalidField("memory size"))?;
+        if version < 4 {
+            // These versions predate the large font, which newer memory maps store where the
+            // stack used to be
+            memory.set_font(&self.font);
+        }
+        memory.set_cache_enabled(self.memory.cache_enabled());
+
+        let mut registers = Registers::new();
+        *registers.data_mut() = reader.bytes()?;
+        *registers.address_mut() = reader.u16()?;
+        registers.set_delay(reader.u8()?);
+        registers.set_sound_timer(reader.u8()?);
+        let program_counter = reader.u16()?;
+
+        let display =
+            Display::from_bytes(&reader.bytes::<{ display::WIDTH * display::HEIGHT / 8 }>()?);
+        let keypad = Keypad::from_bits(reader.u16()?);
+        let key_wait = reader
+            .optional_nibble("key wait register")?
+            .map(|register| {
+                reader
+                    .optional_nibble("pressed key")
+                    .map(|pressed| KeyWait { register, pressed })
+            })
+            .transpose()?;
+        if key_wait.is_none() && reader.u8()? != 0xFF {
+            return Err(StateError::InvalidField("pressed key"));
+        }
+
+        let quirks = Quirks::from_bits(reader.u8()?).ok_or(StateError::InvalidField("quirks"))?;
+        let random_state = if version == 1 {
+            reader.u64()?.to_le_bytes().to_vec()
+        } else {
+            let length = reader.u8()?;
+            reader.take(usize::from(length))?.to_vec()
+        };
+        let mut rng = self.rng.clone();
+        if !rng.restore(&random_state) {
+            return Err(StateError::InvalidField("random state"));
+        }
+        if !reader.data.is_empty() {
+            return Err(StateError::TrailingData);
+        }
+
+        *self = Self {
+            memory,
+            stack,
+            registers,
+            program_counter,
+            display,
+            keypad,
+            font: self.font.clone(),
+            quirks,
+            rng,
+            key_wait,
+            rom_checksum: checksum,
+            rom_sha1: self.rom_sha1,
+            instruction_count: self.instruction_count,
+            coverage: self.coverage.take(),
+            #[cfg(feature = "serde")]
+            database: self.database.take(),
+            #[cfg(feature = "serde")]
+            game: self.game.take(),
+            engine: self.engine,
+            blocks: std::mem::take(&mut self.blocks),
+        };
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+//! Checks the samples synthesized by the beeper
+
+use chip_8::audio::{Beeper, DEFAULT_SAMPLE_RATE};
+
+#[test]
+fn zero_sample_rate_is_rejected() {
+    assert!(Beeper::new(0).is_none());
+    assert_eq!(Beeper::default().sample_rate(), DEFAULT_SAMPLE_RATE);
+}
+
+#[test]
+fn frames_add_up_to_the_sample_rate() {
+    // 100 samples per second don't divide into 60 frames, so frames alternate between 1 and 2
+    let mut beeper = Beeper::new(100).unwrap();
+    let mut samples = Vec::new();
+    let lengths = (0..60)
+        .map(|_| {
+            let before = samples.len();
+            beeper.render_frame(1, &mut samples);
+            samples.len() - before
+        })
+        .collect::<Vec<_>>();
+    assert!(lengths.iter().all(|&length| length == 1 || length == 2));
+    assert_eq!(samples.len(), 100);
+}
+
+#[test]
+fn tone_only_sounds_while_the_sound_timer_runs() {
+    let mut beeper = Beeper::default();
+    let mut samples = Vec::new();
+    beeper.render_frame(1, &mut samples);
+    assert_eq!(samples.len(), 735);
+    assert!(samples.iter().all(|&sample| sample != 0));
+    // 440 Hz at 44100 samples per second changes sign about every 50 samples
+    let changes = samples
+        .windows(2)
+        .filter(|pair| pair[0].signum() != pair[1].signum())
+        .count();
+    assert_eq!(changes, 14);
+
+    samples.clear();
+    beeper.render_frame(0, &mut samples);
+    assert_eq!(samples.len(), 735);
+    assert!(samples.iter().all(|&sample| sample == 0));
+}
@@ -0,0 +1,77 @@
+//! Checks that the block engine executes applications exactly like the interpreter
+
+use chip_8::{
+    blocks::Engine,
+    machine::{Machine, MachineError, Step},
+    quirks::Quirks,
+    rom::Rom,
+};
+
+/// Stores 0x60 0x07 over the instruction at 0x20A of the same block, turning V0 := 1 into
+/// V0 := 7, then halts
+const SELF_MODIFYING: &[u8] = &[
+    0xA2, 0x0A, 0x60, 0x60, 0x61, 0x07, 0xF1, 0x55, 0x62, 0x00, 0x60, 0x01, 0x12, 0x0C,
+];
+
+/// Creates a machine running the program with the engine
+fn machine(program: &[u8], engine: Engine) -> Machine {
+    let mut machine = Machine::new(Quirks::VIP);
+    machine.set_engine(engine);
+    machine
+        .load_rom(&Rom::from_bytes(program).unwrap())
+        .unwrap();
+    machine.set_seed(0);
+    machine
+}
+
+#[test]
+fn blocks_see_their_own_writes() {
+    let mut interpreter = machine(SELF_MODIFYING, Engine::Interpreter);
+    let mut blocks = machine(SELF_MODIFYING, Engine::Blocks);
+    interpreter.run_cycles(7).unwrap();
+    blocks.run_cycles(7).unwrap();
+
+    assert_eq!(blocks.registers().data()[0], 7);
+    assert_eq!(blocks, interpreter);
+}
+
+#[test]
+fn blocks_stop_after_the_requested_cycles() {
+    let program = [0x70, 0x01, 0x70, 0x01, 0x70, 0x01, 0x70, 0x01, 0x12, 0x00];
+    let mut interpreter = machine(&program, Engine::Interpreter);
+    let mut blocks = machine(&program, Engine::Blocks);
+    for cycles in [1, 2, 3, 5, 8, 13] {
+        assert_eq!(blocks.run_cycles(cycles), interpreter.run_cycles(cycles));
+        assert_eq!(blocks, interpreter);
+    }
+    assert_eq!(blocks.instruction_count(), 32);
+    assert_eq!(interpreter.instruction_count(), 32);
+}
+
+#[test]
+fn blocks_report_invalid_instructions_when_reached() {
+    // V0 := 1, then an invalid instruction
+    let program = [0x60, 0x01, 0xFF, 0xFF];
+    let mut blocks = machine(&program, Engine::Blocks);
+    assert_eq!(blocks.run_cycles(1), Ok(Step::Continue));
+    assert_eq!(blocks.registers().data()[0], 1);
+    assert!(matches!(
+        blocks.run_cycles(1),
+        Err(MachineError::InvalidInstruction { address: 0x202, .. })
+    ));
+}
+
+#[test]
+fn blocks_follow_quirk_changes() {
+    // V1 := 0x81, V0 := 2, V0 >>= V1, jump back
+    let program = [0x61, 0x81, 0x60, 0x02, 0x80, 0x16, 0x12, 0x00];
+    let mut interpreter = machine(&program, Engine::Interpreter);
+    let mut blocks = machine(&program, Engine::Blocks);
+    for quirks in [Quirks::VIP, Quirks::SCHIP, Quirks::VIP] {
+        interpreter.set_quirks(quirks);
+        blocks.set_quirks(quirks);
+        assert_eq!(blocks.run_cycles(4), interpreter.run_cycles(4));
+        assert_eq!(blocks, interpreter);
+    }
+    assert_eq!(blocks.registers().data()[0], 0x40);
+}
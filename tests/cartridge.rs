@@ -0,0 +1,141 @@
+//! Checks that cartridges survive being written and read back, and that cartridges holding Octo
+//! sources are compiled on import
+
+use chip_8::{
+    cartridge::{Cartridge, CartridgeError, CartridgeOptions},
+    display::Display,
+    font::Font,
+    octo::compile,
+    quirks::Quirks,
+    rom::Rom,
+    screenshot::Palette,
+};
+
+/// An Octo program using labels, control structures and subroutines
+const SOURCE: &str = "
+# Moves a ball while 5 is held
+:alias x v0
+:alias y v1
+:const KEY 5
+
+: ball
+	0x60 0xF0 0xF0 0x60
+
+: draw
+	i := ball
+	sprite x y 4
+	;
+
+: main
+	x := 10
+	y := 8
+	draw
+	loop
+		v2 := KEY
+		if v2 key begin
+			draw
+			x += 1
+			if x >= 60 then x := 0
+			draw
+		end
+	again
+";
+
+/// Encodes the payload the way Octo does: its length and the JSON text, two bits per pixel of
+/// 160 by 128 frames
+fn encode_cartridge(payload: &str) -> Vec<u8> {
+    let data = [&(payload.len() as u32).to_be_bytes(), payload.as_bytes()].concat();
+    let bits = data
+        .iter()
+        .flat_map(|byte| [6, 4, 2, 0].map(|shift| byte >> shift & 3))
+        .collect::<Vec<_>>();
+    let palette = [0u8; 3 * 8];
+    let mut gif = Vec::new();
+    let mut encoder = gif::Encoder::new(&mut gif, 160, 128, &palette).unwrap();
+    for chunk in bits.chunks(160 * 128) {
+        let mut pixels = chunk.to_vec();
+        pixels.resize(160 * 128, 0);
+        let frame = gif::Frame::from_indexed_pixels(160, 128, pixels, None);
+        encoder.write_frame(&frame).unwrap();
+    }
+    drop(encoder);
+    gif
+}
+
+#[test]
+fn written_cartridges_read_back_the_same() {
+    let options = CartridgeOptions {
+        cycles_per_frame: 30,
+        quirks: Quirks {
+            shift_uses_vy: false,
+            clip_sprites: false,
+            display_wait: true,
+            ..Quirks::default()
+        },
+        palette: Palette {
+            background: [0x11, 0x22, 0x33],
+            foreground: [0xFF, 0x80, 0x00],
+        },
+        font: Font::VIP,
+    };
+    let rom = Rom::from_bytes(compile(SOURCE).unwrap()).unwrap();
+    let cartridge = Cartridge::new(rom, options);
+
+    let mut label = Display::new();
+    label.draw_sprite(0, 0, &[0xFF; 15], true);
+    let mut gif = Vec::new();
+    cartridge.write(&label, &mut gif).unwrap();
+
+    assert_eq!(Cartridge::from_reader(gif.as_slice()).unwrap(), cartridge);
+}
+
+#[test]
+fn octo_sources_are_compiled_on_import() {
+    let payload = serde_json::json!({
+        "program": SOURCE,
+        "options": {
+            "tickrate": 20,
+            "fillColor": "#FFCC00",
+            "backgroundColor": "#996600",
+            "shiftQuirks": true,
+            "loadStoreQuirks": false,
+            "vBlankQuirks": true,
+            "fontStyle": "octo",
+        },
+    });
+    let cartridge =
+        Cartridge::from_reader(encode_cartridge(&payload.to_string()).as_slice()).unwrap();
+
+    assert_eq!(
+        cartridge.rom.data(),
+        [
+            0x12, 0x0C, 0x60, 0xF0, 0xF0, 0x60, 0xA2, 0x02, 0xD0, 0x14, 0x00, 0xEE, 0x60, 0x0A,
+            0x61, 0x08, 0x22, 0x06, 0x62, 0x05, 0xE2, 0x9E, 0x12, 0x26, 0x22, 0x06, 0x70, 0x01,
+            0x6F, 0x3C, 0x8F, 0x07, 0x3F, 0x00, 0x60, 0x00, 0x22, 0x06, 0x12, 0x12
+        ]
+    );
+    assert_eq!(cartridge.options.cycles_per_frame, 20);
+    // Quirk options missing from the cartridge are off, like in Octo
+    assert_eq!(
+        cartridge.options.quirks,
+        Quirks {
+            shift_uses_vy: false,
+            display_wait: true,
+            ..Quirks::XO_CHIP
+        }
+    );
+    assert_eq!(cartridge.options.palette.foreground, [0xFF, 0xCC, 0x00]);
+
+    let mut gif = Vec::new();
+    cartridge.write(&Display::new(), &mut gif).unwrap();
+    assert_eq!(Cartridge::from_reader(gif.as_slice()).unwrap(), cartridge);
+}
+
+#[test]
+fn sources_that_fail_to_compile_are_reported() {
+    let payload = serde_json::json!({ "program": ": main\n\tjump nowhere" });
+    assert!(matches!(
+        Cartridge::from_reader(encode_cartridge(&payload.to_string()).as_slice()),
+        Err(CartridgeError::Compile(_))
+    ));
+}
@@ -0,0 +1,237 @@
+//! Runs the community test applications under every quirk preset with every engine and compares
+//! the final display to the golden hashes in `tests/conformance/hashes.txt`, which both engines
+//! have to match.
+//!
+//! The applications in `roms/` always run. The test suites aren't distributed with the crate,
+//! `tests/conformance/fetch.sh` downloads the binaries of Timendus' chip8-test-suite into
+//! `roms/conformance/`, which CI does before testing. Missing suites are skipped, unless
+//! `CHIP8_REQUIRE_SUITES` is set like in CI, then they fail just like applications without a golden
+//! hash, which print their display. Run with `CHIP8_BLESS=1` and `--test-threads=1` to store the
+//! hashes after checking the displays by hand, both tests rewrite the hash file with the displays
+//! of the interpreter.
+
+use std::{collections::BTreeMap, env, fs, path::Path};
+
+use chip_8::{
+    blocks::Engine,
+    display::{HEIGHT, WIDTH},
+    keypad::Keypad,
+    machine::Machine,
+    memory::Memory,
+    quirks::Quirks,
+    rom::Rom,
+};
+
+/// The file storing the golden hashes
+const HASHES: &str = "tests/conformance/hashes.txt";
+
+/// The quirk presets every application runs under, and their names in the hash file
+const PRESETS: [(&str, Quirks); 3] = [
+    ("vip", Quirks::VIP),
+    ("schip", Quirks::SCHIP),
+    ("xo-chip", Quirks::XO_CHIP),
+];
+
+/// The engines every application runs with
+const ENGINES: [Engine; 2] = [Engine::Interpreter, Engine::Blocks];
+
+/// A test application and how to run it
+struct Case {
+    /// The path of the application
+    path: &'static str,
+
+    /// The number of frames to run
+    frames: u32,
+
+    /// The number of instructions executed per frame
+    cycles_per_frame: u32,
+
+    /// The value written to 0x1FF for every preset, which skips the menus of the test suite
+    select: Option<[u8; 3]>,
+
+    /// The frames the held keys change at and the keys held from then on, in order
+    keys: &'static [(u32, &'static [u8])],
+}
+
+/// The applications distributed with the crate
+const VENDORED: [Case; 1] = [Case {
+    path: "roms/RPS.ch8",
+    frames: 600,
+    cycles_per_frame: 10,
+    select: None,
+    keys: &[],
+}];
+
+/// The community test suites, which are downloaded into `roms/conformance/`
+const SUITES: [Case; 5] = [
+    Case {
+        path: "roms/conformance/3-corax+.ch8",
+        frames: 60,
+        cycles_per_frame: 1000,
+        select: None,
+        keys: &[],
+    },
+    Case {
+        path: "roms/conformance/4-flags.ch8",
+        frames: 60,
+        cycles_per_frame: 1000,
+        select: None,
+        keys: &[],
+    },
+    Case {
+        path: "roms/conformance/5-quirks.ch8",
+        frames: 300,
+        cycles_per_frame: 1000,
+        select: Some([1, 2, 3]),
+        keys: &[],
+    },
+    // The FX0A test only accepts a key once it is released again
+    Case {
+        path: "roms/conformance/6-keypad.ch8",
+        frames: 60,
+        cycles_per_frame: 1000,
+        select: Some([3; 3]),
+        keys: &[(20, &[0x5]), (30, &[])],
+    },
+    Case {
+        path: "roms/conformance/7-beep.ch8",
+        frames: 60,
+        cycles_per_frame: 1000,
+        select: None,
+        keys: &[(0, &[0xB])],
+    },
+];
+
+/// Renders the display as text, a `#` for every lit pixel and a `.` for every other pixel
+fn render(machine: &Machine) -> String {
+    (0..HEIGHT)
+        .map(|y| {
+            (0..WIDTH)
+                .map(|x| match machine.display().pixel(x, y) {
+                    Some(true) => '#',
+                    _ => '.',
+                })
+                .chain(['\n'])
+                .collect::<String>()
+        })
+        .collect()
+}
+
+/// Runs the application under the preset with the engine and returns the machine afterwards
+fn run(case: &Case, rom: &Rom, preset: usize, engine: Engine) -> Machine {
+    let mut machine = Machine::new(PRESETS[preset].1);
+    machine.set_engine(engine);
+    machine.set_seed(0);
+    machine.load_rom(rom).unwrap();
+    machine.set_quirks(PRESETS[preset].1);
+    if let Some(select) = case.select {
+        let layout = machine.memory().layout().clone();
+        let mut contents = machine.memory().data().to_vec();
+        contents[0x1FF] = select[preset];
+        *machine.memory_mut() = Memory::from_raw_parts(layout, &contents).unwrap();
+    }
+    let mut keys = case.keys.iter().peekable();
+    for frame in 0..case.frames {
+        if let Some((_, held)) = keys.next_if(|(start, _)| *start <= frame) {
+            let mut keypad = Keypad::new();
+            for &key in *held {
+                keypad.set(key, true);
+            }
+            *machine.keypad_mut() = keypad;
+        }
+        // Errors are part of the result, the display shows how far the application got
+        if machine.run_frame(case.cycles_per_frame).is_err() {
+            break;
+        }
+    }
+    machine
+}
+
+/// Reads the golden hashes, keyed by the file name of the application and the preset
+fn read_hashes() -> BTreeMap<(String, String), u32> {
+    fs::read_to_string(HASHES)
+        .unwrap()
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let [name, preset, hash] = fields[..] else {
+                panic!("Invalid hash line: {line}");
+            };
+            let hash = u32::from_str_radix(hash, 16).unwrap();
+            ((name.to_owned(), preset.to_owned()), hash)
+        })
+        .collect()
+}
+
+/// Runs the applications under every preset with every engine and compares their displays to the
+/// golden hashes. Missing applications fail if they are required, otherwise they are skipped.
+fn check(cases: &[Case], required: bool) {
+    let bless = env::var_os("CHIP8_BLESS").is_some();
+    let mut hashes = read_hashes();
+    let mut failures = Vec::new();
+    for case in cases {
+        let name = Path::new(case.path).file_name().unwrap().to_string_lossy();
+        let rom = match Rom::from_path(case.path) {
+            Ok(rom) => rom,
+            Err(error) if !required => {
+                eprintln!(
+                    "Skipping {name}, it couldn't be loaded from {}: {error}",
+                    case.path
+                );
+                continue;
+            }
+            Err(error) => {
+                failures.push(format!(
+                    "{name} couldn't be loaded from {}: {error}",
+                    case.path
+                ));
+                continue;
+            }
+        };
+        for ((preset, (preset_name, _)), engine) in PRESETS
+            .iter()
+            .enumerate()
+            .flat_map(|preset| ENGINES.map(|engine| (preset, engine)))
+        {
+            let machine = run(case, &rom, preset, engine);
+            let hash = crc32fast::hash(&machine.display().to_bytes());
+            let key = (name.to_string(), (*preset_name).to_owned());
+            match hashes.get(&key) {
+                Some(&expected) if expected == hash => {}
+                // The interpreter runs first, the other engines are checked against its display
+                _ if bless && engine == Engine::Interpreter => {
+                    hashes.insert(key, hash);
+                }
+                expected => failures.push(format!(
+                    "{name} under {preset_name} with {engine:?}: expected {}, found {hash:08x}\n{}",
+                    expected.map_or("no hash".to_owned(), |hash| format!("{hash:08x}")),
+                    render(&machine)
+                )),
+            }
+        }
+    }
+    if bless {
+        let mut contents = fs::read_to_string(HASHES)
+            .unwrap()
+            .lines()
+            .take_while(|line| line.starts_with('#'))
+            .map(|line| format!("{line}\n"))
+            .collect::<String>();
+        for ((name, preset), hash) in &hashes {
+            contents += &format!("{name} {preset} {hash:08x}\n");
+        }
+        fs::write(HASHES, contents).unwrap();
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn vendored_applications() {
+    check(&VENDORED, true);
+}
+
+#[test]
+fn community_test_suites() {
+    check(&SUITES, env::var_os("CHIP8_REQUIRE_SUITES").is_some());
+}
@@ -0,0 +1,13 @@
+#!/bin/sh
+# Downloads the binaries of Timendus' chip8-test-suite into roms/conformance/ for the conformance
+# tests. Set CHIP8_SUITE_REVISION to a commit or tag of the suite to pin it, the default is main.
+set -eu
+
+revision="${CHIP8_SUITE_REVISION:-main}"
+target="$(dirname "$0")/../../roms/conformance"
+mkdir -p "$target"
+for name in 3-corax+.ch8 4-flags.ch8 5-quirks.ch8 6-keypad.ch8 7-beep.ch8; do
+    curl --fail --silent --show-error --location \
+        --output "$target/$name" \
+        "https://github.com/Timendus/chip8-test-suite/raw/$revision/bin/$name"
+done
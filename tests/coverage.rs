@@ -0,0 +1,96 @@
+//! Checks the accesses tracked by the coverage map and its exports
+
+use chip_8::{
+    coverage::{Access, EXECUTED, READ, SELF_MODIFIED, SelfModification, WRITTEN},
+    machine::Machine,
+    quirks::Quirks,
+};
+use serde_json::json;
+
+/// Overwrites its first instruction, stores a byte past its code and draws a sprite from it
+const PROGRAM: &[u8] = &[
+    0xA2, 0x00, // I := 0x200
+    0x60, 0xA2, // V0 := 0xA2
+    0xF0, 0x55, // Store V0 at 0x200, over the executed first instruction
+    0xA2, 0x10, // I := 0x210
+    0xF0, 0x55, // Store V0 at 0x210, which was never executed
+    0xD0, 0x01, // Draw the byte at 0x210
+    0x12, 0x0C, // Halt
+];
+
+/// Runs the program with coverage tracking until it halts
+fn covered_machine() -> Machine {
+    let mut machine = Machine::new(Quirks::SCHIP);
+    machine.load_program(PROGRAM).unwrap();
+    machine.set_coverage_tracking(true);
+    machine.run_cycles(8).unwrap();
+    machine
+}
+
+#[test]
+fn overwriting_executed_bytes_is_self_modification() {
+    let machine = covered_machine();
+    let coverage = machine.coverage().unwrap();
+
+    // Storing the byte that was already there still counts
+    assert_eq!(
+        coverage.self_modifications(),
+        [SelfModification {
+            address: 0x200,
+            instruction: 0x204,
+        }]
+    );
+    assert_eq!(coverage.access(0x200), EXECUTED | WRITTEN | SELF_MODIFIED);
+    assert_eq!(coverage.access(0x201), EXECUTED);
+    assert_eq!(coverage.access(0x210), WRITTEN | READ);
+    assert_eq!(coverage.access(0x211), 0);
+    let executed = coverage.ranges(EXECUTED);
+    assert_eq!(executed.len(), 1);
+    assert_eq!(executed[0], 0x200..0x20E);
+    assert_eq!(
+        Access::from_flags(coverage.access(0x200)),
+        Access::SelfModified
+    );
+    assert_eq!(Access::from_flags(coverage.access(0x210)), Access::Written);
+}
+
+#[test]
+fn json_lists_ranges_and_self_modifications() {
+    let machine = covered_machine();
+    let mut json = Vec::new();
+    machine.coverage().unwrap().write_json(&mut json).unwrap();
+    let json = serde_json::from_slice::<serde_json::Value>(&json).unwrap();
+
+    assert_eq!(json["size"], 0x1000);
+    assert_eq!(json["executed"], json!([[0x200, 0x20E]]));
+    assert_eq!(json["read"], json!([[0x210, 0x211]]));
+    assert_eq!(json["written"], json!([[0x200, 0x201], [0x210, 0x211]]));
+    assert_eq!(json["self_modified"], json!([[0x200, 0x201]]));
+    assert_eq!(
+        json["self_modifications"],
+        json!([{ "address": 0x200, "instruction": 0x204 }])
+    );
+}
+
+#[test]
+fn overlay_colors_bytes_by_access() {
+    let machine = covered_machine();
+    let mut overlay = Vec::new();
+    machine
+        .coverage()
+        .unwrap()
+        .write_overlay(machine.memory(), &mut overlay)
+        .unwrap();
+    let overlay = String::from_utf8(overlay).unwrap();
+
+    let row = overlay
+        .lines()
+        .find(|line| line.starts_with("200:"))
+        .unwrap();
+    assert!(row.starts_with("200: \x1b[31mA2\x1b[0m \x1b[32m00\x1b[0m"));
+    let row = overlay
+        .lines()
+        .find(|line| line.starts_with("210:"))
+        .unwrap();
+    assert!(row.starts_with("210: \x1b[33mA2\x1b[0m \x1b[2m00\x1b[0m"));
+}
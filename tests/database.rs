@@ -0,0 +1,119 @@
+//! Looks up applications in databases in the format of the community chip-8 database
+
+use chip_8::{database::Database, quirks::Quirks, rom::Rom};
+
+/// Creates a database with one application per platform, named after the platform
+fn platforms() -> Database {
+    let programs = [
+        "originalChip8",
+        "hybridVIP",
+        "modernChip8",
+        "chip48",
+        "superchip1",
+        "superchip",
+        "xochip",
+        "megachip8",
+    ]
+    .iter()
+    .enumerate()
+    .map(|(index, platform)| {
+        format!(
+            r#"{{"title": "{platform}", "roms": {{"{index:040x}": {{"platforms": ["{platform}"]}}}}}}"#
+        )
+    })
+    .collect::<Vec<_>>();
+    Database::from_json(&format!("[{}]", programs.join(","))).unwrap()
+}
+
+/// Creates a SHA-1 hash from a small number, matching the hashes of [`platforms`]
+fn hash(index: u8) -> [u8; 20] {
+    let mut hash = [0; 20];
+    hash[19] = index;
+    hash
+}
+
+#[test]
+fn builtin_database_knows_the_bundled_application() {
+    let rom = Rom::from_path("roms/RPS.ch8").unwrap();
+    let game = Database::builtin().lookup(rom.sha1()).unwrap();
+    assert_eq!(game.title, "Rock Paper Scissors");
+    assert_eq!(game.platform.as_deref(), Some("modernChip8"));
+    assert_eq!(
+        game.quirks,
+        Some(Quirks {
+            vf_reset: false,
+            display_wait: false,
+            ..Quirks::VIP
+        })
+    );
+    assert_eq!(game.cycles_per_frame, Some(10));
+    assert_eq!(Database::builtin().lookup([0; 20]), None);
+}
+
+#[test]
+fn platforms_have_their_quirks() {
+    let database = platforms();
+    let superchip1 = Quirks {
+        load_store_increments_i: true,
+        increment_i_by_x: true,
+        ..Quirks::SCHIP
+    };
+    for (index, quirks) in [
+        (0, Some(Quirks::VIP)),
+        (1, Some(Quirks::VIP)),
+        (
+            2,
+            Some(Quirks {
+                vf_reset: false,
+                display_wait: false,
+                ..Quirks::VIP
+            }),
+        ),
+        (3, Some(superchip1)),
+        (4, Some(superchip1)),
+        (5, Some(Quirks::SCHIP)),
+        (6, Some(Quirks::XO_CHIP)),
+        (7, None),
+    ] {
+        let game = database.lookup(hash(index)).unwrap();
+        assert_eq!(game.quirks, quirks, "{}", game.title);
+    }
+}
+
+#[test]
+fn quirky_platforms_override_quirks() {
+    let database = Database::from_json(
+        r##"[{
+            "title": "Quirky",
+            "authors": ["Someone"],
+            "roms": {
+                "00000000000000000000000000000000000000AB": {
+                    "platforms": ["megachip8", "superchip", "xochip"],
+                    "quirkyPlatforms": {
+                        "superchip": {"memoryIncrementByX": true, "vblank": true, "logic": true}
+                    },
+                    "tickrate": 30,
+                    "keys": {"up": 5, "a": 22},
+                    "colors": {"pixels": ["#000000", "#ff8000"]}
+                }
+            }
+        }]"##,
+    )
+    .unwrap();
+    let game = database.lookup(hash(0xAB)).unwrap();
+    assert_eq!(game.platform.as_deref(), Some("superchip"));
+    assert_eq!(
+        game.quirks,
+        Some(Quirks {
+            vf_reset: true,
+            load_store_increments_i: true,
+            increment_i_by_x: true,
+            display_wait: true,
+            ..Quirks::SCHIP
+        })
+    );
+    assert_eq!(game.authors, ["Someone"]);
+    assert_eq!(game.cycles_per_frame, Some(30));
+    assert_eq!(game.keys, [("a".to_owned(), 6), ("up".to_owned(), 5)]);
+    assert_eq!(game.palette.unwrap().foreground, [0xFF, 0x80, 0x00]);
+}
@@ -0,0 +1,109 @@
+//! Checks font presets, font files and the glyphs FX29 resolves to
+
+use chip_8::{
+    font::{Font, FontError, LARGE_FONT_SIZE, SMALL_FONT_SIZE},
+    machine::Machine,
+    memory::MemoryLayout,
+    quirks::Quirks,
+};
+
+#[test]
+fn presets_are_found_by_name() {
+    assert_eq!(Font::from_name("standard"), Some(Font::STANDARD));
+    assert_eq!(Font::from_name("VIP"), Some(Font::VIP));
+    assert_eq!(Font::from_name("dream6800"), Some(Font::DREAM_6800));
+    assert_eq!(Font::from_name("eti-660"), Some(Font::ETI_660));
+    assert_eq!(Font::from_name("fish-n-chips"), Some(Font::FISH_N_CHIPS));
+    assert_eq!(Font::from_name("schip"), None);
+
+    let presets = [
+        Font::STANDARD,
+        Font::VIP,
+        Font::DREAM_6800,
+        Font::ETI_660,
+        Font::FISH_N_CHIPS,
+    ];
+    for (index, font) in presets.iter().enumerate() {
+        for other in &presets[index + 1..] {
+            assert_ne!(font.small, other.small);
+        }
+    }
+}
+
+/// The character table of the COSMAC VIP interpreter at 0x8110, where glyphs overlap
+const VIP_CHARACTERS: [u8; 51] = [
+    0xF0, 0x80, 0xF0, 0x80, 0xF0, 0x80, 0x80, 0x80, 0xF0, 0x50, 0x70, 0x50, 0xF0, 0x50, 0x50, 0x50,
+    0xF0, 0x80, 0xF0, 0x10, 0xF0, 0x80, 0xF0, 0x90, 0xF0, 0x90, 0xF0, 0x10, 0xF0, 0x10, 0xF0, 0x90,
+    0xF0, 0x90, 0x90, 0x90, 0xF0, 0x10, 0x10, 0x10, 0x10, 0x60, 0x20, 0x20, 0x20, 0x70, 0xA0, 0xA0,
+    0xF0, 0x20, 0x20,
+];
+
+/// The low address bytes of the digits 0 to F in the COSMAC VIP interpreter at 0x8100
+const VIP_DIGIT_ADDRESSES: [u8; 16] = [
+    0x30, 0x39, 0x22, 0x2A, 0x3E, 0x20, 0x24, 0x34, 0x26, 0x28, 0x2E, 0x18, 0x14, 0x1C, 0x10, 0x12,
+];
+
+#[test]
+fn vip_glyphs_match_the_interpreter() {
+    for (digit, address) in VIP_DIGIT_ADDRESSES.into_iter().enumerate() {
+        let start = usize::from(address) - 0x10;
+        assert_eq!(
+            Font::VIP.small[digit],
+            VIP_CHARACTERS[start..start + 5],
+            "digit {digit:X}"
+        );
+    }
+}
+
+#[test]
+fn fonts_are_read_from_raw_glyphs() {
+    let small: Vec<u8> = (0..SMALL_FONT_SIZE as u8).collect();
+    let font = Font::from_bytes(&small).unwrap();
+    assert_eq!(font.small[1], [5, 6, 7, 8, 9]);
+    assert_eq!(font.large, Font::STANDARD.large);
+
+    let both: Vec<u8> = (0..(SMALL_FONT_SIZE + LARGE_FONT_SIZE) as u8).collect();
+    let font = Font::from_bytes(&both).unwrap();
+    assert_eq!(font.large[0][0], SMALL_FONT_SIZE as u8);
+
+    assert!(matches!(
+        Font::from_bytes(&both[..SMALL_FONT_SIZE + 1]),
+        Err(FontError::InvalidLength(81))
+    ));
+
+    let path = std::env::temp_dir().join(format!("chip-8-font-{}.bin", std::process::id()));
+    std::fs::write(&path, &small).unwrap();
+    let from_path = Font::from_path(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(from_path.unwrap().small[1], [5, 6, 7, 8, 9]);
+    assert!(matches!(
+        Font::from_path("fonts/missing.bin"),
+        Err(FontError::Io(_))
+    ));
+}
+
+#[test]
+fn sprite_addresses_resolve_against_the_active_font() {
+    let layout = MemoryLayout {
+        small_font: 0x100,
+        large_font: 0x150,
+        ..MemoryLayout::VIP
+    };
+    let mut machine = Machine::with_layout(Quirks::VIP, layout).unwrap();
+    machine.set_font(Font::DREAM_6800);
+    // V0 = 0x1A, I = address of the glyph of A (only the low nibble counts)
+    machine.load_program(&[0x60, 0x1A, 0xF0, 0x29]).unwrap();
+    machine.step().unwrap();
+    machine.step().unwrap();
+
+    let address = usize::from(machine.registers().address());
+    assert_eq!(address, 0x100 + 0xA * 5);
+    assert_eq!(
+        machine.memory().data()[address..address + 5],
+        Font::DREAM_6800.small[0xA]
+    );
+    assert_eq!(
+        machine.memory().data()[0x150..0x150 + 10],
+        Font::DREAM_6800.large[0]
+    );
+}
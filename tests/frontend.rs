@@ -0,0 +1,113 @@
+//! Drives machines through the run loop with scripted frontends
+
+use std::{collections::VecDeque, fs, io};
+
+use chip_8::{
+    frontend::{Command, DEFAULT_TURBO, Frontend, Input, Runner},
+    machine::Machine,
+    movie::Movie,
+    quirks::Quirks,
+    rom::Rom,
+};
+
+/// A frontend replaying a list of inputs, quitting once they run out
+#[derive(Default)]
+struct Scripted {
+    /// The remaining inputs
+    inputs: VecDeque<Input>,
+
+    /// The number of presented frames
+    presented: usize,
+
+    /// The number of frames whose sound was played
+    played: usize,
+}
+
+impl Frontend for Scripted {
+    fn poll_input(&mut self) -> Input {
+        self.inputs.pop_front().unwrap_or_default()
+    }
+
+    fn present(&mut self, _machine: &Machine) -> io::Result<()> {
+        self.presented += 1;
+        Ok(())
+    }
+
+    fn play_audio(&mut self, _sound_timer: u8) {
+        self.played += 1;
+    }
+
+    fn should_quit(&self) -> bool {
+        self.inputs.is_empty()
+    }
+}
+
+/// Creates a machine running rock paper scissors
+fn rps() -> Machine {
+    let mut machine = Machine::new(Quirks::SCHIP);
+    machine
+        .load_rom(&Rom::from_path("roms/RPS.ch8").unwrap())
+        .unwrap();
+    machine
+}
+
+#[test]
+fn rps_session_through_frontend() {
+    let movie = Movie::from_bytes(&fs::read("tests/movies/rps.c8m").unwrap()).unwrap();
+    let mut expected = rps();
+    movie.play(&mut expected).unwrap();
+
+    let mut machine = rps();
+    movie.start_playback(&mut machine).unwrap();
+    let mut frontend = Scripted {
+        inputs: movie
+            .frames()
+            .iter()
+            .map(|&keypad| Input {
+                keypad,
+                ..Input::default()
+            })
+            .chain([Input::default()])
+            .collect(),
+        ..Scripted::default()
+    };
+    let mut runner = Runner::new(movie.cycles_per_frame());
+    runner.set_real_time(false);
+    runner.run(&mut machine, &mut frontend).unwrap();
+    assert_eq!(runner.frame(), movie.frames().len());
+    assert_eq!(frontend.presented, movie.frames().len());
+    assert!(machine == expected);
+}
+
+#[test]
+fn pause_advance_and_fast_forward() {
+    let commands = |commands: &[Command]| Input {
+        commands: commands.to_vec(),
+        ..Input::default()
+    };
+    let mut frontend = Scripted {
+        inputs: VecDeque::from([
+            commands(&[Command::TogglePause]),
+            commands(&[]),
+            commands(&[Command::Advance]),
+            commands(&[Command::TogglePause]),
+            Input {
+                fast_forward: true,
+                ..Input::default()
+            },
+            commands(&[Command::Quit]),
+        ]),
+        ..Scripted::default()
+    };
+    let mut machine = rps();
+    let mut runner = Runner::new(10);
+    runner.set_real_time(false);
+    let mut frames = Vec::new();
+    while runner.run_frame(&mut machine, &mut frontend).unwrap() {
+        frames.push(runner.frame());
+    }
+    assert_eq!(frames, [0, 0, 1, 2, 2 + DEFAULT_TURBO as usize]);
+    assert_eq!(frontend.presented, 5);
+    // Only the presented frames that were emulated play their sound
+    assert_eq!(frontend.played, 3);
+}
@@ -0,0 +1,59 @@
+//! Decodes instruction words and checks the operands of every instruction
+
+use chip_8::instruction::{Instruction, InvalidInstruction};
+
+#[test]
+fn operands_are_decoded() {
+    for (word, instruction) in [
+        (0x00E0, Instruction::ClearScreen),
+        (0x00EE, Instruction::Return),
+        (0x00FD, Instruction::Exit),
+        (0x0123, Instruction::SystemAddress(0x123)),
+        (0x1ABC, Instruction::JumpAddress(0xABC)),
+        (0x2ABC, Instruction::CallAddress(0xABC)),
+        (0x3A12, Instruction::SkipEqualRegByte(0xA, 0x12)),
+        (0x4A12, Instruction::SkipNotEqualRegByte(0xA, 0x12)),
+        (0x5AB0, Instruction::SkipEqualRegisters(0xAB)),
+        (0x6A12, Instruction::LoadByte(0xA, 0x12)),
+        (0x7A12, Instruction::AddByte(0xA, 0x12)),
+        (0x8AB0, Instruction::LoadRegister(0xAB)),
+        (0x8AB1, Instruction::Or(0xAB)),
+        (0x8AB2, Instruction::And(0xAB)),
+        (0x8AB3, Instruction::Xor(0xAB)),
+        (0x8AB4, Instruction::Add(0xAB)),
+        (0x8AB5, Instruction::Sub(0xAB)),
+        (0x8AB6, Instruction::ShiftRight(0xAB)),
+        (0x8AB7, Instruction::SubInverted(0xAB)),
+        (0x8ABE, Instruction::ShiftLeft(0xAB)),
+        (0x9AB0, Instruction::SkipNotEqualReg(0xAB)),
+        (0xAABC, Instruction::LoadI(0xABC)),
+        (0xBABC, Instruction::JumpAddressOffset(0xABC)),
+        (0xCA12, Instruction::RandRange(0xA, 0x12)),
+        // The registers holding the position share a byte, the height is the last nibble
+        (0xDAB5, Instruction::Draw(0xAB, 0x5)),
+        (0xEA9E, Instruction::SkipPressed(0xA)),
+        (0xEAA1, Instruction::SkipNotPressed(0xA)),
+        (0xFA07, Instruction::LoadRegisterDelayTimer(0xA)),
+        (0xFA0A, Instruction::LoadKeyPress(0xA)),
+        (0xFA15, Instruction::LoadDelayTimerRegister(0xA)),
+        (0xFA18, Instruction::LoadSoundTimerRegister(0xA)),
+        (0xFA1E, Instruction::AddAddresssRegister(0xA)),
+        (0xFA29, Instruction::LoadSpriteAddress(0xA)),
+        (0xFA33, Instruction::LoadRegisterSprites(0xA)),
+        (0xFA55, Instruction::LoadMemoryRegisters(0xA)),
+        (0xFA65, Instruction::LoadRegistersMemory(0xA)),
+    ] {
+        assert_eq!(Instruction::try_from(word), Ok(instruction), "{word:04X}");
+    }
+}
+
+#[test]
+fn invalid_words_are_rejected() {
+    for word in [0x5AB1, 0x8AB8, 0x9AB1, 0xEA00, 0xFA00, 0xFAFF] {
+        assert_eq!(
+            Instruction::try_from(word),
+            Err(InvalidInstruction(word)),
+            "{word:04X}"
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! Runs machine configurations in lockstep and checks where they diverge
+
+use std::fs;
+
+use chip_8::{
+    blocks::Engine,
+    keypad::Keypad,
+    lockstep::Lockstep,
+    machine::{Machine, Step},
+    movie::Movie,
+    quirks::Quirks,
+    rom::Rom,
+};
+
+/// Creates a machine running the application with the engine and instruction cache
+fn machine(rom: &Rom, quirks: Quirks, engine: Engine, cached: bool) -> Machine {
+    let mut machine = Machine::new(quirks);
+    machine.memory_mut().set_cache_enabled(cached);
+    machine.set_engine(engine);
+    machine.load_rom(rom).unwrap();
+    machine.set_quirks(quirks);
+    machine.set_seed(0);
+    machine
+}
+
+#[test]
+fn rps_session_engines_agree() {
+    let movie = Movie::from_bytes(&fs::read("tests/movies/rps.c8m").unwrap()).unwrap();
+    let rom = Rom::from_path("roms/RPS.ch8").unwrap();
+    let mut left = machine(&rom, Quirks::SCHIP, Engine::Interpreter, false);
+    let mut right = machine(&rom, Quirks::SCHIP, Engine::Blocks, true);
+    movie.start_playback(&mut left).unwrap();
+    movie.start_playback(&mut right).unwrap();
+
+    let mut lockstep = Lockstep::new(left, right, movie.cycles_per_frame());
+    if let Some(divergence) = lockstep.run(movie.frames().iter().copied()) {
+        panic!("{divergence}");
+    }
+    assert_eq!(lockstep.frame(), movie.frames().len());
+}
+
+#[test]
+fn shift_quirk_diverges_at_first_shift() {
+    // V0 = 5, V1 = 3, V0 >>= 1, then loop forever
+    let rom = Rom::from_bytes([0x60, 0x05, 0x61, 0x03, 0x80, 0x16, 0x12, 0x06]).unwrap();
+    let left = machine(&rom, Quirks::VIP, Engine::Interpreter, true);
+    let right = machine(&rom, Quirks::SCHIP, Engine::Blocks, true);
+
+    let mut lockstep = Lockstep::new(left, right, 10);
+    let divergence = lockstep.run([Keypad::new(); 3]).unwrap();
+    assert_eq!((divergence.frame, divergence.cycle), (0, Some(3)));
+    assert_eq!(divergence.left.registers().data()[0], 1);
+    assert_eq!(divergence.right.registers().data()[0], 2);
+    assert!(
+        divergence
+            .to_string()
+            .contains("V0      01                  02                  <")
+    );
+}
+
+#[test]
+fn key_waits_run_in_lockstep_until_they_diverge() {
+    // V0 := key, V1 := 3, V0 := V1 >> 1 or V0 >> 1 depending on the quirk, then loop forever
+    let rom = Rom::from_bytes([0xF0, 0x0A, 0x61, 0x03, 0x80, 0x16, 0x12, 0x06]).unwrap();
+    let left = machine(&rom, Quirks::VIP, Engine::Interpreter, true);
+    let right = machine(&rom, Quirks::SCHIP, Engine::Blocks, true);
+
+    // The key is pressed in the first frame and released in the second, which ends the wait
+    let mut lockstep = Lockstep::new(left, right, 10);
+    let mut pressed = Keypad::new();
+    pressed.set(0x5, true);
+    assert_eq!(
+        lockstep.run_frame(pressed).unwrap(),
+        Ok(Step::WaitingForKey)
+    );
+    let divergence = lockstep.run_frame(Keypad::new()).unwrap_err();
+    assert_eq!((divergence.frame, divergence.cycle), (1, Some(3)));
+    assert_eq!(divergence.left.registers().data()[0], 1);
+    assert_eq!(divergence.right.registers().data()[0], 2);
+}
@@ -0,0 +1,153 @@
+//! Checks memory maps and the instructions accessing the end of memory
+
+use chip_8::{
+    instruction::Instruction,
+    machine::{Machine, MachineError},
+    memory::{MAX_SIZE, Memory, MemoryLayout},
+    quirks::Quirks,
+};
+
+/// Creates a machine with 64 KiB of memory, I pointing to the last byte and the instruction at
+/// the program start
+fn end_of_memory(instruction: [u8; 2]) -> Machine {
+    let mut machine = Machine::with_layout(Quirks::VIP, MemoryLayout::XO_CHIP).unwrap();
+    machine.load_program(&instruction).unwrap();
+    *machine.registers_mut().address_mut() = 0xFFFF;
+    machine
+}
+
+#[test]
+fn memory_is_as_large_as_its_map() {
+    for layout in [
+        MemoryLayout::VIP,
+        MemoryLayout::ETI_660,
+        MemoryLayout::XO_CHIP,
+    ] {
+        let memory = Memory::with_layout(layout.clone()).unwrap();
+        assert_eq!(memory.data().len(), layout.size);
+    }
+}
+
+#[test]
+fn invalid_layouts_are_rejected() {
+    let empty = MemoryLayout {
+        size: 0,
+        ..MemoryLayout::VIP
+    };
+    let too_large = MemoryLayout {
+        size: MAX_SIZE + 1,
+        ..MemoryLayout::XO_CHIP
+    };
+    let font_outside = MemoryLayout {
+        large_font: 0xFF0,
+        ..MemoryLayout::VIP
+    };
+    let program_outside = MemoryLayout {
+        program_start: 0x1000,
+        ..MemoryLayout::VIP
+    };
+    for layout in [empty, too_large, font_outside, program_outside] {
+        assert!(!layout.is_valid(), "{layout:?}");
+        assert!(Memory::with_layout(layout.clone()).is_none());
+        assert!(Memory::from_raw_parts(layout.clone(), &vec![0; layout.size]).is_none());
+        assert!(Machine::with_layout(Quirks::VIP, layout).is_none());
+    }
+}
+
+#[test]
+fn stores_past_the_end_of_memory_fail() {
+    // BCD, store V0 and V1, load V0 and V1
+    for instruction in [[0xF0, 0x33], [0xF1, 0x55], [0xF1, 0x65]] {
+        let mut machine = end_of_memory(instruction);
+        assert_eq!(
+            machine.step(),
+            Err(MachineError::MemoryAccess(0xFFFF)),
+            "{instruction:02X?}"
+        );
+    }
+
+    // Loading only the last byte still works
+    let mut machine = end_of_memory([0xF0, 0x65]);
+    machine.memory_mut()[0xFFFF] = 0x42;
+    machine.step().unwrap();
+    assert_eq!(machine.registers().data()[0], 0x42);
+}
+
+#[test]
+fn program_counter_stays_in_memory() {
+    let mut machine = end_of_memory([0x00, 0xE0]);
+    machine.memory_mut()[0xFFFE] = 0x60;
+    machine.set_program_counter(0xFFFE);
+    assert_eq!(
+        machine.step(),
+        Err(MachineError::ProgramCounterOutOfBounds(0xFFFE))
+    );
+
+    // Skipping the last instruction
+    let mut machine = end_of_memory([0x00, 0xE0]);
+    machine.memory_mut()[0xFFFC] = 0x30;
+    machine.set_program_counter(0xFFFC);
+    assert_eq!(
+        machine.step(),
+        Err(MachineError::ProgramCounterOutOfBounds(0xFFFC))
+    );
+}
+
+#[test]
+fn sized_machines_reject_larger_layouts() {
+    assert!(Machine::<0x1000>::sized(Quirks::VIP, MemoryLayout::XO_CHIP).is_none());
+    let mut machine = Machine::<0x1000>::sized(Quirks::VIP, MemoryLayout::ETI_660).unwrap();
+    machine.load_program(&[0x60, 0x42, 0x16, 0x00]).unwrap();
+    machine.run_cycles(10).unwrap();
+    assert_eq!(machine.registers().data()[0], 0x42);
+    assert_eq!(machine.memory().data().len(), 0x1000);
+}
+
+#[test]
+fn writes_discard_cached_instructions() {
+    let mut memory = Memory::new();
+    for (offset, byte) in [0x60, 0x01, 0x61, 0x02].into_iter().enumerate() {
+        assert!(memory.store(0x200 + offset as u16, byte));
+    }
+    assert_eq!(
+        memory.instruction(0x200),
+        Some(Ok(Instruction::LoadByte(0, 1)))
+    );
+    assert_eq!(
+        memory.instruction(0x202),
+        Some(Ok(Instruction::LoadByte(1, 2)))
+    );
+
+    // Writing the second byte of an instruction discards it, but not the one after it
+    assert!(memory.store(0x201, 0x05));
+    assert_eq!(
+        memory.instruction(0x200),
+        Some(Ok(Instruction::LoadByte(0, 5)))
+    );
+    assert_eq!(
+        memory.instruction(0x202),
+        Some(Ok(Instruction::LoadByte(1, 2)))
+    );
+}
+
+#[test]
+fn stored_registers_replace_cached_instructions() {
+    let mut machine = Machine::new(Quirks::VIP);
+    machine
+        .load_program(&[
+            0x61, 0x01, // V1 := 1, overwritten with V1 := 7
+            0xA2, 0x00, // I := 0x200
+            0x60, 0x61, // V0 := 0x61
+            0x61, 0x07, // V1 := 7
+            0xF1, 0x55, // Store V0 and V1 at 0x200
+            0x61, 0x00, // V1 := 0
+            0x12, 0x00, // Jump to 0x200
+        ])
+        .unwrap();
+    assert!(machine.memory().cache_enabled());
+
+    machine.run_cycles(7).unwrap();
+    assert_eq!(machine.registers().data()[1], 0);
+    machine.run_cycles(1).unwrap();
+    assert_eq!(machine.registers().data()[1], 7);
+}
@@ -0,0 +1,106 @@
+//! Replays recorded sessions and compares the final frame to the expected frame
+
+use std::fs;
+
+use chip_8::{
+    blocks::Engine,
+    display::{HEIGHT, WIDTH},
+    font::Font,
+    keypad::Keypad,
+    machine::Machine,
+    memory::MemoryLayout,
+    movie::{Movie, MovieError},
+    quirks::Quirks,
+    rng::CosmacVip,
+    rom::Rom,
+};
+
+/// Renders the display as text, a `#` for every lit pixel and a `.` for every other pixel
+fn render(machine: &Machine) -> String {
+    (0..HEIGHT)
+        .map(|y| {
+            (0..WIDTH)
+                .map(|x| match machine.display().pixel(x, y) {
+                    Some(true) => '#',
+                    _ => '.',
+                })
+                .chain(['\n'])
+                .collect::<String>()
+        })
+        .collect()
+}
+
+/// Replays the rock paper scissors session using the engine
+fn rps_session(engine: Engine) {
+    let movie = Movie::from_bytes(&fs::read("tests/movies/rps.c8m").unwrap()).unwrap();
+    let mut machine = Machine::new(Quirks::SCHIP);
+    machine.set_engine(engine);
+    machine
+        .load_rom(&Rom::from_path("roms/RPS.ch8").unwrap())
+        .unwrap();
+    movie.play(&mut machine).unwrap();
+    assert_eq!(
+        render(&machine),
+        fs::read_to_string("tests/movies/rps.txt").unwrap()
+    );
+}
+
+#[test]
+fn rps_session_interpreter() {
+    rps_session(Engine::Interpreter);
+}
+
+#[test]
+fn rps_session_blocks() {
+    rps_session(Engine::Blocks);
+}
+
+#[test]
+fn movie_round_trip() {
+    let movie = fs::read("tests/movies/rps.c8m").unwrap();
+    assert_eq!(Movie::from_bytes(&movie).unwrap().to_bytes(), movie);
+}
+
+/// Creates a machine running rock paper scissors with the memory map and the COSMAC VIP random
+/// number generator
+fn vip_machine(layout: MemoryLayout) -> Machine {
+    let mut machine = Machine::with_layout(Quirks::VIP, layout).unwrap();
+    machine.set_rng(CosmacVip::new(0, [7; 256]));
+    machine
+        .load_rom(&Rom::from_path("roms/RPS.ch8").unwrap())
+        .unwrap();
+    machine
+}
+
+#[test]
+fn movies_store_the_machine_configuration() {
+    let mut machine = vip_machine(MemoryLayout::VIP);
+    let mut movie = Movie::start_recording(&mut machine, 0x1234, 15);
+    movie.record_frame(Keypad::default());
+    let bytes = movie.to_bytes();
+    assert_eq!(Movie::from_bytes(&bytes), Ok(movie.clone()));
+
+    // The generator is restarted instead of replaced
+    let mut machine = vip_machine(MemoryLayout::VIP);
+    movie.start_playback(&mut machine).unwrap();
+    assert!(machine.rng().configuration().starts_with(b"cosmac-vip"));
+
+    let other_layout = vip_machine(MemoryLayout::XO_CHIP);
+    let mut other_font = vip_machine(MemoryLayout::VIP);
+    other_font.set_font(Font::ETI_660);
+    let mut other_stack = vip_machine(MemoryLayout::VIP);
+    other_stack.stack_mut().set_limit(Some(12));
+    let mut other_rng = vip_machine(MemoryLayout::VIP);
+    other_rng.set_seed(0);
+    for (mut machine, field) in [
+        (other_layout, "memory map"),
+        (other_font, "font"),
+        (other_stack, "stack"),
+        (other_rng, "random number generator"),
+    ] {
+        assert_eq!(
+            movie.start_playback(&mut machine),
+            Err(MovieError::ConfigurationMismatch(field))
+        );
+    }
+}
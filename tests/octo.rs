@@ -0,0 +1,134 @@
+//! Checks the bytes the Octo compiler produces for the statements of chip-8 and super chip-48
+
+use chip_8::octo::compile;
+
+/// Compiles the source, panicking on errors
+fn bytes(source: &str) -> Vec<u8> {
+    compile(source).unwrap_or_else(|error| panic!("{error}"))
+}
+
+#[test]
+fn main_at_the_start_needs_no_jump() {
+    let source = "
+        : main
+            clear
+            v0 := 0x12
+            v1 += 3
+            v1 -= 1
+            i := sprite
+            sprite v0 v1 5
+            loop again
+        : sprite
+            0xF0 0x90
+    ";
+    assert_eq!(
+        bytes(source),
+        [
+            0x00, 0xE0, 0x60, 0x12, 0x71, 0x03, 0x71, 0xFF, 0xA2, 0x0E, 0xD0, 0x15, 0x12, 0x0C,
+            0xF0, 0x90
+        ]
+    );
+}
+
+#[test]
+fn code_before_main_is_jumped_over() {
+    let source = "
+        : draw
+            sprite v0 v0 1
+            ;
+        : main
+            draw
+            jump main
+    ";
+    assert_eq!(
+        bytes(source),
+        [0x12, 0x06, 0xD0, 0x01, 0x00, 0xEE, 0x22, 0x02, 0x12, 0x06]
+    );
+}
+
+#[test]
+fn conditions_skip_the_right_instructions() {
+    let source = "
+        : main
+            if v1 == 2 then v2 := 1
+            if v3 key begin
+                v4 := 1
+            else
+                v4 := 2
+            end
+            if v5 < 7 then exit
+    ";
+    assert_eq!(
+        bytes(source),
+        [
+            0x41, 0x02, 0x62, 0x01, 0xE3, 0x9E, 0x12, 0x0C, 0x64, 0x01, 0x12, 0x0E, 0x64, 0x02,
+            0x6F, 0x07, 0x8F, 0x57, 0x3F, 0x01, 0x00, 0xFD
+        ]
+    );
+}
+
+#[test]
+fn directives_define_names_and_data() {
+    let source = "
+        :const SPEED 3
+        :alias counter v6
+        : main
+            counter := SPEED
+            loop
+                while counter != 0
+                counter -= 1
+            again
+            :unpack 0xA data
+            : patched :next value
+            v7 := 9
+        : data
+            :byte 1
+            :pointer main
+    ";
+    assert_eq!(
+        bytes(source),
+        [
+            0x66, 0x03, 0x46, 0x00, 0x12, 0x0A, 0x76, 0xFF, 0x12, 0x02, 0x60, 0xA2, 0x61, 0x10,
+            0x67, 0x09, 0x01, 0x02, 0x00
+        ]
+    );
+}
+
+#[test]
+fn super_chip_statements_are_compiled() {
+    let source = "
+        : main
+            hires
+            scroll-down 4
+            scroll-left
+            scroll-right
+            i := bighex v2
+            saveflags v3
+            loadflags v3
+            lores
+            exit
+    ";
+    assert_eq!(
+        bytes(source),
+        [
+            0x00, 0xFF, 0x00, 0xC4, 0x00, 0xFC, 0x00, 0xFB, 0xF2, 0x30, 0xF3, 0x75, 0xF3, 0x85,
+            0x00, 0xFE, 0x00, 0xFD
+        ]
+    );
+}
+
+#[test]
+fn errors_name_their_line() {
+    let missing_main = compile(": start\n\tclear").unwrap_err();
+    assert!(missing_main.message.contains("main"));
+
+    let undefined = compile(": main\n\n\tjump nowhere").unwrap_err();
+    assert_eq!(undefined.line, 3);
+    assert!(undefined.message.contains("nowhere"));
+
+    let macro_source = compile(": main\n:macro twice A { A A }").unwrap_err();
+    assert_eq!(macro_source.line, 2);
+
+    let unterminated = compile(": main\n\tloop\n\tclear");
+    assert!(unterminated.is_err());
+}
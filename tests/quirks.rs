@@ -0,0 +1,74 @@
+//! Checks the instructions whose behaviour depends on the quirks
+
+use chip_8::{
+    machine::{Machine, Step},
+    quirks::Quirks,
+};
+
+/// Creates a machine with the quirks running the program
+fn machine(quirks: Quirks, program: &[u8]) -> Machine {
+    let mut machine = Machine::new(quirks);
+    machine.load_program(program).unwrap();
+    machine
+}
+
+#[test]
+fn quirks_survive_packing() {
+    for quirks in [
+        Quirks::VIP,
+        Quirks::SCHIP,
+        Quirks::XO_CHIP,
+        Quirks {
+            increment_i_by_x: true,
+            display_wait: true,
+            ..Quirks::SCHIP
+        },
+    ] {
+        assert_eq!(Quirks::from_bits(quirks.to_bits()), Some(quirks));
+    }
+    assert_eq!(Quirks::from_bits(0x80), None);
+}
+
+#[test]
+fn storing_registers_increments_i() {
+    // Points I at 0x300 and stores V0 to V2
+    let program = [0xA3, 0x00, 0xF2, 0x55];
+    for (quirks, address) in [
+        (Quirks::VIP, 0x303),
+        (Quirks::SCHIP, 0x300),
+        (
+            Quirks {
+                increment_i_by_x: true,
+                ..Quirks::VIP
+            },
+            0x302,
+        ),
+    ] {
+        let mut machine = machine(quirks, &program);
+        machine.run_cycles(2).unwrap();
+        assert_eq!(machine.registers().address(), address, "{quirks:?}");
+    }
+}
+
+#[test]
+fn display_wait_draws_one_sprite_per_frame() {
+    // Draws the glyph 0 twice, then counts in V1
+    let program = [0xD0, 0x05, 0xD0, 0x05, 0x71, 0x01, 0x12, 0x04];
+    let mut machine = machine(Quirks::VIP, &program);
+    assert_eq!(machine.run_frame(10), Ok(Step::WaitingForDisplay));
+    assert_eq!(machine.program_counter(), 0x202);
+    assert_eq!(machine.display().pixel(0, 0), Some(true));
+    assert_eq!(machine.run_frame(10), Ok(Step::WaitingForDisplay));
+    assert_eq!(machine.display().pixel(0, 0), Some(false));
+    assert_eq!(machine.run_frame(10), Ok(Step::Continue));
+    assert_eq!(machine.registers().data()[1], 5);
+
+    // Without the quirk both sprites are drawn in the first frame
+    let quirks = Quirks {
+        display_wait: false,
+        ..Quirks::VIP
+    };
+    let mut machine = self::machine(quirks, &program);
+    assert_eq!(machine.run_frame(10), Ok(Step::Continue));
+    assert_eq!(machine.registers().data()[1], 4);
+}
@@ -0,0 +1,1550 @@
+//! Recompiled from RPS.ch8 by chip8-recompile: 16 routines, 168 blocks and 294 instructions.
+//!
+//! Create a machine running the application with [`machine`], then execute frames with
+//! [`run_frame`] instead of `Machine::run_frame`.
+
+use chip_8::{
+    instruction::Instruction,
+    machine::{Machine, MachineError, Step},
+    memory::MemoryLayout,
+    quirks::Quirks,
+    rom::Rom,
+};
+
+/// The application
+pub const ROM: &[u8] = &[
+    0x17, 0x8D, 0x3C, 0x42, 0xA1, 0x8B, 0x95, 0x8B, 0x56, 0x3C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x78, 0x4C, 0x4E, 0x42, 0x42, 0x42, 0x42, 0x7E, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC3, 0xA5,
+    0x66, 0x3C, 0x18, 0x66, 0xC3, 0x81, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x9F, 0x3F, 0x5F, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xFC, 0xC0, 0xFF, 0xFF, 0xFF, 0x7F, 0x3F, 0x1F, 0x0F, 0x07, 0xFF, 0xFF, 0xFF, 0xFE,
+    0xFC, 0xF8, 0xF0, 0xE0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x3F, 0x03, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xFF, 0xF9, 0xFC, 0xFA, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x03,
+    0x3B, 0xC7, 0x3F, 0xFF, 0x7F, 0xBF, 0x7F, 0x00, 0x00, 0x01, 0x1E, 0x01, 0x00, 0x70, 0xFD, 0xF8,
+    0x88, 0x00, 0xB0, 0xF8, 0x00, 0x00, 0xE0, 0xF3, 0x73, 0x37, 0x77, 0xE7, 0x00, 0x00, 0xCF, 0xEF,
+    0xEE, 0xEE, 0xCE, 0x8F, 0x00, 0x07, 0x8F, 0x0F, 0x0F, 0x1C, 0x1D, 0x1F, 0xFE, 0xFD, 0xFE, 0x00,
+    0x00, 0x80, 0x78, 0x80, 0xFF, 0xFF, 0xFF, 0xC0, 0xDC, 0xE3, 0xFC, 0xFF, 0xFF, 0xFF, 0xFF, 0x1F,
+    0x03, 0xFB, 0x07, 0xFF, 0x1F, 0x0F, 0x01, 0x00, 0x00, 0xFF, 0x80, 0x7F, 0xFC, 0x38, 0xF8, 0xF8,
+    0xE0, 0x01, 0x00, 0x00, 0xC3, 0x00, 0x01, 0x03, 0x03, 0x00, 0x00, 0x02, 0x8F, 0xCE, 0xEE, 0xEE,
+    0xCE, 0x8C, 0x00, 0x40, 0x1F, 0x1F, 0x1D, 0x1C, 0x1C, 0x84, 0x00, 0x00, 0xF8, 0xF0, 0x80, 0x00,
+    0x00, 0xFF, 0x01, 0xFE, 0xFF, 0xFF, 0xFF, 0xF8, 0xC0, 0xDF, 0xE0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xFF, 0xFF, 0x3F, 0x3F, 0x9F, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xE0, 0x1C, 0xE3, 0xFC,
+    0xFF, 0xFF, 0xFF, 0xFF, 0x05, 0x0A, 0x17, 0x2F, 0x5F, 0xBF, 0x7F, 0xFF, 0xA0, 0x50, 0xE8, 0xF4,
+    0xFA, 0xFD, 0xFE, 0xFF, 0x07, 0x38, 0xC7, 0x3F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFC, 0xFC, 0xF9, 0xFE,
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x05, 0x0B, 0x17, 0x2F,
+    0x5F, 0xBF, 0x7F, 0xFF, 0xE0, 0xC0, 0x80, 0x00, 0x00, 0x00, 0x01, 0x00, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xFE, 0xFC, 0x7C, 0xFA, 0x5F, 0x2F, 0x17, 0x0B, 0x05, 0x02, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xF8, 0x04, 0x06, 0x06, 0x06, 0x06, 0x00, 0x00, 0x1F, 0x20,
+    0x20, 0x20, 0x20, 0x20, 0x00, 0x80, 0x40, 0xA0, 0xD0, 0xF0, 0xE0, 0xC0, 0xFD, 0xFE, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x07, 0x05, 0x05, 0x05, 0x05, 0xFF, 0xFF, 0xFF, 0xE0,
+    0xE0, 0xE0, 0xE0, 0xE0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0xFC, 0x02, 0x06, 0x06, 0x06, 0x06, 0x06, 0xCE, 0xEC, 0x1D, 0x20, 0x20, 0x20, 0x20,
+    0x20, 0x1F, 0x0F, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0xFF, 0xFF, 0xFE, 0xFC,
+    0xFC, 0xFA, 0xFD, 0xFE, 0x05, 0x05, 0x05, 0x05, 0x05, 0xFD, 0x03, 0xFF, 0xE0, 0xE0, 0xE0, 0xE0,
+    0xE0, 0x73, 0x36, 0xBD, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x80, 0x3F, 0x40, 0x4A, 0x4A, 0x02, 0x02,
+    0x7A, 0x02, 0xFC, 0x00, 0x09, 0x01, 0x01, 0x01, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x40, 0xA0, 0xD0, 0xF0, 0xE0, 0xC0, 0x80, 0x00, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xBF, 0xBF, 0xBF, 0xBF,
+    0xBF, 0xBF, 0x3F, 0x7F, 0x7F, 0x7F, 0x40, 0x40, 0x5E, 0x40, 0x3F, 0x80, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xFF, 0xFF, 0x4F, 0x87, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE, 0xFF, 0x71, 0x75, 0x71,
+    0x17, 0xFF, 0xE9, 0xD0, 0xFF, 0x51, 0x17, 0x53, 0x51, 0xFF, 0xFF, 0x3F, 0xFF, 0x1F, 0x3F, 0xDF,
+    0x1F, 0xFF, 0xFE, 0xFD, 0xFF, 0x11, 0x55, 0x31, 0x57, 0xFF, 0xDF, 0xA7, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFB, 0xF4, 0x03, 0x01, 0x00, 0x00,
+    0x00, 0x00, 0x80, 0x00, 0x7D, 0x3A, 0x14, 0x08, 0x00, 0x00, 0xBB, 0xAA, 0xA0, 0x40, 0x80, 0x00,
+    0x00, 0x00, 0xBB, 0x22, 0x1F, 0x0F, 0x06, 0x03, 0x00, 0x00, 0xBB, 0x12, 0xFA, 0xF4, 0xE8, 0x50,
+    0x20, 0x00, 0x3B, 0xA2, 0x43, 0x81, 0x00, 0x00, 0x00, 0x00, 0xD5, 0x18, 0x7F, 0x3E, 0x1D, 0x0A,
+    0x04, 0x00, 0xDD, 0x55, 0xE8, 0xD0, 0xA0, 0x40, 0x80, 0x00, 0x01, 0x01, 0x80, 0x80, 0x00, 0x00,
+    0xE0, 0xA0, 0xC0, 0xA0, 0xB1, 0xAB, 0x00, 0x00, 0xEE, 0xA8, 0xEC, 0x8E, 0x9A, 0xBB, 0x00, 0x00,
+    0xEE, 0xAA, 0xEE, 0x8A, 0x11, 0xBB, 0x00, 0x00, 0x74, 0x42, 0x34, 0x70, 0x1A, 0x3B, 0x00, 0x00,
+    0x77, 0x55, 0x56, 0x75, 0x15, 0xD4, 0x00, 0x00, 0x77, 0x44, 0x33, 0x77, 0x95, 0x5D, 0x00, 0x00,
+    0x77, 0x42, 0x42, 0x77, 0x01, 0x01, 0x00, 0x00, 0x07, 0x04, 0x03, 0x07, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0xFF, 0x00, 0x00, 0xA0, 0xC0, 0xA0, 0xA0, 0x00, 0xFF, 0x00, 0x00, 0xEE, 0xA8,
+    0xA8, 0xEE, 0x00, 0xFF, 0x00, 0x00, 0x4E, 0x2A, 0x4C, 0x0A, 0x00, 0xFF, 0x00, 0x00, 0x77, 0x45,
+    0x66, 0x75, 0x00, 0xFF, 0x00, 0x00, 0x77, 0x55, 0x77, 0x54, 0x00, 0xFF, 0x00, 0x00, 0x07, 0x05,
+    0x07, 0x04, 0x00, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xFF, 0xFF, 0x4F, 0x87, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE, 0xFF, 0x71, 0x75, 0x71,
+    0x17, 0xFF, 0xE9, 0xD0, 0xFF, 0x51, 0x17, 0x53, 0x51, 0xFF, 0xFF, 0x3F, 0xFF, 0x1F, 0x3F, 0xDF,
+    0x1F, 0xFF, 0xFE, 0xFD, 0xFF, 0x11, 0x55, 0x31, 0x57, 0xFF, 0xDF, 0xA7, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFB, 0xF4, 0x03, 0x01, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x7D, 0x3A, 0x14, 0x08, 0x30, 0x50, 0x60, 0xC0, 0xA0, 0x40, 0x80, 0x00,
+    0x0C, 0x0A, 0x06, 0x03, 0x1F, 0x0F, 0x06, 0x03, 0x80, 0xC0, 0xE0, 0x20, 0xFA, 0xF4, 0xE8, 0x50,
+    0x27, 0x04, 0x04, 0x04, 0x43, 0x81, 0x00, 0x00, 0x00, 0xC0, 0x20, 0x10, 0x7F, 0x3E, 0x1D, 0x0A,
+    0x04, 0x03, 0x04, 0x0A, 0xE8, 0xD0, 0xA0, 0x40, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x80, 0x60, 0x30, 0x10, 0x00, 0x00, 0xF0, 0x10, 0x01, 0x06, 0x0C, 0x08,
+    0x00, 0x00, 0x0F, 0x08, 0x20, 0x20, 0x20, 0xE0, 0x00, 0x00, 0xF0, 0x10, 0x04, 0x04, 0x04, 0x07,
+    0x00, 0x00, 0x0F, 0x08, 0xB0, 0x50, 0xB0, 0x60, 0xC0, 0x00, 0xF0, 0x10, 0x08, 0x09, 0x08, 0x05,
+    0x03, 0x00, 0x0F, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0xFF, 0xD0, 0x10, 0x10, 0xD0, 0x10, 0xF0, 0x00, 0xFF, 0x0B, 0x0A, 0x0A, 0x0B,
+    0x08, 0x0F, 0x00, 0xFF, 0x50, 0x90, 0x90, 0x50, 0x10, 0xF0, 0x00, 0xFF, 0x0A, 0x09, 0x09, 0x0A,
+    0x08, 0x0F, 0x00, 0xFF, 0xD0, 0x90, 0x10, 0xD0, 0x10, 0xF0, 0x00, 0xFF, 0x0B, 0x08, 0x09, 0x0B,
+    0x08, 0x0F, 0x00, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0x00, 0xDE, 0x5C, 0xD0,
+    0x9E, 0x00, 0x00, 0x00, 0x00, 0x7B, 0x4A, 0x4B, 0x7A, 0x00, 0x00, 0xE8, 0x00, 0xEF, 0x08, 0xE8,
+    0xEF, 0x00, 0x00, 0xBD, 0x00, 0x79, 0x49, 0x79, 0x48, 0x00, 0x00, 0xF7, 0x00, 0xDE, 0x48, 0x48,
+    0xC8, 0x00, 0x00, 0xBC, 0x00, 0x7B, 0x42, 0x4A, 0x7B, 0x00, 0x00, 0x97, 0x00, 0xD2, 0x52, 0x52,
+    0xDE, 0x00, 0x00, 0x1E, 0x00, 0x4B, 0x4A, 0x7A, 0x23, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x28, 0x20, 0xE8, 0x00, 0x00, 0xBC, 0xA4, 0xBC, 0xA5, 0xA5, 0xBD, 0x00,
+    0x00, 0xF7, 0x84, 0xE4, 0x24, 0x24, 0xF7, 0x00, 0x00, 0xBC, 0x20, 0x3C, 0x24, 0x3C, 0xA8, 0x00,
+    0x00, 0xD5, 0xB6, 0x95, 0x97, 0x94, 0x67, 0x00, 0x00, 0x5E, 0x52, 0xDE, 0x12, 0x12, 0x1E, 0x00,
+    0x00, 0x7A, 0x22, 0x23, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0xA8, 0x00, 0x00, 0x40, 0x40, 0x00, 0x40, 0x00, 0x87, 0x00, 0x00, 0xAF,
+    0x68, 0x29, 0x2F, 0x00, 0x9C, 0x00, 0x00, 0xBD, 0x91, 0x91, 0x3D, 0x00, 0x94, 0x00, 0x00, 0xF4,
+    0x94, 0xF7, 0x92, 0x00, 0x52, 0x00, 0x00, 0xD0, 0x50, 0xD0, 0x1E, 0x00, 0x22, 0x00, 0x00, 0x03,
+    0x02, 0x03, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x7A, 0x72, 0x40, 0x7A, 0x00, 0x00, 0x00, 0x00, 0xEE, 0x89, 0x89,
+    0xEE, 0x00, 0x00, 0x00, 0x00, 0xBD, 0x20, 0x3C, 0x1D, 0x00, 0x00, 0x00, 0x00, 0x97, 0x92, 0x92,
+    0xF2, 0x00, 0x00, 0x00, 0x00, 0x9E, 0x92, 0x92, 0x9E, 0x00, 0x00, 0x00, 0x00, 0xF7, 0x84, 0x94,
+    0xF7, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x8A, 0x8A, 0xAA, 0xAA,
+    0xDA, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x88, 0xC8, 0xA8, 0x98, 0x88,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x8E, 0x8A, 0x8A, 0x8A, 0xEE, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xEE, 0x88, 0xEC, 0x28, 0xEE, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xEB, 0x4A, 0x4B, 0x4A, 0x4B, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x60, 0x60, 0x90, 0x90, 0x48, 0x84, 0x90, 0xCD, 0xFF, 0x4A,
+    0x00, 0x27, 0xAB, 0x4A, 0x01, 0x27, 0xCD, 0x4A, 0x02, 0x27, 0xEF, 0x4A, 0x03, 0x28, 0x33, 0x4A,
+    0x04, 0x28, 0x57, 0x4A, 0x05, 0x28, 0x11, 0x17, 0x8D, 0x00, 0xEE, 0xA2, 0x2C, 0x28, 0x3D, 0xF6,
+    0x0A, 0x6E, 0x00, 0x8E, 0x67, 0x4F, 0x01, 0x6A, 0x01, 0x6E, 0x00, 0x8E, 0x67, 0x4F, 0x01, 0x00,
+    0xE0, 0x6E, 0x00, 0x8E, 0x67, 0x4F, 0x01, 0x00, 0xEE, 0x17, 0xAF, 0x00, 0xEE, 0xA4, 0x2C, 0x28,
+    0x3D, 0xF6, 0x0A, 0x6E, 0x00, 0x8E, 0x67, 0x4F, 0x01, 0x6A, 0x02, 0x6E, 0x00, 0x8E, 0x67, 0x4F,
+    0x01, 0x00, 0xE0, 0x6E, 0x00, 0x8E, 0x67, 0x4F, 0x01, 0x00, 0xEE, 0x17, 0xD1, 0x00, 0xEE, 0xA5,
+    0x2C, 0x28, 0x3D, 0xF6, 0x0A, 0x6E, 0x00, 0x8E, 0x67, 0x4F, 0x01, 0x6A, 0x03, 0x6E, 0x00, 0x8E,
+    0x67, 0x4F, 0x01, 0x00, 0xE0, 0x6E, 0x00, 0x8E, 0x67, 0x4F, 0x01, 0x00, 0xEE, 0x17, 0xF3, 0x00,
+    0xEE, 0xA6, 0x2C, 0x28, 0x3D, 0xF6, 0x0A, 0x6E, 0x00, 0x8E, 0x67, 0x4F, 0x01, 0x6A, 0x05, 0x6E,
+    0x00, 0x8E, 0x67, 0x4F, 0x01, 0x00, 0xE0, 0x6E, 0x00, 0x8E, 0x67, 0x4F, 0x01, 0x00, 0xEE, 0x18,
+    0x15, 0x00, 0xEE, 0xA3, 0x2C, 0x28, 0x3D, 0x6A, 0x04, 0x29, 0xA5, 0x00, 0xEE, 0x69, 0x38, 0x68,
+    0x00, 0x67, 0x08, 0xD9, 0x88, 0xF7, 0x1E, 0x79, 0xF8, 0x49, 0xF8, 0x78, 0x08, 0x49, 0xF8, 0x69,
+    0x38, 0x38, 0x20, 0x18, 0x43, 0x00, 0xEE, 0x6E, 0x00, 0x8E, 0xD7, 0x4F, 0x01, 0x6C, 0x01, 0x6E,
+    0x56, 0x8E, 0xD7, 0x4F, 0x01, 0x6C, 0x02, 0x6E, 0xAB, 0x8E, 0xD7, 0x4F, 0x01, 0x6C, 0x03, 0x6B,
+    0x00, 0x67, 0x00, 0xF6, 0x0A, 0x46, 0x0A, 0x6B, 0x01, 0x46, 0x00, 0x6B, 0x02, 0x46, 0x0B, 0x6B,
+    0x03, 0x4B, 0x00, 0x18, 0x73, 0x28, 0xA7, 0x28, 0xC7, 0x28, 0xE7, 0x29, 0x67, 0x35, 0x01, 0x18,
+    0x97, 0x29, 0xA5, 0x70, 0x01, 0x29, 0xA5, 0x77, 0x01, 0x37, 0xA0, 0x18, 0x97, 0x28, 0xA7, 0x28,
+    0xC7, 0x28, 0xE7, 0x29, 0x67, 0x00, 0xEE, 0x6E, 0x01, 0x8E, 0xB7, 0x4F, 0x01, 0xA2, 0x02, 0x6E,
+    0x02, 0x8E, 0xB7, 0x4F, 0x01, 0xA2, 0x10, 0x6E, 0x03, 0x8E, 0xB7, 0x4F, 0x01, 0xA2, 0x1E, 0x69,
+    0x2C, 0x68, 0x0C, 0xD9, 0x88, 0x00, 0xEE, 0x6E, 0x01, 0x8E, 0xC7, 0x4F, 0x01, 0xA2, 0x02, 0x6E,
+    0x02, 0x8E, 0xC7, 0x4F, 0x01, 0xA2, 0x10, 0x6E, 0x03, 0x8E, 0xC7, 0x4F, 0x01, 0xA2, 0x1E, 0x69,
+    0x0C, 0x68, 0x0C, 0xD9, 0x88, 0x00, 0xEE, 0x3B, 0x01, 0x18, 0xFD, 0x3C, 0x01, 0x18, 0xF1, 0x29,
+    0x53, 0x3C, 0x02, 0x18, 0xF7, 0x29, 0x2B, 0x3C, 0x03, 0x18, 0xFD, 0x29, 0x3F, 0x3B, 0x02, 0x19,
+    0x13, 0x3C, 0x02, 0x19, 0x07, 0x29, 0x53, 0x3C, 0x03, 0x19, 0x0D, 0x29, 0x2B, 0x3C, 0x01, 0x19,
+    0x13, 0x29, 0x3F, 0x3B, 0x03, 0x19, 0x29, 0x3C, 0x03, 0x19, 0x1D, 0x29, 0x53, 0x3C, 0x01, 0x19,
+    0x23, 0x29, 0x2B, 0x3C, 0x02, 0x19, 0x29, 0x29, 0x3F, 0x00, 0xEE, 0xA7, 0x4A, 0x69, 0x01, 0x68,
+    0x01, 0xD9, 0x85, 0xA7, 0x59, 0x69, 0x09, 0x68, 0x01, 0xD9, 0x85, 0x65, 0x00, 0x00, 0xEE, 0xA7,
+    0x2C, 0x69, 0x01, 0x68, 0x01, 0xD9, 0x85, 0xA7, 0x3B, 0x69, 0x09, 0x68, 0x01, 0xD9, 0x85, 0x65,
+    0x01, 0x00, 0xEE, 0xA7, 0x68, 0x69, 0x01, 0x68, 0x01, 0xD9, 0x85, 0xA7, 0x77, 0x69, 0x09, 0x68,
+    0x01, 0xD9, 0x85, 0x65, 0x02, 0x00, 0xEE, 0x35, 0x00, 0x19, 0x7B, 0xA7, 0x86, 0x69, 0x39, 0x68,
+    0x1B, 0xD9, 0x82, 0xA7, 0x88, 0x69, 0x03, 0x68, 0x1B, 0xD9, 0x82, 0x35, 0x01, 0x19, 0x8F, 0xA7,
+    0x86, 0x69, 0x03, 0x68, 0x1B, 0xD9, 0x82, 0xA7, 0x88, 0x69, 0x39, 0x68, 0x1B, 0xD9, 0x82, 0x35,
+    0x02, 0x19, 0xA3, 0xA7, 0x8A, 0x69, 0x38, 0x68, 0x18, 0xD9, 0x82, 0xA7, 0x88, 0x69, 0x03, 0x68,
+    0x1C, 0xD9, 0x81, 0x00, 0xEE, 0x30, 0x0A, 0x19, 0xAD, 0x71, 0x01, 0x60, 0x00, 0x31, 0x0A, 0x19,
+    0xB5, 0x72, 0x01, 0x61, 0x00, 0x32, 0x0A, 0x19, 0xC7, 0x62, 0x00, 0x61, 0x00, 0x60, 0x00, 0x00,
+    0xE0, 0x6A, 0x05, 0x27, 0x8D, 0x00, 0xE0, 0x69, 0x3A, 0x68, 0x01, 0xF0, 0x29, 0xD9, 0x85, 0x69,
+    0x35, 0x68, 0x01, 0xF1, 0x29, 0xD9, 0x85, 0x69, 0x30, 0x68, 0x01, 0xF2, 0x29, 0xD9, 0x85, 0x00,
+    0xEE,
+];
+
+/// The memory map the application was recompiled for
+pub const LAYOUT: MemoryLayout = MemoryLayout {
+    program_start: 0x200,
+    small_font: 0x000,
+    large_font: 0x050,
+    size: 0x1000,
+    writable: 0x200..0x1000,
+};
+
+/// The quirks the application was recompiled for
+pub const QUIRKS: Quirks = Quirks {
+    vf_reset: false,
+    load_store_increments_i: false,
+    shift_uses_vy: false,
+    jump_uses_vx: true,
+    clip_sprites: true,
+    increment_i_by_x: false,
+    display_wait: false,
+};
+
+/// Creates a machine with the memory map and quirks, running the application
+pub fn machine() -> Machine {
+    let mut machine = Machine::with_layout(QUIRKS, LAYOUT).expect("The memory map is valid");
+    machine
+        .load_rom(&Rom::from_bytes(ROM).expect("The application isn't empty"))
+        .expect("The application fits in the memory map");
+    machine.set_quirks(QUIRKS);
+    machine
+}
+
+/// Executes up to the given number of instructions, then updates the timers, exactly like
+/// `Machine::run_frame`. Falls back to the interpreter if the quirks were changed.
+pub fn run_frame(machine: &mut Machine, cycles: u32) -> Result<Step, MachineError> {
+    if machine.quirks() != QUIRKS {
+        return machine.run_frame(cycles);
+    }
+    let step = run(machine, cycles)?;
+    machine.registers_mut().cycle();
+    Ok(step)
+}
+
+/// Executes up to the given number of instructions, natively where possible
+fn run(machine: &mut Machine, cycles: u32) -> Result<Step, MachineError> {
+    let mut budget = cycles;
+    while budget > 0 {
+        let remaining = budget;
+        let mut step = match machine.program_counter() {
+            0x200 => routine_200(machine, &mut budget)?,
+            0x78D => routine_200(machine, &mut budget)?,
+            0x791 => routine_200(machine, &mut budget)?,
+            0x793 => routine_200(machine, &mut budget)?,
+            0x795 => routine_200(machine, &mut budget)?,
+            0x797 => routine_200(machine, &mut budget)?,
+            0x799 => routine_200(machine, &mut budget)?,
+            0x79B => routine_200(machine, &mut budget)?,
+            0x79D => routine_200(machine, &mut budget)?,
+            0x79F => routine_200(machine, &mut budget)?,
+            0x7A1 => routine_200(machine, &mut budget)?,
+            0x7A3 => routine_200(machine, &mut budget)?,
+            0x7A5 => routine_200(machine, &mut budget)?,
+            0x7A7 => routine_200(machine, &mut budget)?,
+            0x7AB => routine_7ab(machine, &mut budget)?,
+            0x7AF => routine_7ab(machine, &mut budget)?,
+            0x7B1 => routine_7ab(machine, &mut budget)?,
+            0x7B7 => routine_7ab(machine, &mut budget)?,
+            0x7B9 => routine_7ab(machine, &mut budget)?,
+            0x7BF => routine_7ab(machine, &mut budget)?,
+            0x7C1 => routine_7ab(machine, &mut budget)?,
+            0x7C7 => routine_7ab(machine, &mut budget)?,
+            0x7C9 => routine_7ab(machine, &mut budget)?,
+            0x7CD => routine_7cd(machine, &mut budget)?,
+            0x7D1 => routine_7cd(machine, &mut budget)?,
+            0x7D3 => routine_7cd(machine, &mut budget)?,
+            0x7D9 => routine_7cd(machine, &mut budget)?,
+            0x7DB => routine_7cd(machine, &mut budget)?,
+            0x7E1 => routine_7cd(machine, &mut budget)?,
+            0x7E3 => routine_7cd(machine, &mut budget)?,
+            0x7E9 => routine_7cd(machine, &mut budget)?,
+            0x7EB => routine_7cd(machine, &mut budget)?,
+            0x7EF => routine_7ef(machine, &mut budget)?,
+            0x7F3 => routine_7ef(machine, &mut budget)?,
+            0x7F5 => routine_7ef(machine, &mut budget)?,
+            0x7FB => routine_7ef(machine, &mut budget)?,
+            0x7FD => routine_7ef(machine, &mut budget)?,
+            0x803 => routine_7ef(machine, &mut budget)?,
+            0x805 => routine_7ef(machine, &mut budget)?,
+            0x80B => routine_7ef(machine, &mut budget)?,
+            0x80D => routine_7ef(machine, &mut budget)?,
+            0x811 => routine_811(machine, &mut budget)?,
+            0x815 => routine_811(machine, &mut budget)?,
+            0x817 => routine_811(machine, &mut budget)?,
+            0x81D => routine_811(machine, &mut budget)?,
+            0x81F => routine_811(machine, &mut budget)?,
+            0x825 => routine_811(machine, &mut budget)?,
+            0x827 => routine_811(machine, &mut budget)?,
+            0x82D => routine_811(machine, &mut budget)?,
+            0x82F => routine_811(machine, &mut budget)?,
+            0x833 => routine_833(machine, &mut budget)?,
+            0x837 => routine_833(machine, &mut budget)?,
+            0x83B => routine_833(machine, &mut budget)?,
+            0x83D => routine_83d(machine, &mut budget)?,
+            0x843 => routine_83d(machine, &mut budget)?,
+            0x84B => routine_83d(machine, &mut budget)?,
+            0x84D => routine_83d(machine, &mut budget)?,
+            0x84F => routine_83d(machine, &mut budget)?,
+            0x851 => routine_83d(machine, &mut budget)?,
+            0x853 => routine_83d(machine, &mut budget)?,
+            0x855 => routine_83d(machine, &mut budget)?,
+            0x857 => routine_857(machine, &mut budget)?,
+            0x85D => routine_857(machine, &mut budget)?,
+            0x85F => routine_857(machine, &mut budget)?,
+            0x865 => routine_857(machine, &mut budget)?,
+            0x867 => routine_857(machine, &mut budget)?,
+            0x86D => routine_857(machine, &mut budget)?,
+            0x86F => routine_857(machine, &mut budget)?,
+            0x873 => routine_857(machine, &mut budget)?,
+            0x875 => routine_857(machine, &mut budget)?,
+            0x877 => routine_857(machine, &mut budget)?,
+            0x879 => routine_857(machine, &mut budget)?,
+            0x87B => routine_857(machine, &mut budget)?,
+            0x87D => routine_857(machine, &mut budget)?,
+            0x87F => routine_857(machine, &mut budget)?,
+            0x881 => routine_857(machine, &mut budget)?,
+            0x883 => routine_857(machine, &mut budget)?,
+            0x885 => routine_857(machine, &mut budget)?,
+            0x887 => routine_857(machine, &mut budget)?,
+            0x889 => routine_857(machine, &mut budget)?,
+            0x88B => routine_857(machine, &mut budget)?,
+            0x88D => routine_857(machine, &mut budget)?,
+            0x88F => routine_857(machine, &mut budget)?,
+            0x891 => routine_857(machine, &mut budget)?,
+            0x893 => routine_857(machine, &mut budget)?,
+            0x897 => routine_857(machine, &mut budget)?,
+            0x89B => routine_857(machine, &mut budget)?,
+            0x89D => routine_857(machine, &mut budget)?,
+            0x89F => routine_857(machine, &mut budget)?,
+            0x8A1 => routine_857(machine, &mut budget)?,
+            0x8A3 => routine_857(machine, &mut budget)?,
+            0x8A5 => routine_857(machine, &mut budget)?,
+            0x8A7 => routine_8a7(machine, &mut budget)?,
+            0x8AD => routine_8a7(machine, &mut budget)?,
+            0x8AF => routine_8a7(machine, &mut budget)?,
+            0x8B5 => routine_8a7(machine, &mut budget)?,
+            0x8B7 => routine_8a7(machine, &mut budget)?,
+            0x8BD => routine_8a7(machine, &mut budget)?,
+            0x8BF => routine_8a7(machine, &mut budget)?,
+            0x8C7 => routine_8c7(machine, &mut budget)?,
+            0x8CD => routine_8c7(machine, &mut budget)?,
+            0x8CF => routine_8c7(machine, &mut budget)?,
+            0x8D5 => routine_8c7(machine, &mut budget)?,
+            0x8D7 => routine_8c7(machine, &mut budget)?,
+            0x8DD => routine_8c7(machine, &mut budget)?,
+            0x8DF => routine_8c7(machine, &mut budget)?,
+            0x8E7 => routine_8e7(machine, &mut budget)?,
+            0x8E9 => routine_8e7(machine, &mut budget)?,
+            0x8EB => routine_8e7(machine, &mut budget)?,
+            0x8ED => routine_8e7(machine, &mut budget)?,
+            0x8EF => routine_8e7(machine, &mut budget)?,
+            0x8F1 => routine_8e7(machine, &mut budget)?,
+            0x8F3 => routine_8e7(machine, &mut budget)?,
+            0x8F5 => routine_8e7(machine, &mut budget)?,
+            0x8F7 => routine_8e7(machine, &mut budget)?,
+            0x8F9 => routine_8e7(machine, &mut budget)?,
+            0x8FB => routine_8e7(machine, &mut budget)?,
+            0x8FD => routine_8e7(machine, &mut budget)?,
+            0x8FF => routine_8e7(machine, &mut budget)?,
+            0x901 => routine_8e7(machine, &mut budget)?,
+            0x903 => routine_8e7(machine, &mut budget)?,
+            0x905 => routine_8e7(machine, &mut budget)?,
+            0x907 => routine_8e7(machine, &mut budget)?,
+            0x909 => routine_8e7(machine, &mut budget)?,
+            0x90B => routine_8e7(machine, &mut budget)?,
+            0x90D => routine_8e7(machine, &mut budget)?,
+            0x90F => routine_8e7(machine, &mut budget)?,
+            0x911 => routine_8e7(machine, &mut budget)?,
+            0x913 => routine_8e7(machine, &mut budget)?,
+            0x915 => routine_8e7(machine, &mut budget)?,
+            0x917 => routine_8e7(machine, &mut budget)?,
+            0x919 => routine_8e7(machine, &mut budget)?,
+            0x91B => routine_8e7(machine, &mut budget)?,
+            0x91D => routine_8e7(machine, &mut budget)?,
+            0x91F => routine_8e7(machine, &mut budget)?,
+            0x921 => routine_8e7(machine, &mut budget)?,
+            0x923 => routine_8e7(machine, &mut budget)?,
+            0x925 => routine_8e7(machine, &mut budget)?,
+            0x927 => routine_8e7(machine, &mut budget)?,
+            0x929 => routine_8e7(machine, &mut budget)?,
+            0x92B => routine_92b(machine, &mut budget)?,
+            0x93B => routine_92b(machine, &mut budget)?,
+            0x93F => routine_93f(machine, &mut budget)?,
+            0x94F => routine_93f(machine, &mut budget)?,
+            0x953 => routine_953(machine, &mut budget)?,
+            0x963 => routine_953(machine, &mut budget)?,
+            0x967 => routine_967(machine, &mut budget)?,
+            0x969 => routine_967(machine, &mut budget)?,
+            0x96B => routine_967(machine, &mut budget)?,
+            0x97B => routine_967(machine, &mut budget)?,
+            0x97D => routine_967(machine, &mut budget)?,
+            0x97F => routine_967(machine, &mut budget)?,
+            0x98F => routine_967(machine, &mut budget)?,
+            0x991 => routine_967(machine, &mut budget)?,
+            0x993 => routine_967(machine, &mut budget)?,
+            0x9A3 => routine_967(machine, &mut budget)?,
+            0x9A5 => routine_9a5(machine, &mut budget)?,
+            0x9A7 => routine_9a5(machine, &mut budget)?,
+            0x9A9 => routine_9a5(machine, &mut budget)?,
+            0x9AD => routine_9a5(machine, &mut budget)?,
+            0x9AF => routine_9a5(machine, &mut budget)?,
+            0x9B1 => routine_9a5(machine, &mut budget)?,
+            0x9B5 => routine_9a5(machine, &mut budget)?,
+            0x9B7 => routine_9a5(machine, &mut budget)?,
+            0x9B9 => routine_9a5(machine, &mut budget)?,
+            0x9C5 => routine_9a5(machine, &mut budget)?,
+            0x9C7 => routine_9a5(machine, &mut budget)?,
+            0x9D7 => routine_9a5(machine, &mut budget)?,
+            _ => Step::Continue,
+        };
+        if budget == remaining {
+            budget -= 1;
+            step = machine.step()?;
+        }
+        if step != Step::Continue {
+            return Ok(step);
+        }
+    }
+    Ok(Step::Continue)
+}
+
+/// Retrieves whether the block with the number of instructions at the address is unchanged
+/// and fits in the remaining cycles
+fn ready(machine: &Machine, budget: u32, address: usize, count: u32) -> bool {
+    let offset = address - usize::from(LAYOUT.program_start);
+    let length = 2 * count as usize;
+    budget >= count
+        && machine.memory().data().get(address..address + length)
+            == ROM.get(offset..offset + length)
+}
+
+/// The routine at 0x200
+fn routine_200(machine: &mut Machine, budget: &mut u32) -> Result<Step, MachineError> {
+    loop {
+        match machine.program_counter() {
+            0x200 if ready(machine, *budget, 0x200, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x78D);
+            }
+            0x78D if ready(machine, *budget, 0x78D, 2) => {
+                *budget -= 2;
+                machine.execute_at(0x78D, Instruction::RandRange(0xD, 0xFF))?;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0xA] != 0x00;
+                machine.set_program_counter(if skip { 0x793 } else { 0x791 });
+            }
+            0x791 if ready(machine, *budget, 0x791, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x791, Instruction::CallAddress(0x7AB))?;
+            }
+            0x793 if ready(machine, *budget, 0x793, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0xA] != 0x01;
+                machine.set_program_counter(if skip { 0x797 } else { 0x795 });
+            }
+            0x795 if ready(machine, *budget, 0x795, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x795, Instruction::CallAddress(0x7CD))?;
+            }
+            0x797 if ready(machine, *budget, 0x797, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0xA] != 0x02;
+                machine.set_program_counter(if skip { 0x79B } else { 0x799 });
+            }
+            0x799 if ready(machine, *budget, 0x799, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x799, Instruction::CallAddress(0x7EF))?;
+            }
+            0x79B if ready(machine, *budget, 0x79B, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0xA] != 0x03;
+                machine.set_program_counter(if skip { 0x79F } else { 0x79D });
+            }
+            0x79D if ready(machine, *budget, 0x79D, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x79D, Instruction::CallAddress(0x833))?;
+            }
+            0x79F if ready(machine, *budget, 0x79F, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0xA] != 0x04;
+                machine.set_program_counter(if skip { 0x7A3 } else { 0x7A1 });
+            }
+            0x7A1 if ready(machine, *budget, 0x7A1, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x7A1, Instruction::CallAddress(0x857))?;
+            }
+            0x7A3 if ready(machine, *budget, 0x7A3, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0xA] != 0x05;
+                machine.set_program_counter(if skip { 0x7A7 } else { 0x7A5 });
+            }
+            0x7A5 if ready(machine, *budget, 0x7A5, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x7A5, Instruction::CallAddress(0x811))?;
+            }
+            0x7A7 if ready(machine, *budget, 0x7A7, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x78D);
+            }
+            _ => return Ok(Step::Continue),
+        }
+    }
+}
+
+/// The routine at 0x7AB
+fn routine_7ab(machine: &mut Machine, budget: &mut u32) -> Result<Step, MachineError> {
+    loop {
+        match machine.program_counter() {
+            0x7AB if ready(machine, *budget, 0x7AB, 2) => {
+                *budget -= 2;
+                *machine.registers_mut().address_mut() = 0x22C;
+                machine.execute_at(0x7AD, Instruction::CallAddress(0x83D))?;
+            }
+            0x7AF if ready(machine, *budget, 0x7AF, 1) => {
+                *budget -= 1;
+                let step = machine.execute_at(0x7AF, Instruction::LoadKeyPress(0x6))?;
+                if step != Step::Continue {
+                    return Ok(step);
+                }
+            }
+            0x7B1 if ready(machine, *budget, 0x7B1, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x00;
+                let (result, flag) = v[0x6].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x7B9 } else { 0x7B7 });
+            }
+            0x7B7 if ready(machine, *budget, 0x7B7, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                v[0xA] = 0x01;
+                machine.set_program_counter(0x7B9);
+            }
+            0x7B9 if ready(machine, *budget, 0x7B9, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x00;
+                let (result, flag) = v[0x6].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x7C1 } else { 0x7BF });
+            }
+            0x7BF if ready(machine, *budget, 0x7BF, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x7BF, Instruction::ClearScreen)?;
+            }
+            0x7C1 if ready(machine, *budget, 0x7C1, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x00;
+                let (result, flag) = v[0x6].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x7C9 } else { 0x7C7 });
+            }
+            0x7C7 if ready(machine, *budget, 0x7C7, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x7C7, Instruction::Return)?;
+            }
+            0x7C9 if ready(machine, *budget, 0x7C9, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x7AF);
+            }
+            _ => return Ok(Step::Continue),
+        }
+    }
+}
+
+/// The routine at 0x7CD
+fn routine_7cd(machine: &mut Machine, budget: &mut u32) -> Result<Step, MachineError> {
+    loop {
+        match machine.program_counter() {
+            0x7CD if ready(machine, *budget, 0x7CD, 2) => {
+                *budget -= 2;
+                *machine.registers_mut().address_mut() = 0x42C;
+                machine.execute_at(0x7CF, Instruction::CallAddress(0x83D))?;
+            }
+            0x7D1 if ready(machine, *budget, 0x7D1, 1) => {
+                *budget -= 1;
+                let step = machine.execute_at(0x7D1, Instruction::LoadKeyPress(0x6))?;
+                if step != Step::Continue {
+                    return Ok(step);
+                }
+            }
+            0x7D3 if ready(machine, *budget, 0x7D3, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x00;
+                let (result, flag) = v[0x6].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x7DB } else { 0x7D9 });
+            }
+            0x7D9 if ready(machine, *budget, 0x7D9, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                v[0xA] = 0x02;
+                machine.set_program_counter(0x7DB);
+            }
+            0x7DB if ready(machine, *budget, 0x7DB, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x00;
+                let (result, flag) = v[0x6].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x7E3 } else { 0x7E1 });
+            }
+            0x7E1 if ready(machine, *budget, 0x7E1, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x7E1, Instruction::ClearScreen)?;
+            }
+            0x7E3 if ready(machine, *budget, 0x7E3, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x00;
+                let (result, flag) = v[0x6].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x7EB } else { 0x7E9 });
+            }
+            0x7E9 if ready(machine, *budget, 0x7E9, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x7E9, Instruction::Return)?;
+            }
+            0x7EB if ready(machine, *budget, 0x7EB, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x7D1);
+            }
+            _ => return Ok(Step::Continue),
+        }
+    }
+}
+
+/// The routine at 0x7EF
+fn routine_7ef(machine: &mut Machine, budget: &mut u32) -> Result<Step, MachineError> {
+    loop {
+        match machine.program_counter() {
+            0x7EF if ready(machine, *budget, 0x7EF, 2) => {
+                *budget -= 2;
+                *machine.registers_mut().address_mut() = 0x52C;
+                machine.execute_at(0x7F1, Instruction::CallAddress(0x83D))?;
+            }
+            0x7F3 if ready(machine, *budget, 0x7F3, 1) => {
+                *budget -= 1;
+                let step = machine.execute_at(0x7F3, Instruction::LoadKeyPress(0x6))?;
+                if step != Step::Continue {
+                    return Ok(step);
+                }
+            }
+            0x7F5 if ready(machine, *budget, 0x7F5, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x00;
+                let (result, flag) = v[0x6].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x7FD } else { 0x7FB });
+            }
+            0x7FB if ready(machine, *budget, 0x7FB, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                v[0xA] = 0x03;
+                machine.set_program_counter(0x7FD);
+            }
+            0x7FD if ready(machine, *budget, 0x7FD, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x00;
+                let (result, flag) = v[0x6].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x805 } else { 0x803 });
+            }
+            0x803 if ready(machine, *budget, 0x803, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x803, Instruction::ClearScreen)?;
+            }
+            0x805 if ready(machine, *budget, 0x805, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x00;
+                let (result, flag) = v[0x6].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x80D } else { 0x80B });
+            }
+            0x80B if ready(machine, *budget, 0x80B, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x80B, Instruction::Return)?;
+            }
+            0x80D if ready(machine, *budget, 0x80D, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x7F3);
+            }
+            _ => return Ok(Step::Continue),
+        }
+    }
+}
+
+/// The routine at 0x811
+fn routine_811(machine: &mut Machine, budget: &mut u32) -> Result<Step, MachineError> {
+    loop {
+        match machine.program_counter() {
+            0x811 if ready(machine, *budget, 0x811, 2) => {
+                *budget -= 2;
+                *machine.registers_mut().address_mut() = 0x62C;
+                machine.execute_at(0x813, Instruction::CallAddress(0x83D))?;
+            }
+            0x815 if ready(machine, *budget, 0x815, 1) => {
+                *budget -= 1;
+                let step = machine.execute_at(0x815, Instruction::LoadKeyPress(0x6))?;
+                if step != Step::Continue {
+                    return Ok(step);
+                }
+            }
+            0x817 if ready(machine, *budget, 0x817, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x00;
+                let (result, flag) = v[0x6].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x81F } else { 0x81D });
+            }
+            0x81D if ready(machine, *budget, 0x81D, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                v[0xA] = 0x05;
+                machine.set_program_counter(0x81F);
+            }
+            0x81F if ready(machine, *budget, 0x81F, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x00;
+                let (result, flag) = v[0x6].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x827 } else { 0x825 });
+            }
+            0x825 if ready(machine, *budget, 0x825, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x825, Instruction::ClearScreen)?;
+            }
+            0x827 if ready(machine, *budget, 0x827, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x00;
+                let (result, flag) = v[0x6].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x82F } else { 0x82D });
+            }
+            0x82D if ready(machine, *budget, 0x82D, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x82D, Instruction::Return)?;
+            }
+            0x82F if ready(machine, *budget, 0x82F, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x815);
+            }
+            _ => return Ok(Step::Continue),
+        }
+    }
+}
+
+/// The routine at 0x833
+fn routine_833(machine: &mut Machine, budget: &mut u32) -> Result<Step, MachineError> {
+    loop {
+        match machine.program_counter() {
+            0x833 if ready(machine, *budget, 0x833, 2) => {
+                *budget -= 2;
+                *machine.registers_mut().address_mut() = 0x32C;
+                machine.execute_at(0x835, Instruction::CallAddress(0x83D))?;
+            }
+            0x837 if ready(machine, *budget, 0x837, 2) => {
+                *budget -= 2;
+                let v = machine.registers_mut().data_mut();
+                v[0xA] = 0x04;
+                machine.execute_at(0x839, Instruction::CallAddress(0x9A5))?;
+            }
+            0x83B if ready(machine, *budget, 0x83B, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x83B, Instruction::Return)?;
+            }
+            _ => return Ok(Step::Continue),
+        }
+    }
+}
+
+/// The routine at 0x83D
+fn routine_83d(machine: &mut Machine, budget: &mut u32) -> Result<Step, MachineError> {
+    loop {
+        match machine.program_counter() {
+            0x83D if ready(machine, *budget, 0x83D, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = 0x38;
+                v[0x8] = 0x00;
+                v[0x7] = 0x08;
+                machine.set_program_counter(0x843);
+            }
+            0x843 if ready(machine, *budget, 0x843, 4) => {
+                *budget -= 4;
+                machine.execute_at(0x843, Instruction::Draw(0x98, 0x8))?;
+                machine.execute_at(0x845, Instruction::AddAddresssRegister(0x7))?;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = v[0x9].wrapping_add(0xF8);
+                let skip = v[0x9] != 0xF8;
+                machine.set_program_counter(if skip { 0x84D } else { 0x84B });
+            }
+            0x84B if ready(machine, *budget, 0x84B, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                v[0x8] = v[0x8].wrapping_add(0x08);
+                machine.set_program_counter(0x84D);
+            }
+            0x84D if ready(machine, *budget, 0x84D, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0x9] != 0xF8;
+                machine.set_program_counter(if skip { 0x851 } else { 0x84F });
+            }
+            0x84F if ready(machine, *budget, 0x84F, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = 0x38;
+                machine.set_program_counter(0x851);
+            }
+            0x851 if ready(machine, *budget, 0x851, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0x8] == 0x20;
+                machine.set_program_counter(if skip { 0x855 } else { 0x853 });
+            }
+            0x853 if ready(machine, *budget, 0x853, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x843);
+            }
+            0x855 if ready(machine, *budget, 0x855, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x855, Instruction::Return)?;
+            }
+            _ => return Ok(Step::Continue),
+        }
+    }
+}
+
+/// The routine at 0x857
+fn routine_857(machine: &mut Machine, budget: &mut u32) -> Result<Step, MachineError> {
+    loop {
+        match machine.program_counter() {
+            0x857 if ready(machine, *budget, 0x857, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x00;
+                let (result, flag) = v[0xD].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x85F } else { 0x85D });
+            }
+            0x85D if ready(machine, *budget, 0x85D, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                v[0xC] = 0x01;
+                machine.set_program_counter(0x85F);
+            }
+            0x85F if ready(machine, *budget, 0x85F, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x56;
+                let (result, flag) = v[0xD].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x867 } else { 0x865 });
+            }
+            0x865 if ready(machine, *budget, 0x865, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                v[0xC] = 0x02;
+                machine.set_program_counter(0x867);
+            }
+            0x867 if ready(machine, *budget, 0x867, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0xAB;
+                let (result, flag) = v[0xD].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x86F } else { 0x86D });
+            }
+            0x86D if ready(machine, *budget, 0x86D, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                v[0xC] = 0x03;
+                machine.set_program_counter(0x86F);
+            }
+            0x86F if ready(machine, *budget, 0x86F, 2) => {
+                *budget -= 2;
+                let v = machine.registers_mut().data_mut();
+                v[0xB] = 0x00;
+                v[0x7] = 0x00;
+                machine.set_program_counter(0x873);
+            }
+            0x873 if ready(machine, *budget, 0x873, 1) => {
+                *budget -= 1;
+                let step = machine.execute_at(0x873, Instruction::LoadKeyPress(0x6))?;
+                if step != Step::Continue {
+                    return Ok(step);
+                }
+            }
+            0x875 if ready(machine, *budget, 0x875, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0x6] != 0x0A;
+                machine.set_program_counter(if skip { 0x879 } else { 0x877 });
+            }
+            0x877 if ready(machine, *budget, 0x877, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                v[0xB] = 0x01;
+                machine.set_program_counter(0x879);
+            }
+            0x879 if ready(machine, *budget, 0x879, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0x6] != 0x00;
+                machine.set_program_counter(if skip { 0x87D } else { 0x87B });
+            }
+            0x87B if ready(machine, *budget, 0x87B, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                v[0xB] = 0x02;
+                machine.set_program_counter(0x87D);
+            }
+            0x87D if ready(machine, *budget, 0x87D, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0x6] != 0x0B;
+                machine.set_program_counter(if skip { 0x881 } else { 0x87F });
+            }
+            0x87F if ready(machine, *budget, 0x87F, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                v[0xB] = 0x03;
+                machine.set_program_counter(0x881);
+            }
+            0x881 if ready(machine, *budget, 0x881, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0xB] != 0x00;
+                machine.set_program_counter(if skip { 0x885 } else { 0x883 });
+            }
+            0x883 if ready(machine, *budget, 0x883, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x873);
+            }
+            0x885 if ready(machine, *budget, 0x885, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x885, Instruction::CallAddress(0x8A7))?;
+            }
+            0x887 if ready(machine, *budget, 0x887, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x887, Instruction::CallAddress(0x8C7))?;
+            }
+            0x889 if ready(machine, *budget, 0x889, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x889, Instruction::CallAddress(0x8E7))?;
+            }
+            0x88B if ready(machine, *budget, 0x88B, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x88B, Instruction::CallAddress(0x967))?;
+            }
+            0x88D if ready(machine, *budget, 0x88D, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0x5] == 0x01;
+                machine.set_program_counter(if skip { 0x891 } else { 0x88F });
+            }
+            0x88F if ready(machine, *budget, 0x88F, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x897);
+            }
+            0x891 if ready(machine, *budget, 0x891, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x891, Instruction::CallAddress(0x9A5))?;
+            }
+            0x893 if ready(machine, *budget, 0x893, 2) => {
+                *budget -= 2;
+                let v = machine.registers_mut().data_mut();
+                v[0x0] = v[0x0].wrapping_add(0x01);
+                machine.execute_at(0x895, Instruction::CallAddress(0x9A5))?;
+            }
+            0x897 if ready(machine, *budget, 0x897, 2) => {
+                *budget -= 2;
+                let v = machine.registers_mut().data_mut();
+                v[0x7] = v[0x7].wrapping_add(0x01);
+                let skip = v[0x7] == 0xA0;
+                machine.set_program_counter(if skip { 0x89D } else { 0x89B });
+            }
+            0x89B if ready(machine, *budget, 0x89B, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x897);
+            }
+            0x89D if ready(machine, *budget, 0x89D, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x89D, Instruction::CallAddress(0x8A7))?;
+            }
+            0x89F if ready(machine, *budget, 0x89F, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x89F, Instruction::CallAddress(0x8C7))?;
+            }
+            0x8A1 if ready(machine, *budget, 0x8A1, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x8A1, Instruction::CallAddress(0x8E7))?;
+            }
+            0x8A3 if ready(machine, *budget, 0x8A3, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x8A3, Instruction::CallAddress(0x967))?;
+            }
+            0x8A5 if ready(machine, *budget, 0x8A5, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x8A5, Instruction::Return)?;
+            }
+            _ => return Ok(Step::Continue),
+        }
+    }
+}
+
+/// The routine at 0x8A7
+fn routine_8a7(machine: &mut Machine, budget: &mut u32) -> Result<Step, MachineError> {
+    loop {
+        match machine.program_counter() {
+            0x8A7 if ready(machine, *budget, 0x8A7, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x01;
+                let (result, flag) = v[0xB].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x8AF } else { 0x8AD });
+            }
+            0x8AD if ready(machine, *budget, 0x8AD, 1) => {
+                *budget -= 1;
+                *machine.registers_mut().address_mut() = 0x202;
+                machine.set_program_counter(0x8AF);
+            }
+            0x8AF if ready(machine, *budget, 0x8AF, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x02;
+                let (result, flag) = v[0xB].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x8B7 } else { 0x8B5 });
+            }
+            0x8B5 if ready(machine, *budget, 0x8B5, 1) => {
+                *budget -= 1;
+                *machine.registers_mut().address_mut() = 0x210;
+                machine.set_program_counter(0x8B7);
+            }
+            0x8B7 if ready(machine, *budget, 0x8B7, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x03;
+                let (result, flag) = v[0xB].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x8BF } else { 0x8BD });
+            }
+            0x8BD if ready(machine, *budget, 0x8BD, 1) => {
+                *budget -= 1;
+                *machine.registers_mut().address_mut() = 0x21E;
+                machine.set_program_counter(0x8BF);
+            }
+            0x8BF if ready(machine, *budget, 0x8BF, 4) => {
+                *budget -= 4;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = 0x2C;
+                v[0x8] = 0x0C;
+                machine.execute_at(0x8C3, Instruction::Draw(0x98, 0x8))?;
+                machine.execute_at(0x8C5, Instruction::Return)?;
+            }
+            _ => return Ok(Step::Continue),
+        }
+    }
+}
+
+/// The routine at 0x8C7
+fn routine_8c7(machine: &mut Machine, budget: &mut u32) -> Result<Step, MachineError> {
+    loop {
+        match machine.program_counter() {
+            0x8C7 if ready(machine, *budget, 0x8C7, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x01;
+                let (result, flag) = v[0xC].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x8CF } else { 0x8CD });
+            }
+            0x8CD if ready(machine, *budget, 0x8CD, 1) => {
+                *budget -= 1;
+                *machine.registers_mut().address_mut() = 0x202;
+                machine.set_program_counter(0x8CF);
+            }
+            0x8CF if ready(machine, *budget, 0x8CF, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x02;
+                let (result, flag) = v[0xC].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x8D7 } else { 0x8D5 });
+            }
+            0x8D5 if ready(machine, *budget, 0x8D5, 1) => {
+                *budget -= 1;
+                *machine.registers_mut().address_mut() = 0x210;
+                machine.set_program_counter(0x8D7);
+            }
+            0x8D7 if ready(machine, *budget, 0x8D7, 3) => {
+                *budget -= 3;
+                let v = machine.registers_mut().data_mut();
+                v[0xE] = 0x03;
+                let (result, flag) = v[0xC].overflowing_sub(v[0xE]);
+                v[0xE] = result;
+                v[0xF] = u8::from(!flag);
+                let skip = v[0xF] != 0x01;
+                machine.set_program_counter(if skip { 0x8DF } else { 0x8DD });
+            }
+            0x8DD if ready(machine, *budget, 0x8DD, 1) => {
+                *budget -= 1;
+                *machine.registers_mut().address_mut() = 0x21E;
+                machine.set_program_counter(0x8DF);
+            }
+            0x8DF if ready(machine, *budget, 0x8DF, 4) => {
+                *budget -= 4;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = 0x0C;
+                v[0x8] = 0x0C;
+                machine.execute_at(0x8E3, Instruction::Draw(0x98, 0x8))?;
+                machine.execute_at(0x8E5, Instruction::Return)?;
+            }
+            _ => return Ok(Step::Continue),
+        }
+    }
+}
+
+/// The routine at 0x8E7
+fn routine_8e7(machine: &mut Machine, budget: &mut u32) -> Result<Step, MachineError> {
+    loop {
+        match machine.program_counter() {
+            0x8E7 if ready(machine, *budget, 0x8E7, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0xB] == 0x01;
+                machine.set_program_counter(if skip { 0x8EB } else { 0x8E9 });
+            }
+            0x8E9 if ready(machine, *budget, 0x8E9, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x8FD);
+            }
+            0x8EB if ready(machine, *budget, 0x8EB, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0xC] == 0x01;
+                machine.set_program_counter(if skip { 0x8EF } else { 0x8ED });
+            }
+            0x8ED if ready(machine, *budget, 0x8ED, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x8F1);
+            }
+            0x8EF if ready(machine, *budget, 0x8EF, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x8EF, Instruction::CallAddress(0x953))?;
+            }
+            0x8F1 if ready(machine, *budget, 0x8F1, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0xC] == 0x02;
+                machine.set_program_counter(if skip { 0x8F5 } else { 0x8F3 });
+            }
+            0x8F3 if ready(machine, *budget, 0x8F3, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x8F7);
+            }
+            0x8F5 if ready(machine, *budget, 0x8F5, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x8F5, Instruction::CallAddress(0x92B))?;
+            }
+            0x8F7 if ready(machine, *budget, 0x8F7, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0xC] == 0x03;
+                machine.set_program_counter(if skip { 0x8FB } else { 0x8F9 });
+            }
+            0x8F9 if ready(machine, *budget, 0x8F9, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x8FD);
+            }
+            0x8FB if ready(machine, *budget, 0x8FB, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x8FB, Instruction::CallAddress(0x93F))?;
+            }
+            0x8FD if ready(machine, *budget, 0x8FD, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0xB] == 0x02;
+                machine.set_program_counter(if skip { 0x901 } else { 0x8FF });
+            }
+            0x8FF if ready(machine, *budget, 0x8FF, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x913);
+            }
+            0x901 if ready(machine, *budget, 0x901, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0xC] == 0x02;
+                machine.set_program_counter(if skip { 0x905 } else { 0x903 });
+            }
+            0x903 if ready(machine, *budget, 0x903, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x907);
+            }
+            0x905 if ready(machine, *budget, 0x905, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x905, Instruction::CallAddress(0x953))?;
+            }
+            0x907 if ready(machine, *budget, 0x907, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0xC] == 0x03;
+                machine.set_program_counter(if skip { 0x90B } else { 0x909 });
+            }
+            0x909 if ready(machine, *budget, 0x909, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x90D);
+            }
+            0x90B if ready(machine, *budget, 0x90B, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x90B, Instruction::CallAddress(0x92B))?;
+            }
+            0x90D if ready(machine, *budget, 0x90D, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0xC] == 0x01;
+                machine.set_program_counter(if skip { 0x911 } else { 0x90F });
+            }
+            0x90F if ready(machine, *budget, 0x90F, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x913);
+            }
+            0x911 if ready(machine, *budget, 0x911, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x911, Instruction::CallAddress(0x93F))?;
+            }
+            0x913 if ready(machine, *budget, 0x913, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0xB] == 0x03;
+                machine.set_program_counter(if skip { 0x917 } else { 0x915 });
+            }
+            0x915 if ready(machine, *budget, 0x915, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x929);
+            }
+            0x917 if ready(machine, *budget, 0x917, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0xC] == 0x03;
+                machine.set_program_counter(if skip { 0x91B } else { 0x919 });
+            }
+            0x919 if ready(machine, *budget, 0x919, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x91D);
+            }
+            0x91B if ready(machine, *budget, 0x91B, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x91B, Instruction::CallAddress(0x953))?;
+            }
+            0x91D if ready(machine, *budget, 0x91D, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0xC] == 0x01;
+                machine.set_program_counter(if skip { 0x921 } else { 0x91F });
+            }
+            0x91F if ready(machine, *budget, 0x91F, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x923);
+            }
+            0x921 if ready(machine, *budget, 0x921, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x921, Instruction::CallAddress(0x92B))?;
+            }
+            0x923 if ready(machine, *budget, 0x923, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0xC] == 0x02;
+                machine.set_program_counter(if skip { 0x927 } else { 0x925 });
+            }
+            0x925 if ready(machine, *budget, 0x925, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x929);
+            }
+            0x927 if ready(machine, *budget, 0x927, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x927, Instruction::CallAddress(0x93F))?;
+            }
+            0x929 if ready(machine, *budget, 0x929, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x929, Instruction::Return)?;
+            }
+            _ => return Ok(Step::Continue),
+        }
+    }
+}
+
+/// The routine at 0x92B
+fn routine_92b(machine: &mut Machine, budget: &mut u32) -> Result<Step, MachineError> {
+    loop {
+        match machine.program_counter() {
+            0x92B if ready(machine, *budget, 0x92B, 8) => {
+                *budget -= 8;
+                *machine.registers_mut().address_mut() = 0x74A;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = 0x01;
+                v[0x8] = 0x01;
+                machine.execute_at(0x931, Instruction::Draw(0x98, 0x5))?;
+                *machine.registers_mut().address_mut() = 0x759;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = 0x09;
+                v[0x8] = 0x01;
+                machine.execute_at(0x939, Instruction::Draw(0x98, 0x5))?;
+            }
+            0x93B if ready(machine, *budget, 0x93B, 2) => {
+                *budget -= 2;
+                let v = machine.registers_mut().data_mut();
+                v[0x5] = 0x00;
+                machine.execute_at(0x93D, Instruction::Return)?;
+            }
+            _ => return Ok(Step::Continue),
+        }
+    }
+}
+
+/// The routine at 0x93F
+fn routine_93f(machine: &mut Machine, budget: &mut u32) -> Result<Step, MachineError> {
+    loop {
+        match machine.program_counter() {
+            0x93F if ready(machine, *budget, 0x93F, 8) => {
+                *budget -= 8;
+                *machine.registers_mut().address_mut() = 0x72C;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = 0x01;
+                v[0x8] = 0x01;
+                machine.execute_at(0x945, Instruction::Draw(0x98, 0x5))?;
+                *machine.registers_mut().address_mut() = 0x73B;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = 0x09;
+                v[0x8] = 0x01;
+                machine.execute_at(0x94D, Instruction::Draw(0x98, 0x5))?;
+            }
+            0x94F if ready(machine, *budget, 0x94F, 2) => {
+                *budget -= 2;
+                let v = machine.registers_mut().data_mut();
+                v[0x5] = 0x01;
+                machine.execute_at(0x951, Instruction::Return)?;
+            }
+            _ => return Ok(Step::Continue),
+        }
+    }
+}
+
+/// The routine at 0x953
+fn routine_953(machine: &mut Machine, budget: &mut u32) -> Result<Step, MachineError> {
+    loop {
+        match machine.program_counter() {
+            0x953 if ready(machine, *budget, 0x953, 8) => {
+                *budget -= 8;
+                *machine.registers_mut().address_mut() = 0x768;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = 0x01;
+                v[0x8] = 0x01;
+                machine.execute_at(0x959, Instruction::Draw(0x98, 0x5))?;
+                *machine.registers_mut().address_mut() = 0x777;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = 0x09;
+                v[0x8] = 0x01;
+                machine.execute_at(0x961, Instruction::Draw(0x98, 0x5))?;
+            }
+            0x963 if ready(machine, *budget, 0x963, 2) => {
+                *budget -= 2;
+                let v = machine.registers_mut().data_mut();
+                v[0x5] = 0x02;
+                machine.execute_at(0x965, Instruction::Return)?;
+            }
+            _ => return Ok(Step::Continue),
+        }
+    }
+}
+
+/// The routine at 0x967
+fn routine_967(machine: &mut Machine, budget: &mut u32) -> Result<Step, MachineError> {
+    loop {
+        match machine.program_counter() {
+            0x967 if ready(machine, *budget, 0x967, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0x5] == 0x00;
+                machine.set_program_counter(if skip { 0x96B } else { 0x969 });
+            }
+            0x969 if ready(machine, *budget, 0x969, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x97B);
+            }
+            0x96B if ready(machine, *budget, 0x96B, 8) => {
+                *budget -= 8;
+                *machine.registers_mut().address_mut() = 0x786;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = 0x39;
+                v[0x8] = 0x1B;
+                machine.execute_at(0x971, Instruction::Draw(0x98, 0x2))?;
+                *machine.registers_mut().address_mut() = 0x788;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = 0x03;
+                v[0x8] = 0x1B;
+                machine.execute_at(0x979, Instruction::Draw(0x98, 0x2))?;
+            }
+            0x97B if ready(machine, *budget, 0x97B, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0x5] == 0x01;
+                machine.set_program_counter(if skip { 0x97F } else { 0x97D });
+            }
+            0x97D if ready(machine, *budget, 0x97D, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x98F);
+            }
+            0x97F if ready(machine, *budget, 0x97F, 8) => {
+                *budget -= 8;
+                *machine.registers_mut().address_mut() = 0x786;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = 0x03;
+                v[0x8] = 0x1B;
+                machine.execute_at(0x985, Instruction::Draw(0x98, 0x2))?;
+                *machine.registers_mut().address_mut() = 0x788;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = 0x39;
+                v[0x8] = 0x1B;
+                machine.execute_at(0x98D, Instruction::Draw(0x98, 0x2))?;
+            }
+            0x98F if ready(machine, *budget, 0x98F, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0x5] == 0x02;
+                machine.set_program_counter(if skip { 0x993 } else { 0x991 });
+            }
+            0x991 if ready(machine, *budget, 0x991, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x9A3);
+            }
+            0x993 if ready(machine, *budget, 0x993, 8) => {
+                *budget -= 8;
+                *machine.registers_mut().address_mut() = 0x78A;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = 0x38;
+                v[0x8] = 0x18;
+                machine.execute_at(0x999, Instruction::Draw(0x98, 0x2))?;
+                *machine.registers_mut().address_mut() = 0x788;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = 0x03;
+                v[0x8] = 0x1C;
+                machine.execute_at(0x9A1, Instruction::Draw(0x98, 0x1))?;
+            }
+            0x9A3 if ready(machine, *budget, 0x9A3, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x9A3, Instruction::Return)?;
+            }
+            _ => return Ok(Step::Continue),
+        }
+    }
+}
+
+/// The routine at 0x9A5
+fn routine_9a5(machine: &mut Machine, budget: &mut u32) -> Result<Step, MachineError> {
+    loop {
+        match machine.program_counter() {
+            0x9A5 if ready(machine, *budget, 0x9A5, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0x0] == 0x0A;
+                machine.set_program_counter(if skip { 0x9A9 } else { 0x9A7 });
+            }
+            0x9A7 if ready(machine, *budget, 0x9A7, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x9AD);
+            }
+            0x9A9 if ready(machine, *budget, 0x9A9, 2) => {
+                *budget -= 2;
+                let v = machine.registers_mut().data_mut();
+                v[0x1] = v[0x1].wrapping_add(0x01);
+                v[0x0] = 0x00;
+                machine.set_program_counter(0x9AD);
+            }
+            0x9AD if ready(machine, *budget, 0x9AD, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0x1] == 0x0A;
+                machine.set_program_counter(if skip { 0x9B1 } else { 0x9AF });
+            }
+            0x9AF if ready(machine, *budget, 0x9AF, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x9B5);
+            }
+            0x9B1 if ready(machine, *budget, 0x9B1, 2) => {
+                *budget -= 2;
+                let v = machine.registers_mut().data_mut();
+                v[0x2] = v[0x2].wrapping_add(0x01);
+                v[0x1] = 0x00;
+                machine.set_program_counter(0x9B5);
+            }
+            0x9B5 if ready(machine, *budget, 0x9B5, 1) => {
+                *budget -= 1;
+                let v = machine.registers_mut().data_mut();
+                let skip = v[0x2] == 0x0A;
+                machine.set_program_counter(if skip { 0x9B9 } else { 0x9B7 });
+            }
+            0x9B7 if ready(machine, *budget, 0x9B7, 1) => {
+                *budget -= 1;
+                machine.set_program_counter(0x9C7);
+            }
+            0x9B9 if ready(machine, *budget, 0x9B9, 6) => {
+                *budget -= 6;
+                let v = machine.registers_mut().data_mut();
+                v[0x2] = 0x00;
+                v[0x1] = 0x00;
+                v[0x0] = 0x00;
+                machine.execute_at(0x9BF, Instruction::ClearScreen)?;
+                let v = machine.registers_mut().data_mut();
+                v[0xA] = 0x05;
+                machine.execute_at(0x9C3, Instruction::CallAddress(0x78D))?;
+            }
+            0x9C5 if ready(machine, *budget, 0x9C5, 1) => {
+                *budget -= 1;
+                machine.execute_at(0x9C5, Instruction::ClearScreen)?;
+            }
+            0x9C7 if ready(machine, *budget, 0x9C7, 8) => {
+                *budget -= 8;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = 0x3A;
+                v[0x8] = 0x01;
+                machine.execute_at(0x9CB, Instruction::LoadSpriteAddress(0x0))?;
+                machine.execute_at(0x9CD, Instruction::Draw(0x98, 0x5))?;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = 0x35;
+                v[0x8] = 0x01;
+                machine.execute_at(0x9D3, Instruction::LoadSpriteAddress(0x1))?;
+                machine.execute_at(0x9D5, Instruction::Draw(0x98, 0x5))?;
+            }
+            0x9D7 if ready(machine, *budget, 0x9D7, 5) => {
+                *budget -= 5;
+                let v = machine.registers_mut().data_mut();
+                v[0x9] = 0x30;
+                v[0x8] = 0x01;
+                machine.execute_at(0x9DB, Instruction::LoadSpriteAddress(0x2))?;
+                machine.execute_at(0x9DD, Instruction::Draw(0x98, 0x5))?;
+                machine.execute_at(0x9DF, Instruction::Return)?;
+            }
+            _ => return Ok(Step::Continue),
+        }
+    }
+}
@@ -0,0 +1,46 @@
+//! Checks that recompiled applications behave exactly like interpreted ones
+
+use std::fs;
+
+use chip_8::{
+    machine::Machine, memory::MemoryLayout, movie::Movie, quirks::Quirks, recompiler::Program,
+    rom::Rom,
+};
+
+/// Rock paper scissors, recompiled by `chip8-recompile roms/RPS.ch8 --quirks schip`
+#[path = "recompiled/rps.rs"]
+mod rps;
+
+#[test]
+fn rps_recompiled_source_is_current() {
+    let rom = Rom::from_path("roms/RPS.ch8").unwrap();
+    let mut source = Vec::new();
+    Program::analyze(&rom, &MemoryLayout::default())
+        .write_rust("RPS.ch8", Quirks::SCHIP, &mut source)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(source).unwrap(),
+        fs::read_to_string("tests/recompiled/rps.rs").unwrap()
+    );
+}
+
+#[test]
+fn rps_session_recompiled() {
+    let movie = Movie::from_bytes(&fs::read("tests/movies/rps.c8m").unwrap()).unwrap();
+    let mut interpreted = Machine::new(Quirks::SCHIP);
+    interpreted
+        .load_rom(&Rom::from_path("roms/RPS.ch8").unwrap())
+        .unwrap();
+    movie.start_playback(&mut interpreted).unwrap();
+    let mut recompiled = rps::machine();
+    movie.start_playback(&mut recompiled).unwrap();
+
+    for (frame, keypad) in movie.frames().iter().enumerate() {
+        *interpreted.keypad_mut() = *keypad;
+        *recompiled.keypad_mut() = *keypad;
+        let expected = interpreted.run_frame(movie.cycles_per_frame());
+        let step = rps::run_frame(&mut recompiled, movie.cycles_per_frame());
+        assert_eq!(step, expected, "frame {frame}");
+        assert!(recompiled == interpreted, "frame {frame}");
+    }
+}
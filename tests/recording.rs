@@ -0,0 +1,85 @@
+//! Checks the frames and timing of GIF and Y4M recordings
+
+use std::{fs, path::PathBuf};
+
+use chip_8::{
+    display::{Display, HEIGHT, WIDTH},
+    recording::Recorder,
+    screenshot::Palette,
+};
+
+/// Retrieves a path in the temporary directory, unique to the test
+fn temporary_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("chip8-recording-{}-{name}", std::process::id()))
+}
+
+/// Records the displays to a file with the name, then returns its contents
+fn record(name: &str, displays: impl IntoIterator<Item = Display>) -> Vec<u8> {
+    let path = temporary_path(name);
+    let mut recorder = Recorder::create(&path, 1, Palette::MONOCHROME).unwrap();
+    for display in displays {
+        recorder.record_frame(&display).unwrap();
+    }
+    recorder.finish().unwrap();
+    let bytes = fs::read(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    bytes
+}
+
+/// Creates a display with only the pixel in the top left corner lit or unlit
+fn display(lit: bool) -> Display {
+    let mut display = Display::new();
+    if lit {
+        display.draw_sprite(0, 0, &[0x80], false);
+    }
+    display
+}
+
+/// Retrieves the delays of the frames of a GIF
+fn gif_delays(gif: &[u8]) -> Vec<u16> {
+    let mut decoder = gif::DecodeOptions::new().read_info(gif).unwrap();
+    let mut delays = Vec::new();
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+        delays.push(frame.delay);
+    }
+    delays
+}
+
+#[test]
+fn gif_merges_identical_frames() {
+    let displays = (0..60).map(|frame| display(frame >= 30));
+    assert_eq!(gif_delays(&record("merged.gif", displays)), [50, 50]);
+}
+
+#[test]
+fn gif_frames_last_at_least_two_hundredths() {
+    // Three frames of flicker lasting 1.67 hundredths of a second each, then a steady frame
+    let displays = (0..60).map(|frame| display(frame < 3 && frame % 2 == 0));
+    let gif = record("flicker.gif", displays);
+    assert_eq!(gif_delays(&gif), [2, 2, 2, 94]);
+
+    // Continuous flicker keeps every frame
+    let displays = (0..60).map(|frame| display(frame % 2 == 0));
+    let delays = gif_delays(&record("flicker-long.gif", displays));
+    assert_eq!(delays.len(), 60);
+    assert!(delays.iter().all(|&delay| delay == 2), "{delays:?}");
+}
+
+#[test]
+fn y4m_stores_every_frame() {
+    let y4m = record("video.y4m", [display(true), display(true), display(false)]);
+    let header = b"YUV4MPEG2 W64 H32 F60:1 Ip A1:1 C444\n";
+    assert!(y4m.starts_with(header));
+
+    let frame_size = b"FRAME\n".len() + 3 * WIDTH * HEIGHT;
+    let frames = y4m[header.len()..].chunks(frame_size).collect::<Vec<_>>();
+    assert_eq!(frames.len(), 3);
+    for (frame, lit) in frames.iter().zip([true, true, false]) {
+        assert_eq!(frame.len(), frame_size);
+        assert!(frame.starts_with(b"FRAME\n"));
+        // The luma of white is 235 and the luma of black 16 in limited range
+        let luma = &frame[6..6 + WIDTH * HEIGHT];
+        assert_eq!(luma[0], if lit { 235 } else { 16 });
+        assert!(luma[1..].iter().all(|&luma| luma == 16));
+    }
+}
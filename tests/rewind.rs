@@ -0,0 +1,141 @@
+//! Records machines into the rewind buffer and steps them back in time
+
+use chip_8::{machine::Machine, quirks::Quirks, rewind::Rewind, state::StateError};
+
+/// Counts in V0 and stores the count as decimal digits at 0x300, forever
+const COUNTER: [u8; 8] = [0x70, 0x01, 0xA3, 0x00, 0xF0, 0x33, 0x12, 0x00];
+
+/// Calls a subroutine calling another one and counts in V0, so the stack depth changes every
+/// instruction
+const SUBROUTINES: [u8; 12] = [
+    0x22, 0x06, 0x70, 0x01, 0x12, 0x00, 0x22, 0x0A, 0x00, 0xEE, 0x00, 0xEE,
+];
+
+/// Creates a machine running the program
+fn machine(program: &[u8]) -> Machine {
+    let mut machine = Machine::new(Quirks::VIP);
+    machine.load_program(program).unwrap();
+    machine.set_seed(0);
+    machine
+}
+
+/// Creates a machine running the counter
+fn counter() -> Machine {
+    machine(&COUNTER)
+}
+
+/// Runs the machine for the number of frames, recording every frame into the rewind buffer.
+/// Returns the machine after every frame, oldest first.
+fn record(machine: &mut Machine, rewind: &mut Rewind, frames: usize) -> Vec<Machine> {
+    record_cycles(machine, rewind, frames, 4)
+}
+
+/// Runs the machine for the number of frames of the given number of instructions, recording
+/// every frame into the rewind buffer. Returns the machine after every frame, oldest first.
+fn record_cycles(
+    machine: &mut Machine,
+    rewind: &mut Rewind,
+    frames: usize,
+    cycles: u32,
+) -> Vec<Machine> {
+    (0..frames)
+        .map(|_| {
+            machine.run_frame(cycles).unwrap();
+            rewind.record(machine);
+            machine.clone()
+        })
+        .collect()
+}
+
+#[test]
+fn rewinding_restores_every_frame() {
+    let mut machine = counter();
+    let mut rewind = Rewind::default();
+    rewind.record(&machine);
+    let mut history = [
+        vec![machine.clone()],
+        record(&mut machine, &mut rewind, 100),
+    ]
+    .concat();
+    assert_eq!(rewind.len(), 100);
+
+    history.pop();
+    while let Some(expected) = history.pop() {
+        assert_eq!(rewind.rewind(&mut machine), Ok(true));
+        assert!(machine == expected, "{} frames left", history.len());
+    }
+    assert_eq!(rewind.rewind(&mut machine), Ok(false));
+    assert!(rewind.is_empty());
+}
+
+#[test]
+fn rewinding_restores_changing_stack_depths() {
+    let mut machine = machine(&SUBROUTINES);
+    let mut rewind = Rewind::default();
+    let mut history = record_cycles(&mut machine, &mut rewind, 50, 1);
+    let depths = history
+        .iter()
+        .map(|machine| machine.stack().depth())
+        .collect::<Vec<_>>();
+    assert_eq!(depths[..5], [1, 2, 1, 0, 0]);
+    assert_eq!(rewind.len(), 49);
+
+    history.pop();
+    while let Some(expected) = history.pop() {
+        assert_eq!(rewind.rewind(&mut machine), Ok(true));
+        assert!(machine == expected, "{} frames left", history.len());
+    }
+    assert_eq!(rewind.rewind(&mut machine), Ok(false));
+}
+
+#[test]
+fn recording_continues_after_rewinding() {
+    let mut machine = counter();
+    let mut rewind = Rewind::default();
+    let history = record(&mut machine, &mut rewind, 20);
+    for _ in 0..10 {
+        assert_eq!(rewind.rewind(&mut machine), Ok(true));
+    }
+    assert!(machine == history[9]);
+
+    let mut history = [history[..10].to_vec(), record(&mut machine, &mut rewind, 5)].concat();
+    history.pop();
+    while let Some(expected) = history.pop() {
+        assert_eq!(rewind.rewind(&mut machine), Ok(true));
+        assert!(machine == expected);
+    }
+}
+
+#[test]
+fn memory_usage_stays_within_budget() {
+    let budget = 3 * machine_state_size();
+    let mut machine = counter();
+    let mut rewind = Rewind::new(budget);
+    record(&mut machine, &mut rewind, 1_000);
+    assert!(rewind.memory_usage() <= budget);
+    assert!(rewind.len() < 1_000);
+    assert!(!rewind.is_empty());
+}
+
+/// Retrieves the size of a full snapshot of the counter
+fn machine_state_size() -> usize {
+    counter().save_state().len()
+}
+
+#[test]
+fn loading_another_application_clears_the_buffer() {
+    let mut machine = counter();
+    let mut rewind = Rewind::default();
+    record(&mut machine, &mut rewind, 10);
+
+    machine.load_program(&[0x12, 0x00]).unwrap();
+    let loaded = machine.clone();
+    assert!(matches!(
+        rewind.rewind(&mut machine),
+        Err(StateError::RomMismatch { .. })
+    ));
+    assert!(machine == loaded);
+    assert!(rewind.is_empty());
+    assert_eq!(rewind.memory_usage(), 0);
+    assert_eq!(rewind.rewind(&mut machine), Ok(false));
+}
@@ -0,0 +1,88 @@
+//! Checks that every random number generator is reproducible from its seed and saved state
+
+use chip_8::{
+    machine::Machine,
+    quirks::Quirks,
+    rng::{CosmacVip, Rng, VIP_INTERPRETER_SIZE, Xorshift},
+};
+
+/// Generates the given number of bytes
+fn bytes(rng: &mut impl Rng, count: usize) -> Vec<u8> {
+    (0..count).map(|_| rng.next_byte()).collect()
+}
+
+/// An interpreter page whose bytes are their own index
+fn counting_page() -> [u8; 256] {
+    core::array::from_fn(|index| index as u8)
+}
+
+#[test]
+fn xorshift_sequences_depend_on_the_seed() {
+    let sequence = bytes(&mut Xorshift::new(7), 64);
+    assert_eq!(bytes(&mut Xorshift::new(7), 64), sequence);
+    assert_ne!(bytes(&mut Xorshift::new(8), 64), sequence);
+
+    // Seed 0 must not get stuck at 0
+    assert!(
+        bytes(&mut Xorshift::new(0), 64)
+            .iter()
+            .any(|&byte| byte != 0)
+    );
+}
+
+#[test]
+fn cosmac_vip_sequences_depend_on_the_seed() {
+    // The low byte of the seed indexes the page, the high byte is the first sum
+    let mut rng = CosmacVip::new(0x1000, counting_page());
+    assert_eq!(bytes(&mut rng, 4), [0x11, 0x13, 0x16, 0x1A]);
+
+    let sequence = bytes(&mut CosmacVip::new(0x1234, counting_page()), 512);
+    assert_eq!(
+        bytes(&mut CosmacVip::new(0x1234, counting_page()), 512),
+        sequence
+    );
+    assert_ne!(
+        bytes(&mut CosmacVip::new(0x1235, counting_page()), 512),
+        sequence
+    );
+}
+
+#[test]
+fn cosmac_vip_reads_the_page_from_interpreter_dumps() {
+    let mut interpreter = vec![0xFF; VIP_INTERPRETER_SIZE];
+    interpreter[0x100..].copy_from_slice(&counting_page());
+    let from_page = CosmacVip::from_interpreter(0x1000, &counting_page()).unwrap();
+    let from_interpreter = CosmacVip::from_interpreter(0x1000, &interpreter).unwrap();
+    assert_eq!(from_page, from_interpreter);
+    assert_eq!(from_page, CosmacVip::new(0x1000, counting_page()));
+    assert!(CosmacVip::from_interpreter(0, &[0; 100]).is_none());
+}
+
+#[test]
+fn restored_generators_continue_the_sequence() {
+    let mut xorshift = Xorshift::new(3);
+    let mut vip = CosmacVip::new(0x0102, counting_page());
+    bytes(&mut xorshift, 10);
+    bytes(&mut vip, 10);
+
+    let mut restored_xorshift = Xorshift::new(0);
+    assert!(restored_xorshift.restore(&xorshift.save()));
+    assert_eq!(bytes(&mut restored_xorshift, 64), bytes(&mut xorshift, 64));
+
+    let mut restored_vip = CosmacVip::new(0, counting_page());
+    assert!(restored_vip.restore(&vip.save()));
+    assert_eq!(bytes(&mut restored_vip, 64), bytes(&mut vip, 64));
+
+    assert!(!restored_xorshift.restore(&[0; 8]));
+    assert!(!restored_vip.restore(&[0; 3]));
+}
+
+#[test]
+fn random_instruction_uses_the_generator() {
+    // Loads a random byte into V0
+    let mut machine = Machine::new(Quirks::VIP);
+    machine.set_rng(CosmacVip::new(0x1000, counting_page()));
+    machine.load_program(&[0xC0, 0xFF, 0xC1, 0x0F]).unwrap();
+    machine.run_cycles(2).unwrap();
+    assert_eq!(machine.registers().data()[..2], [0x11, 0x03]);
+}
@@ -0,0 +1,57 @@
+//! Checks reading, identifying and loading applications
+
+use chip_8::{
+    machine::Machine,
+    memory::MemoryLayout,
+    quirks::Quirks,
+    rom::{Rom, RomError},
+};
+
+#[test]
+fn applications_are_identified_by_their_checksums() {
+    let rom = Rom::from_bytes(*b"abc").unwrap();
+    assert_eq!(rom.len(), 3);
+    assert_eq!(rom.sha1_hex(), "a9993e364706816aba3e25717850c26c9cd0d89d");
+    assert_eq!(rom.crc32(), 0x3524_41C2);
+    assert_eq!(Rom::from_reader(&b"abc"[..]).unwrap(), rom);
+}
+
+#[test]
+fn empty_and_missing_applications_are_rejected() {
+    assert!(matches!(Rom::from_bytes([]), Err(RomError::Empty)));
+    assert!(matches!(Rom::from_reader(&[][..]), Err(RomError::Empty)));
+    assert!(matches!(
+        Rom::from_path("roms/missing.ch8"),
+        Err(RomError::Io(_))
+    ));
+}
+
+#[test]
+fn applications_must_fit_after_the_program_start() {
+    let mut machine = Machine::new(Quirks::VIP);
+    let largest = Rom::from_bytes(vec![0xAB; 0xE00]).unwrap();
+    machine.load_rom(&largest).unwrap();
+    assert_eq!(machine.memory().data()[0x200], 0xAB);
+    assert_eq!(machine.memory().data()[0xFFF], 0xAB);
+    assert_eq!(machine.rom_sha1(), largest.sha1());
+    assert_eq!(machine.rom_checksum(), largest.crc32());
+
+    let too_large = Rom::from_bytes(vec![0; 0xE01]).unwrap();
+    assert!(matches!(
+        machine.load_rom(&too_large),
+        Err(RomError::TooLarge {
+            size: 0xE01,
+            capacity: 0xE00
+        })
+    ));
+
+    // The ETI-660 loads applications at 0x600, leaving less room
+    let mut eti = Machine::with_layout(Quirks::VIP, MemoryLayout::ETI_660).unwrap();
+    assert!(matches!(
+        eti.load_rom(&largest),
+        Err(RomError::TooLarge {
+            size: 0xE00,
+            capacity: 0xA00
+        })
+    ));
+}
@@ -0,0 +1,99 @@
+//! Checks the images the display is exported to
+
+use std::path::Path;
+
+use chip_8::{
+    display::{Display, HEIGHT, WIDTH},
+    screenshot::{ImageFormat, Palette, write_pbm, write_png, write_ppm},
+};
+
+/// The palette of the images, distinct in every channel
+const PALETTE: Palette = Palette {
+    background: [0x10, 0x20, 0x30],
+    foreground: [0xF0, 0xE0, 0xD0],
+};
+
+/// Creates a display with the two leftmost pixels of the top row and the bottom right pixel lit
+fn display() -> Display {
+    let mut display = Display::new();
+    display.draw_sprite(0, 0, &[0xC0], false);
+    display.draw_sprite(WIDTH - 1, HEIGHT - 1, &[0x80], false);
+    display
+}
+
+#[test]
+fn pbm_packs_scaled_pixels() {
+    let mut pbm = Vec::new();
+    write_pbm(&display(), 2, &mut pbm).unwrap();
+    let header = b"P4\n128 64\n";
+    assert!(pbm.starts_with(header));
+
+    // Every scaled row is 16 bytes, lit pixels are set bits
+    let rows = pbm[header.len()..].chunks(16).collect::<Vec<_>>();
+    assert_eq!(rows.len(), 64);
+    for row in &rows[..2] {
+        assert_eq!(row[0], 0xF0);
+        assert!(row[1..].iter().all(|&byte| byte == 0));
+    }
+    assert!(
+        rows[2..62]
+            .iter()
+            .all(|row| row.iter().all(|&byte| byte == 0))
+    );
+    for row in &rows[62..] {
+        assert_eq!(row[15], 0x03);
+    }
+}
+
+#[test]
+fn ppm_maps_pixels_to_the_palette() {
+    let mut ppm = Vec::new();
+    write_ppm(&display(), 1, PALETTE, &mut ppm).unwrap();
+    let header = b"P6\n64 32\n255\n";
+    assert!(ppm.starts_with(header));
+
+    let pixels = ppm[header.len()..].chunks(3).collect::<Vec<_>>();
+    assert_eq!(pixels.len(), WIDTH * HEIGHT);
+    assert_eq!(pixels[0], PALETTE.foreground);
+    assert_eq!(pixels[1], PALETTE.foreground);
+    assert_eq!(pixels[2], PALETTE.background);
+    assert_eq!(pixels[WIDTH * HEIGHT - 1], PALETTE.foreground);
+}
+
+#[test]
+fn png_decodes_to_the_display() {
+    let mut png = Vec::new();
+    write_png(&display(), 3, PALETTE, &mut png).unwrap();
+
+    let mut decoder = png::Decoder::new(std::io::Cursor::new(png));
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    assert_eq!((info.width, info.height), (192, 96));
+    assert_eq!(info.color_type, png::ColorType::Rgb);
+
+    let expected = display();
+    for (index, pixel) in pixels.chunks(3).enumerate() {
+        let (x, y) = (index % 192 / 3, index / 192 / 3);
+        let lit = expected.pixel(x, y) == Some(true);
+        assert_eq!(pixel, PALETTE.color(lit), "pixel {x}, {y}");
+    }
+}
+
+#[test]
+fn formats_follow_the_extension() {
+    assert_eq!(
+        ImageFormat::from_path(Path::new("shot.PNG")),
+        Some(ImageFormat::Png)
+    );
+    assert_eq!(
+        ImageFormat::from_path(Path::new("golden.pbm")),
+        Some(ImageFormat::Pbm)
+    );
+    assert_eq!(
+        ImageFormat::from_path(Path::new("shot.ppm")),
+        Some(ImageFormat::Ppm)
+    );
+    assert_eq!(ImageFormat::from_path(Path::new("shot.bmp")), None);
+}
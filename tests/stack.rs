@@ -0,0 +1,66 @@
+//! Pushes and pops return addresses, on their own and through the call instructions
+
+use chip_8::{
+    machine::{Machine, MachineError, Step},
+    quirks::Quirks,
+    stack::{SCHIP_DEPTH, Stack, StackError, VIP_DEPTH},
+};
+
+#[test]
+fn stack_is_last_in_first_out() {
+    let mut stack = Stack::new(Some(VIP_DEPTH));
+    for address in 0..VIP_DEPTH as u16 {
+        stack.push(0x200 + address).unwrap();
+    }
+    assert_eq!(stack.push(0x300), Err(StackError::Overflow));
+    assert_eq!(stack.depth(), VIP_DEPTH);
+    for address in (0..VIP_DEPTH as u16).rev() {
+        assert_eq!(stack.pop(), Ok(0x200 + address));
+    }
+    assert_eq!(stack.pop(), Err(StackError::Underflow));
+    assert!(stack.entries().is_empty());
+}
+
+#[test]
+fn limits_below_the_depth_are_rejected() {
+    let mut stack = Stack::new(None);
+    for _ in 0..3 {
+        stack.push(0x200).unwrap();
+    }
+    assert!(!stack.set_limit(Some(2)));
+    assert_eq!(stack.limit(), None);
+    assert!(stack.set_limit(Some(3)));
+    assert_eq!(stack.push(0x200), Err(StackError::Overflow));
+}
+
+#[test]
+fn recursion_overflows_the_stack() {
+    // Calls itself forever
+    let mut machine = Machine::new(Quirks::SCHIP);
+    machine.load_program(&[0x22, 0x00]).unwrap();
+    assert_eq!(machine.run_cycles(SCHIP_DEPTH as u32), Ok(Step::Continue));
+    assert_eq!(machine.step(), Err(MachineError::StackOverflow(0x200)));
+    assert_eq!(machine.stack().depth(), SCHIP_DEPTH);
+}
+
+#[test]
+fn returning_from_the_application_underflows_the_stack() {
+    let mut machine = Machine::new(Quirks::SCHIP);
+    machine.load_program(&[0x00, 0xEE]).unwrap();
+    assert_eq!(machine.step(), Err(MachineError::StackUnderflow(0x200)));
+}
+
+#[test]
+fn calls_are_mirrored_into_memory() {
+    let mut machine = Machine::new(Quirks::VIP);
+    machine.stack_mut().set_vip_mirror(true);
+    machine
+        .load_program(&[0x22, 0x04, 0x00, 0x00, 0x22, 0x08, 0x00, 0x00, 0x00, 0xEE])
+        .unwrap();
+    machine.run_cycles(2).unwrap();
+    assert_eq!(machine.stack().entries(), [0x202, 0x206]);
+    let first = usize::from(Stack::mirror_address(0));
+    let second = usize::from(Stack::mirror_address(1));
+    assert_eq!(machine.memory().data()[first..first + 2], [0x02, 0x02]);
+    assert_eq!(machine.memory().data()[second..second + 2], [0x02, 0x06]);
+}
@@ -0,0 +1,116 @@
+//! Loads save states of every format version and checks the restored machine
+
+use std::fs;
+
+use chip_8::{
+    machine::Machine,
+    quirks::Quirks,
+    rom::Rom,
+    state::{MAGIC, StateError, VERSION},
+};
+
+/// Creates a machine running rock paper scissors
+fn rps() -> Machine {
+    let mut machine = Machine::new(Quirks::SCHIP);
+    machine
+        .load_rom(&Rom::from_path("roms/RPS.ch8").unwrap())
+        .unwrap();
+    machine
+}
+
+/// Loads the rock paper scissors state written by the format version, 20 frames into the game
+fn load_fixture(version: u16) -> Machine {
+    let state = fs::read(format!("tests/states/rps-v{version}.state")).unwrap();
+    assert_eq!(state[..4], MAGIC);
+    assert_eq!(u16::from_le_bytes([state[4], state[5]]), version);
+    let mut machine = rps();
+    machine.load_state(&state).unwrap();
+    machine
+}
+
+#[test]
+fn legacy_states_migrate() {
+    for version in 1..=3 {
+        let machine = load_fixture(version);
+        assert_eq!(machine.program_counter(), 0x849, "version {version}");
+        assert_eq!(machine.registers().address(), 0x304, "version {version}");
+        assert_eq!(machine.registers().data()[7..10], [0x08, 0x18, 0x20]);
+        assert_eq!(
+            machine.stack().entries(),
+            [0x793, 0x7AF],
+            "version {version}"
+        );
+
+        // Both fonts survive moving the stack out of memory
+        assert_eq!(
+            machine.memory().data()[..0x200],
+            rps().memory().data()[..0x200],
+            "version {version}"
+        );
+    }
+}
+
+#[test]
+fn legacy_states_match_current_format() {
+    // Versions 2 and 3 were written with the same seed, so only their format differs
+    let (v2, v3) = (load_fixture(2), load_fixture(3));
+    assert!(v2 == v3);
+
+    let mut reloaded = rps();
+    reloaded.load_state(&v3.save_state()).unwrap();
+    assert!(reloaded == v3);
+    assert_eq!(
+        u16::from_le_bytes([v3.save_state()[4], v3.save_state()[5]]),
+        VERSION
+    );
+}
+
+#[test]
+fn migrated_states_keep_running() {
+    for version in 1..=3 {
+        let mut machine = load_fixture(version);
+        for _ in 0..600 {
+            machine.run_frame(10).unwrap();
+        }
+    }
+}
+
+#[test]
+fn invalid_states_are_rejected() {
+    let state = rps().save_state();
+    let mut machine = rps();
+    let unchanged = machine.clone();
+
+    assert_eq!(machine.load_state(b"PNG!"), Err(StateError::InvalidMagic));
+    let mut newer = state.clone();
+    newer[4..6].copy_from_slice(&(VERSION + 1).to_le_bytes());
+    assert_eq!(
+        machine.load_state(&newer),
+        Err(StateError::UnsupportedVersion(VERSION + 1))
+    );
+    assert_eq!(
+        machine.load_state(&state[..state.len() - 1]),
+        Err(StateError::Truncated)
+    );
+    assert_eq!(
+        machine.load_state(&[state.as_slice(), &[0]].concat()),
+        Err(StateError::TrailingData)
+    );
+
+    let mut other = Machine::new(Quirks::SCHIP);
+    other.load_program(&[0x12, 0x00]).unwrap();
+    assert!(matches!(
+        other.load_state(&state),
+        Err(StateError::RomMismatch { .. })
+    ));
+
+    // A legacy stack pointer inside the font, it follows the header and 4 KiB of memory
+    let mut legacy = fs::read("tests/states/rps-v1.state").unwrap();
+    let stack_pointer = 10 + 0x1000;
+    legacy[stack_pointer..stack_pointer + 2].copy_from_slice(&10u16.to_le_bytes());
+    assert_eq!(
+        machine.load_state(&legacy),
+        Err(StateError::InvalidField("stack pointer"))
+    );
+    assert!(machine == unchanged);
+}